@@ -14,7 +14,6 @@ use jsonschema::Draft;
 use pyo3::{
     create_exception, exceptions, prelude::*, types::PyAny, wrap_pyfunction, PyObjectProtocol,
 };
-use serde_json::Value;
 
 mod ser;
 mod string;
@@ -144,8 +143,7 @@ fn validate(
 #[pyclass]
 #[text_signature = "(schema, draft=None, with_meta_schemas=False)"]
 struct JSONSchema {
-    schema: jsonschema::JSONSchema<'static>,
-    raw_schema: &'static Value,
+    schema: jsonschema::OwnedJSONSchema,
 }
 
 #[pymethods]
@@ -153,15 +151,11 @@ impl JSONSchema {
     #[new]
     fn new(schema: &PyAny, draft: Option<u8>, with_meta_schemas: Option<bool>) -> PyResult<Self> {
         let options = make_options(draft, with_meta_schemas)?;
-        // Currently, it is the simplest way to pass a reference to `JSONSchema`
-        // It is cleaned up in the `Drop` implementation
         let raw_schema = ser::to_value(schema)?;
-        let schema: &'static Value = Box::leak(Box::new(raw_schema));
         Ok(JSONSchema {
             schema: options
-                .compile(schema)
+                .compile_owned(raw_schema)
                 .map_err(JSONSchemaError::Compilation)?,
-            raw_schema: schema,
         })
     }
 
@@ -201,7 +195,7 @@ const SCHEMA_LENGTH_LIMIT: usize = 32;
 #[pyproto]
 impl<'p> PyObjectProtocol<'p> for JSONSchema {
     fn __repr__(&self) -> PyResult<String> {
-        let mut schema = self.raw_schema.to_string();
+        let mut schema = self.schema.schema().to_string();
         if schema.len() > SCHEMA_LENGTH_LIMIT {
             schema.truncate(SCHEMA_LENGTH_LIMIT);
             schema = format!("{}...}}", schema);
@@ -210,13 +204,6 @@ impl<'p> PyObjectProtocol<'p> for JSONSchema {
     }
 }
 
-impl Drop for JSONSchema {
-    fn drop(&mut self) {
-        // Since `self.raw_schema` is not used anywhere else, there should be no double-free
-        unsafe { Box::from_raw(self.raw_schema as *const _ as *mut Value) };
-    }
-}
-
 #[allow(dead_code)]
 mod build {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
@@ -0,0 +1,424 @@
+//! Persistent cache for documents loaded via HTTP(S).
+//! Cached documents survive process restarts and are available when the network is not.
+//!
+//! It decides when to make requests and how to handle their responses, while the requests
+//! themselves are made by the blocking resolver and by the async compilation.
+use crate::resolver::SchemaResolverError;
+use reqwest::{
+    header::{HeaderMap, HeaderName, ETAG, LAST_MODIFIED},
+    StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use url::Url;
+
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Makes names of temporary files unique within the process.
+static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+/// A directory with documents loaded via HTTP(S).
+///
+/// It is set via `CompilationOptions::with_cache` and is consulted before making a request.
+/// Documents are fresh within their time to live, afterwards they are revalidated with their
+/// `ETag` and `Last-Modified` headers, if the server provided them. If the network is not
+/// available, expired documents are used as they are.
+///
+/// Writing to the cache is best-effort, failures to do so don't affect validation.
+///
+/// ```rust
+/// # use jsonschema::{DiskCache, JSONSchema};
+/// # use std::time::Duration;
+/// # let schema = serde_json::json!({});
+/// let cache = DiskCache::new(std::env::temp_dir().join("schemas"))
+///     .with_ttl(Duration::from_secs(60 * 60));
+/// let compiled = JSONSchema::options()
+///     .with_cache(cache)
+///     .compile(&schema);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    directory: PathBuf,
+    ttl: Duration,
+    offline: bool,
+}
+
+impl DiskCache {
+    /// Create a cache that stores documents in `directory`. The directory is created on the
+    /// first write. Documents are fresh for 24 hours.
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        DiskCache {
+            directory: directory.into(),
+            ttl: DEFAULT_TTL,
+            offline: false,
+        }
+    }
+
+    /// Set for how long cached documents are used without revalidating them.
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Serve documents only from the cache, regardless of their age, and never make requests.
+    /// References to documents that are not cached can't be resolved.
+    #[must_use]
+    pub const fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Find a cached document for `url` or prepare a request for it.
+    /// It fails if the document is not cached and the cache is offline.
+    pub(crate) fn lookup<'a>(&'a self, url: &'a Url) -> Result<Lookup<'a>, SchemaResolverError> {
+        let stale = match self.read(url) {
+            Some(entry) if self.offline || entry.age() < self.ttl => {
+                return Ok(Lookup::Fresh(entry.document))
+            }
+            Some(entry) => Some(entry),
+            None if self.offline => return Err("The document is not cached".into()),
+            None => None,
+        };
+        Ok(Lookup::Request(Revalidation {
+            cache: self,
+            url,
+            stale,
+        }))
+    }
+
+    /// Store a document that was just loaded.
+    pub(crate) fn save(
+        &self,
+        url: &Url,
+        document: &Value,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) {
+        self.write(&CacheEntry {
+            url: url.to_string(),
+            fetched_at: now(),
+            etag: etag.map(str::to_string),
+            last_modified: last_modified.map(str::to_string),
+            document: document.clone(),
+        })
+    }
+
+    /// Mark a stale document as fresh after the server confirmed it is not modified.
+    fn revalidate(&self, mut entry: CacheEntry) -> Value {
+        entry.fetched_at = now();
+        self.write(&entry);
+        entry.document
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        // Entries contain their URLs, therefore hash collisions lead to cache misses
+        // and not to wrong documents
+        self.directory.join(format!("{:016x}.json", fnv1a(url)))
+    }
+
+    fn read(&self, url: &Url) -> Option<CacheEntry> {
+        let file = File::open(self.path(url.as_str())).ok()?;
+        let entry: CacheEntry = serde_json::from_reader(BufReader::new(file)).ok()?;
+        if entry.url == url.as_str() {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    fn write(&self, entry: &CacheEntry) {
+        let path = self.path(&entry.url);
+        // Concurrent readers should never see partially written entries
+        let temporary = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result = fs::create_dir_all(&self.directory)
+            .and_then(|_| write_entry(&temporary, entry))
+            .and_then(|_| fs::rename(&temporary, &path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+    }
+}
+
+fn write_entry(path: &Path, entry: &CacheEntry) -> std::io::Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer(BufWriter::new(file), entry)?;
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// The result of looking up a document in the cache.
+#[derive(Debug)]
+pub(crate) enum Lookup<'a> {
+    /// The document can be used as it is.
+    Fresh(Value),
+    /// The document should be requested.
+    Request(Revalidation<'a>),
+}
+
+/// A request for a document that is not cached or should be revalidated.
+/// It is made with `headers` and its response is passed to `finish`.
+#[derive(Debug)]
+pub(crate) struct Revalidation<'a> {
+    cache: &'a DiskCache,
+    url: &'a Url,
+    // Can be used if the server is not available
+    stale: Option<CacheEntry>,
+}
+
+impl Revalidation<'_> {
+    /// Headers that make the request conditional on the document being modified.
+    pub(crate) fn headers(&self) -> Vec<(&'static str, &str)> {
+        let mut headers = Vec::with_capacity(2);
+        if let Some(entry) = &self.stale {
+            if let Some(etag) = &entry.etag {
+                headers.push(("If-None-Match", etag.as_str()));
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                headers.push(("If-Modified-Since", last_modified.as_str()));
+            }
+        }
+        headers
+    }
+
+    /// Get the document from the response and update the cache.
+    pub(crate) fn finish(
+        self,
+        response: Result<Response, SchemaResolverError>,
+    ) -> Result<Value, SchemaResolverError> {
+        match (response, self.stale) {
+            (Ok(Response::NotModified), Some(entry)) => Ok(self.cache.revalidate(entry)),
+            (Ok(Response::NotModified), None) => Err("Unexpected '304 Not Modified'".into()),
+            (
+                Ok(Response::Modified {
+                    document,
+                    etag,
+                    last_modified,
+                }),
+                _,
+            ) => {
+                self.cache.save(
+                    self.url,
+                    &document,
+                    etag.as_deref(),
+                    last_modified.as_deref(),
+                );
+                Ok(document)
+            }
+            // Expired documents are better than none if the server is not available
+            (Err(_), Some(entry)) => Ok(entry.document),
+            (Err(error), None) => Err(error),
+        }
+    }
+}
+
+/// A successful response to a `Revalidation` request.
+#[derive(Debug)]
+pub(crate) enum Response {
+    NotModified,
+    Modified {
+        document: Value,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+impl Response {
+    /// Whether the document should be read from the response with this status.
+    pub(crate) fn is_modified(status: StatusCode) -> bool {
+        status != StatusCode::NOT_MODIFIED
+    }
+
+    /// The document with validation headers of its response.
+    pub(crate) fn modified(document: Value, headers: &HeaderMap) -> Self {
+        Response::Modified {
+            document,
+            etag: header(headers, ETAG),
+            last_modified: header(headers, LAST_MODIFIED),
+        }
+    }
+}
+
+/// 64-bit FNV-1a hash. Unlike `DefaultHasher`, it is the same across Rust versions and platforms,
+/// therefore cache directories can be shared and survive toolchain upgrades.
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn header(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    /// Seconds since the Unix epoch.
+    fetched_at: u64,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    document: Value,
+}
+
+impl CacheEntry {
+    fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.fetched_at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fnv1a, DiskCache};
+    use crate::{error::ValidationErrorKind, JSONSchema};
+    use serde_json::{json, Value};
+    use std::{fs, path::PathBuf, time::Duration};
+    use url::Url;
+
+    fn cache_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("jsonschema-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    fn remote(path: &str) -> Url {
+        Url::parse(&format!("http://{}{}", mockito::server_address(), path)).unwrap()
+    }
+
+    fn is_valid(cache: &DiskCache, url: &Url, instance: &Value) -> bool {
+        let schema = json!({"$ref": url.as_str()});
+        JSONSchema::options()
+            .with_cache(cache.clone())
+            .compile(&schema)
+            .unwrap()
+            .is_valid(instance)
+    }
+
+    #[test]
+    fn file_names_are_stable() {
+        // Reference values of 64-bit FNV-1a
+        assert_eq!(fnv1a(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a("a"), 0xaf63_dc4c_8601_ec8c);
+        let cache = DiskCache::new("cache");
+        assert_eq!(
+            cache.path("https://example.com/schema.json"),
+            PathBuf::from("cache").join("737ee54577542161.json")
+        );
+    }
+
+    #[test]
+    fn documents_are_cached() {
+        let url = remote("/cache/cached.json");
+        let mock = mockito::mock("GET", url.path())
+            .with_body(r#"{"type": "integer"}"#)
+            .expect(1)
+            .create();
+        let cache = DiskCache::new(cache_directory("cached"));
+        // Every compiled schema loads documents on its own, the second one reads the cache
+        assert!(is_valid(&cache, &url, &json!(1)));
+        assert!(!is_valid(&cache, &url, &json!("1")));
+        mock.assert();
+    }
+
+    #[test]
+    fn expired_documents_are_revalidated() {
+        let url = remote("/cache/revalidated.json");
+        let cache = DiskCache::new(cache_directory("revalidated")).with_ttl(Duration::from_secs(0));
+        let modified = mockito::mock("GET", url.path())
+            .with_header("ETag", "\"v1\"")
+            .with_body(r#"{"type": "integer"}"#)
+            .expect(1)
+            .create();
+        assert!(is_valid(&cache, &url, &json!(1)));
+        modified.assert();
+        drop(modified);
+        let not_modified = mockito::mock("GET", url.path())
+            .match_header("If-None-Match", "\"v1\"")
+            .with_status(304)
+            .expect(1)
+            .create();
+        assert!(!is_valid(&cache, &url, &json!("1")));
+        not_modified.assert();
+    }
+
+    #[test]
+    fn expired_documents_are_used_if_server_fails() {
+        let url = remote("/cache/unavailable.json");
+        let cache = DiskCache::new(cache_directory("unavailable")).with_ttl(Duration::from_secs(0));
+        cache.save(&url, &json!({"type": "integer"}), None, None);
+        let mock = mockito::mock("GET", url.path())
+            .with_status(500)
+            .expect(1)
+            .create();
+        assert!(!is_valid(&cache, &url, &json!("1")));
+        mock.assert();
+    }
+
+    #[test]
+    fn offline() {
+        let url = remote("/cache/offline.json");
+        let mock = mockito::mock("GET", url.path())
+            .with_body(r#"{"type": "integer"}"#)
+            .expect(0)
+            .create();
+        let cache = DiskCache::new(cache_directory("offline")).offline(true);
+        let schema = json!({"$ref": url.as_str()});
+        let compiled = JSONSchema::options()
+            .with_cache(cache.clone())
+            .compile(&schema)
+            .unwrap();
+        let instance = json!(1);
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0].kind,
+            ValidationErrorKind::Resolver { .. }
+        ));
+        // Cached documents are used regardless of their age
+        cache.save(&url, &json!({"type": "integer"}), None, None);
+        let cache = cache.with_ttl(Duration::from_secs(0));
+        assert!(is_valid(&cache, &url, &json!(1)));
+        assert!(!is_valid(&cache, &url, &json!("1")));
+        mock.assert();
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn compile_async() {
+        let url = remote("/cache/async.json");
+        let mock = mockito::mock("GET", url.path())
+            .with_body(r#"{"type": "integer"}"#)
+            .expect(1)
+            .create();
+        let cache = DiskCache::new(cache_directory("async"));
+        let schema = json!({"$ref": url.as_str()});
+        for _ in 0..2 {
+            let compiled = JSONSchema::options()
+                .with_cache(cache.clone())
+                .compile_async(&schema)
+                .await
+                .unwrap();
+            assert!(compiled.is_valid(&json!(1)));
+        }
+        mock.assert();
+    }
+}
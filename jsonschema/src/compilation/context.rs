@@ -1,5 +1,10 @@
 use super::options::CompilationOptions;
-use crate::schemas;
+use crate::{
+    error::CompilationError,
+    keywords::ref_::ReferenceCollector,
+    paths::{JSONPointer, PathChunk},
+    schemas::{self, Draft},
+};
 use serde_json::Value;
 use std::borrow::Cow;
 use url::{ParseError, Url};
@@ -9,13 +14,48 @@ use url::{ParseError, Url};
 pub(crate) struct CompilationContext<'a> {
     pub(crate) scope: Cow<'a, Url>,
     pub(crate) config: Cow<'a, CompilationOptions>,
+    /// Location of the current subschema. It is relative to the schema being compiled,
+    /// i.e. the root schema or the target of a `$ref`.
+    pub(crate) schema_path: JSONPointer,
+    /// The outermost schema resource with `"$recursiveAnchor": true` in the current dynamic
+    /// scope. It is the target of `$recursiveRef` in Draft 2019-09.
+    pub(crate) recursive_anchor: Option<Cow<'a, Url>>,
+    /// Schema resources with dynamic anchors entered so far, the outermost first. The targets of
+    /// `$dynamicRef` are looked up in them in Draft 2020-12. Other resources and repeated entries
+    /// can't change the targets, therefore they are skipped and the scope is bounded.
+    pub(crate) dynamic_scope: Cow<'a, [Url]>,
+    /// Collects references to resolve them right after compilation.
+    pub(crate) references: Option<&'a ReferenceCollector>,
 }
 
 impl<'a> CompilationContext<'a> {
-    pub(crate) fn new(scope: Url, config: Cow<'a, CompilationOptions>) -> Self {
+    /// `resource` is the root of the schema resource identified by `scope`.
+    pub(crate) fn new(scope: Url, config: Cow<'a, CompilationOptions>, resource: &Value) -> Self {
+        let dynamic_scope = if schemas::has_dynamic_anchors(config.draft(), resource) {
+            vec![resource_of(&scope)]
+        } else {
+            vec![]
+        };
         CompilationContext {
             scope: Cow::Owned(scope),
             config,
+            schema_path: JSONPointer::default(),
+            recursive_anchor: None,
+            dynamic_scope: Cow::Owned(dynamic_scope),
+            references: None,
+        }
+    }
+
+    /// Collect all references found during compilation into `references`.
+    #[inline]
+    pub(crate) fn with_references(&'a self, references: &'a ReferenceCollector) -> Self {
+        CompilationContext {
+            scope: Cow::Borrowed(self.scope.as_ref()),
+            config: Cow::Borrowed(&self.config),
+            schema_path: self.schema_path.clone(),
+            recursive_anchor: self.recursive_anchor.as_deref().map(Cow::Borrowed),
+            dynamic_scope: Cow::Borrowed(self.dynamic_scope.as_ref()),
+            references: Some(references),
         }
     }
 
@@ -30,23 +70,102 @@ impl<'a> CompilationContext<'a> {
     ///
     /// In other words it keeps track of sub-folders during compilation.
     #[inline]
-    pub(crate) fn push(&'a self, schema: &Value) -> Result<Self, ParseError> {
-        if let Some(id) = schemas::id_of(self.config.draft(), schema) {
-            let scope = Url::options().base_url(Some(&self.scope)).parse(id)?;
+    pub(crate) fn push(&'a self, schema: &Value) -> Result<Self, CompilationError> {
+        let draft = self.config.draft();
+        if let Some(id) = schemas::id_of(draft, schema) {
+            let scope = Url::options()
+                .base_url(Some(&self.scope))
+                .parse(id)
+                .map_err(|error| {
+                    let keyword = if draft == Draft::Draft4 { "id" } else { "$id" };
+                    CompilationError::invalid_url(
+                        self.as_pointer_with(keyword),
+                        &Value::String(id.to_string()),
+                        error,
+                    )
+                })?;
             Ok(CompilationContext {
+                recursive_anchor: self.push_recursive_anchor(schema, &scope),
+                dynamic_scope: self.push_dynamic_scope(schema, &scope),
                 scope: Cow::Owned(scope),
                 config: Cow::Borrowed(&self.config),
+                schema_path: self.schema_path.clone(),
+                references: self.references,
             })
         } else {
             Ok(CompilationContext {
                 scope: Cow::Borrowed(self.scope.as_ref()),
                 config: Cow::Borrowed(&self.config),
+                schema_path: self.schema_path.clone(),
+                recursive_anchor: self.push_recursive_anchor(schema, &self.scope),
+                dynamic_scope: Cow::Borrowed(self.dynamic_scope.as_ref()),
+                references: self.references,
             })
         }
     }
 
+    /// Only the outermost schema with `"$recursiveAnchor": true` is relevant.
+    fn push_recursive_anchor(&'a self, schema: &Value, scope: &Url) -> Option<Cow<'a, Url>> {
+        match &self.recursive_anchor {
+            Some(anchor) => Some(Cow::Borrowed(anchor.as_ref())),
+            None if self.config.draft() == Draft::Draft201909
+                && schema.get("$recursiveAnchor") == Some(&Value::Bool(true)) =>
+            {
+                Some(Cow::Owned(scope.clone()))
+            }
+            None => None,
+        }
+    }
+
+    /// A schema with `$id` is a new schema resource in the dynamic scope.
+    fn push_dynamic_scope(&'a self, schema: &Value, scope: &Url) -> Cow<'a, [Url]> {
+        let resource = resource_of(scope);
+        if !self.dynamic_scope.contains(&resource)
+            && schemas::has_dynamic_anchors(self.config.draft(), schema)
+        {
+            let mut dynamic_scope = self.dynamic_scope.to_vec();
+            dynamic_scope.push(resource);
+            Cow::Owned(dynamic_scope)
+        } else {
+            Cow::Borrowed(self.dynamic_scope.as_ref())
+        }
+    }
+
+    /// Descend into a subschema located at `chunk` relative to the current location.
+    /// For example, `context.with_path("properties").with_path("foo")`.
+    #[inline]
+    pub(crate) fn with_path(&'a self, chunk: impl Into<PathChunk>) -> Self {
+        CompilationContext {
+            scope: Cow::Borrowed(self.scope.as_ref()),
+            config: Cow::Borrowed(&self.config),
+            schema_path: self.schema_path.clone_with(chunk),
+            recursive_anchor: self.recursive_anchor.as_deref().map(Cow::Borrowed),
+            dynamic_scope: Cow::Borrowed(self.dynamic_scope.as_ref()),
+            references: self.references,
+        }
+    }
+
+    /// Location of the current subschema.
+    #[inline]
+    pub(crate) fn into_pointer(self) -> JSONPointer {
+        self.schema_path
+    }
+
+    /// Location of a keyword with the given name within the current subschema.
+    #[inline]
+    pub(crate) fn as_pointer_with(&self, chunk: impl Into<PathChunk>) -> JSONPointer {
+        self.schema_path.clone_with(chunk)
+    }
+
     /// Build a new URL. Used for `ref` compilation to keep their full paths.
     pub(crate) fn build_url(&self, reference: &str) -> Result<Url, ParseError> {
         Url::options().base_url(Some(&self.scope)).parse(reference)
     }
 }
+
+/// Schema resource URL without a fragment.
+pub(crate) fn resource_of(scope: &Url) -> Url {
+    let mut resource = scope.clone();
+    resource.set_fragment(None);
+    resource
+}
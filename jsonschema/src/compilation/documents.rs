@@ -0,0 +1,363 @@
+//! Loading documents into the store from the file system.
+use super::options::path_to_url;
+use crate::{
+    error::{DocumentError, DocumentErrorKind, LoadError},
+    schemas,
+};
+use ahash::AHashMap;
+use serde_json::Value;
+use std::{
+    collections::hash_map::Entry,
+    ffi::OsStr,
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use url::Url;
+
+/// Documents that are already in the store. Loaded documents must not replace them.
+pub(crate) struct Known<'a> {
+    pub(crate) store: &'a AHashMap<String, Arc<Value>>,
+    /// Files of the documents loaded before.
+    pub(crate) locations: &'a AHashMap<String, PathBuf>,
+}
+
+/// A document with its `$id` and the file it is loaded from.
+pub(crate) type Document = (String, PathBuf, Value);
+
+/// Read all `.json` files in `directory` and its subdirectories and index them by `$id`.
+pub(crate) fn load_directory(
+    directory: &Path,
+    draft: Option<schemas::Draft>,
+    known: &Known<'_>,
+) -> Result<Vec<Document>, LoadError> {
+    let mut paths = Vec::new();
+    let mut errors = Vec::new();
+    collect_files(directory, &mut paths, &mut errors);
+    index(paths, draft, known, errors)
+}
+
+/// Read all documents listed in a SchemaStore-style catalog and index them by `$id`.
+/// Catalog entries are objects with the `url` property, which is either a `file://` URL or
+/// a path relative to the catalog.
+pub(crate) fn load_catalog(
+    catalog: &Path,
+    draft: Option<schemas::Draft>,
+    known: &Known<'_>,
+) -> Result<Vec<Document>, LoadError> {
+    let invalid = |message: String| {
+        DocumentError::new(
+            catalog.to_path_buf(),
+            DocumentErrorKind::InvalidCatalog { message },
+        )
+    };
+    let document = read(catalog).map_err(|error| LoadError {
+        errors: vec![error],
+    })?;
+    let entries = document
+        .get("schemas")
+        .and_then(Value::as_array)
+        .ok_or_else(|| LoadError {
+            errors: vec![invalid(
+                "expected an object with the 'schemas' array".to_string(),
+            )],
+        })?;
+    let base = catalog.parent().unwrap_or_else(|| Path::new(""));
+    let mut paths = Vec::with_capacity(entries.len());
+    let mut errors = Vec::new();
+    for entry in entries {
+        match entry.get("url").and_then(Value::as_str) {
+            Some(location) => match Url::parse(location) {
+                Ok(url) if url.scheme() == "file" => match url.to_file_path() {
+                    Ok(path) => paths.push(path),
+                    Err(_) => errors.push(invalid(format!("invalid file URL '{}'", location))),
+                },
+                Ok(_) => errors.push(invalid(format!("'{}' is not a local file", location))),
+                // Relative paths are not valid URLs
+                Err(_) => paths.push(base.join(location)),
+            },
+            None => errors.push(invalid(format!("{} has no 'url'", entry))),
+        }
+    }
+    index(paths, draft, known, errors)
+}
+
+fn collect_files(directory: &Path, paths: &mut Vec<PathBuf>, errors: &mut Vec<DocumentError>) {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) => {
+            return errors.push(DocumentError::new(
+                directory.to_path_buf(),
+                DocumentErrorKind::FileNotFound { error },
+            ))
+        }
+    };
+    // Sorted, so duplicate ids are reported in a stable way
+    let mut entries: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect_files(&path, paths, errors)
+        } else if path.extension() == Some(OsStr::new("json")) {
+            paths.push(path)
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<Value, DocumentError> {
+    let file = File::open(path).map_err(|error| {
+        DocumentError::new(
+            path.to_path_buf(),
+            DocumentErrorKind::FileNotFound { error },
+        )
+    })?;
+    serde_json::from_reader(BufReader::new(file)).map_err(|error| {
+        DocumentError::new(path.to_path_buf(), DocumentErrorKind::JSONParse { error })
+    })
+}
+
+/// Read documents and pair them with their `$id`. Relative ids are resolved against the
+/// documents' files. Problems with all documents are reported together with the ones found before.
+fn index(
+    paths: Vec<PathBuf>,
+    draft: Option<schemas::Draft>,
+    known: &Known<'_>,
+    mut errors: Vec<DocumentError>,
+) -> Result<Vec<Document>, LoadError> {
+    let mut documents = Vec::with_capacity(paths.len());
+    let mut locations: AHashMap<String, PathBuf> = AHashMap::with_capacity(paths.len());
+    for path in paths {
+        let document = match read(&path) {
+            Ok(document) => document,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        let draft = draft
+            .or_else(|| schemas::draft_from_schema(&document))
+            .unwrap_or_default();
+        let id = match schemas::id_of(draft, &document).map(|id| (id, parse_id(id, &path))) {
+            Some((_, Ok(mut url))) => {
+                // Documents are identified without fragments, e.g. an empty one in `schema.json#`
+                url.set_fragment(None);
+                url.to_string()
+            }
+            Some((id, Err(error))) => {
+                let id = id.to_string();
+                errors.push(DocumentError::new(
+                    path,
+                    DocumentErrorKind::InvalidId { id, error },
+                ));
+                continue;
+            }
+            None => {
+                errors.push(DocumentError::new(path, DocumentErrorKind::MissingId));
+                continue;
+            }
+        };
+        if known.store.contains_key(&id) {
+            let other = known.locations.get(&id).cloned();
+            errors.push(DocumentError::new(
+                path,
+                DocumentErrorKind::DuplicateId { id, other },
+            ));
+            continue;
+        }
+        match locations.entry(id) {
+            Entry::Occupied(entry) => errors.push(DocumentError::new(
+                path,
+                DocumentErrorKind::DuplicateId {
+                    id: entry.key().clone(),
+                    other: Some(entry.get().clone()),
+                },
+            )),
+            Entry::Vacant(entry) => {
+                documents.push((entry.key().clone(), path.clone(), document));
+                entry.insert(path);
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(documents)
+    } else {
+        Err(LoadError { errors })
+    }
+}
+
+fn parse_id(id: &str, path: &Path) -> Result<Url, url::ParseError> {
+    match Url::parse(id) {
+        Err(url::ParseError::RelativeUrlWithoutBase) => path_to_url(path)?.join(id),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::DocumentErrorKind, JSONSchema};
+    use serde_json::{json, Value};
+    use std::path::{Path, PathBuf};
+    use test_case::test_case;
+
+    fn schemas_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/schemas")
+    }
+
+    #[test_case(&json!({"customer": {"name": "Alice"}, "items": [{"price": 5}]}), true)]
+    #[test_case(&json!({"customer": {}}), false)]
+    #[test_case(&json!({"customer": {"name": "Alice"}, "items": [{"price": -5}]}), false)]
+    fn directory(instance: &Value, expected: bool) {
+        let schema = json!({"$ref": "https://example.com/order.json"});
+        let compiled = JSONSchema::options()
+            .with_directory(schemas_dir().join("store"))
+            .unwrap()
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+    }
+
+    #[test_case(&json!({"customer": {"name": "Alice"}, "items": [{"price": 5}]}), true)]
+    #[test_case(&json!({"customer": {"name": "Alice"}, "items": [{"price": -5}]}), false)]
+    fn catalog(instance: &Value, expected: bool) {
+        let schema = json!({"$ref": "https://example.com/order.json"});
+        let compiled = JSONSchema::options()
+            .with_catalog(schemas_dir().join("catalog.json"))
+            .unwrap()
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+    }
+
+    #[test]
+    fn directory_errors() {
+        let directory = schemas_dir().join("store-invalid");
+        let error = JSONSchema::options()
+            .with_directory(&directory)
+            .expect_err("Documents are invalid");
+        assert_eq!(error.errors.len(), 4);
+        assert!(matches!(
+            &error.errors[0].kind,
+            DocumentErrorKind::DuplicateId { id, other: Some(other) }
+                if id == "https://example.com/a.json" && other == &directory.join("a.json")
+        ));
+        assert_eq!(error.errors[0].path, directory.join("b.json"));
+        assert!(matches!(
+            error.errors[1].kind,
+            DocumentErrorKind::JSONParse { .. }
+        ));
+        assert!(matches!(
+            error.errors[2].kind,
+            DocumentErrorKind::InvalidId { .. }
+        ));
+        assert!(matches!(error.errors[3].kind, DocumentErrorKind::MissingId));
+        assert_eq!(error.errors[3].path, directory.join("missing-id.json"));
+        assert_eq!(
+            error.errors[3].to_string(),
+            format!(
+                "'{}': the schema has no '$id'",
+                directory.join("missing-id.json").display()
+            )
+        );
+    }
+
+    #[test_case(&json!({"address": {"city": "Prague"}}), true)]
+    #[test_case(&json!({"address": {}}), false)]
+    fn relative_ids(instance: &Value, expected: bool) {
+        let directory = schemas_dir().join("relative-ids");
+        let url = url::Url::from_directory_path(&directory).unwrap();
+        let schema = json!({"$ref": url.join("person.json").unwrap().as_str()});
+        let compiled = JSONSchema::options()
+            .with_directory(&directory)
+            .unwrap()
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+    }
+
+    #[test]
+    fn duplicates_of_loaded_documents() {
+        let directory = schemas_dir().join("store");
+        let error = JSONSchema::options()
+            .with_directory(&directory)
+            .unwrap()
+            .with_catalog(schemas_dir().join("catalog.json"))
+            .expect_err("Documents are already loaded");
+        assert_eq!(error.errors.len(), 3);
+        assert!(matches!(
+            &error.errors[0].kind,
+            DocumentErrorKind::DuplicateId { id, other: Some(other) }
+                if id == "https://example.com/order.json" && other == &directory.join("order.json")
+        ));
+    }
+
+    #[test]
+    fn duplicates_of_stored_documents() {
+        let error = JSONSchema::options()
+            .with_document("https://example.com/order.json".to_string(), json!({}))
+            .with_directory(schemas_dir().join("store"))
+            .expect_err("The document is already in the store");
+        assert_eq!(error.errors.len(), 1);
+        assert!(matches!(
+            &error.errors[0].kind,
+            DocumentErrorKind::DuplicateId { id, other: None } if id == "https://example.com/order.json"
+        ));
+        assert_eq!(
+            error.errors[0].to_string(),
+            format!(
+                "'{}': '$id' 'https://example.com/order.json' is already used by another document",
+                schemas_dir().join("store/order.json").display()
+            )
+        );
+    }
+
+    #[test]
+    fn duplicates_of_meta_schemas() {
+        let error = JSONSchema::options()
+            .with_meta_schemas()
+            .with_directory(schemas_dir().join("meta-schema"))
+            .expect_err("The meta-schema is already in the store");
+        assert!(matches!(
+            &error.errors[0].kind,
+            DocumentErrorKind::DuplicateId { id, other: None } if id == "http://json-schema.org/draft-07/schema"
+        ));
+    }
+
+    #[test]
+    fn missing_directory() {
+        let error = JSONSchema::options()
+            .with_directory(schemas_dir().join("unknown"))
+            .expect_err("The directory doesn't exist");
+        assert_eq!(error.errors.len(), 1);
+        assert!(matches!(
+            error.errors[0].kind,
+            DocumentErrorKind::FileNotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn catalog_errors() {
+        let catalog = schemas_dir().join("catalog-invalid.json");
+        let error = JSONSchema::options()
+            .with_catalog(&catalog)
+            .expect_err("The catalog is invalid");
+        assert_eq!(error.errors.len(), 2);
+        for error in &error.errors {
+            assert_eq!(error.path, catalog);
+            assert!(matches!(
+                error.kind,
+                DocumentErrorKind::InvalidCatalog { .. }
+            ));
+        }
+        assert_eq!(
+            error.errors[0].to_string(),
+            format!(
+                "'{}': invalid catalog: 'https://example.com/remote.json' is not a local file",
+                catalog.display()
+            )
+        );
+    }
+}
@@ -2,17 +2,22 @@
 //! The main idea is to compile the input JSON Schema to a validators tree that will contain
 //! everything needed to perform such validation in runtime.
 pub(crate) mod context;
+mod documents;
 pub(crate) mod options;
 
 use crate::{
     error::{CompilationError, ErrorIterator},
     keywords,
-    keywords::Validators,
-    resolver::Resolver,
+    keywords::{ref_::RefTargets, Validators},
+    output::Output,
+    paths::{InstancePath, JSONPointer, PathChunk},
+    resolver::{Resolved, Resolver},
+    validator::{annotate_subschema, coerce, Annotations, MissingProperty},
 };
 use context::CompilationContext;
 use options::CompilationOptions;
 use serde_json::Value;
+use std::{ops::Deref, sync::Arc};
 
 use url::Url;
 
@@ -21,10 +26,11 @@ pub(crate) const DEFAULT_ROOT_URL: &str = "json-schema:///";
 /// The structure that holds a JSON Schema compiled into a validation tree
 #[derive(Debug)]
 pub struct JSONSchema<'a> {
-    pub(crate) schema: &'a Value,
+    pub(crate) schema: Resolved<'a>,
     pub(crate) validators: Validators,
     pub(crate) resolver: Resolver<'a>,
     pub(crate) context: CompilationContext<'a>,
+    pub(crate) references: RefTargets,
 }
 
 lazy_static::lazy_static! {
@@ -60,10 +66,16 @@ impl<'a> JSONSchema<'a> {
     /// Run validation against `instance` and return an iterator over `ValidationError` in the error case.
     #[inline]
     pub fn validate(&'a self, instance: &'a Value) -> Result<(), ErrorIterator<'a>> {
+        let instance_path = InstancePath::new();
         let mut errors = self
             .validators
             .iter()
-            .flat_map(move |validator| validator.validate(self, instance))
+            .flat_map(move |validator| validator.validate(self, instance, &instance_path))
+            .map(move |mut error| {
+                // Errors that didn't pass through any reference are located in the root schema
+                error.rebase(&self.context.scope, &JSONPointer::default());
+                error
+            })
             .peekable();
         if errors.peek().is_none() {
             Ok(())
@@ -82,6 +94,163 @@ impl<'a> JSONSchema<'a> {
             .iter()
             .all(|validator| validator.is_valid(self, instance))
     }
+
+    /// Apply the schema to `instance` and return the result that can be presented in
+    /// one of the output formats defined by the JSON Schema specification.
+    #[must_use]
+    #[inline]
+    pub const fn apply(&'a self, instance: &'a Value) -> Output<'a> {
+        Output::new(self, instance)
+    }
+
+    /// Insert `default` values from `properties` subschemas into objects in `instance` where
+    /// these properties are missing.
+    ///
+    /// Defaults are taken from the same subschemas as annotations: `$ref`, `allOf`,
+    /// `dependentSchemas` and the selected `then` / `else` branch always contribute,
+    /// `anyOf` / `oneOf` branches contribute only if `instance` is valid against them, and `not`
+    /// never does.
+    ///
+    /// Existing values are never replaced and if several subschemas provide a default for the same
+    /// property, then only one of them is inserted. Inserted values get their own defaults as well,
+    /// e.g. nested properties of an inserted object. A default is not inserted into a value that is
+    /// inserted from the same default, so recursive schemas don't lead to endless nesting.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "properties": {
+    ///         "host": {"type": "string", "default": "localhost"},
+    ///         "port": {"type": "integer", "default": 8080}
+    ///     }
+    /// });
+    /// let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+    /// let mut instance = json!({"port": 443});
+    /// compiled.apply_defaults(&mut instance);
+    /// assert_eq!(instance, json!({"host": "localhost", "port": 443}));
+    /// ```
+    pub fn apply_defaults(&self, instance: &mut Value) {
+        // Locations of inserted values together with the defaults they are inserted from
+        let mut inserted: Vec<(JSONPointer, MissingProperty)> = Vec::new();
+        // Inserted values may have missing properties with defaults too, therefore defaults are
+        // collected again until there is nothing to insert
+        loop {
+            let mut annotations = Annotations::default();
+            annotate_subschema(
+                &self.validators,
+                self,
+                instance,
+                &InstancePath::new(),
+                &mut annotations,
+            );
+            let mut changed = false;
+            for missing in annotations.defaults {
+                let is_recursive = inserted.iter().any(|(location, other)| {
+                    missing
+                        .instance_path
+                        .chunks()
+                        .starts_with(location.chunks())
+                        && missing.name == other.name
+                        && missing.default == other.default
+                });
+                if is_recursive {
+                    continue;
+                }
+                if let Some(Value::Object(object)) =
+                    instance.pointer_mut(&missing.instance_path.to_string())
+                {
+                    if !object.contains_key(&missing.name) {
+                        object.insert(missing.name.clone(), missing.default.clone());
+                        let location = missing
+                            .instance_path
+                            .extend_with(&[PathChunk::from(missing.name.clone())]);
+                        inserted.push((location, missing));
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    /// Convert strings in `instance` to the types required by the `type` keyword, e.g. `"42"` to
+    /// `42` for `{"type": "integer"}`, and then validate it.
+    ///
+    /// It is useful for inputs that contain only strings, like query strings or environment
+    /// variables. Strings are converted to `integer` and `number` if they are valid numbers,
+    /// to `boolean` if they are `"true"` or `"false"`, and to `null` if they are empty.
+    /// Values that can't be converted are kept as they are and reported as `Type` errors.
+    ///
+    /// Types are taken from the same subschemas as annotations. If `instance` is not valid against
+    /// any `anyOf` / `oneOf` branch, types are taken from the first branch it is valid against
+    /// after the conversion.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"properties": {"port": {"type": "integer"}}});
+    /// let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+    /// let mut instance = json!({"port": "8080"});
+    /// assert!(compiled.validate_coerced(&mut instance).is_ok());
+    /// assert_eq!(instance, json!({"port": 8080}));
+    /// ```
+    pub fn validate_coerced(&'a self, instance: &'a mut Value) -> Result<(), ErrorIterator<'a>> {
+        let mut annotations = Annotations::default();
+        annotate_subschema(
+            &self.validators,
+            self,
+            instance,
+            &InstancePath::new(),
+            &mut annotations,
+        );
+        coerce(instance, annotations.coercions.iter());
+        self.validate(instance)
+    }
+}
+
+/// A JSON Schema compiled into a validation tree that owns its source document.
+///
+/// Unlike `JSONSchema`, it doesn't borrow the input `Value`, therefore it can be stored in
+/// structs, returned from functions and shared between threads. Clones are cheap and share
+/// the same validation tree.
+///
+/// All `JSONSchema` methods are available via `Deref`.
+#[derive(Debug, Clone)]
+pub struct OwnedJSONSchema {
+    inner: Arc<JSONSchema<'static>>,
+}
+
+impl OwnedJSONSchema {
+    pub(crate) fn new(inner: JSONSchema<'static>) -> Self {
+        OwnedJSONSchema {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Compile the input schema into a validation tree.
+    ///
+    /// The method is equivalent to `JSONSchema::options().compile_owned(schema)`
+    pub fn compile<S: Into<Arc<Value>>>(schema: S) -> Result<OwnedJSONSchema, CompilationError> {
+        JSONSchema::options().compile_owned(schema)
+    }
+
+    /// The source document of the compiled schema.
+    #[must_use]
+    #[inline]
+    pub fn schema(&self) -> &Value {
+        &self.inner.schema
+    }
+}
+
+impl Deref for OwnedJSONSchema {
+    type Target = JSONSchema<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
 }
 
 /// Compile JSON schema into a tree of validators.
@@ -92,39 +261,57 @@ pub(crate) fn compile_validators(
 ) -> Result<Validators, CompilationError> {
     let context = context.push(schema)?;
     match schema {
-        Value::Bool(value) => Ok(vec![
-            keywords::boolean::compile(*value).expect("Should always compile")?
-        ]),
+        Value::Bool(value) => Ok(vec![keywords::boolean::compile(
+            *value,
+            context.schema_path.clone(),
+        )
+        .expect("Should always compile")?]),
         Value::Object(object) => {
-            if let Some(reference) = object.get("$ref") {
-                if let Value::String(reference) = reference {
-                    Ok(vec![keywords::ref_::compile(schema, reference, &context)
-                        .expect("Should always return Some")?])
-                } else {
-                    Err(CompilationError::SchemaError)
-                }
+            let draft = context.config.draft();
+            if let (Some(reference), true) = (object.get("$ref"), draft.ref_overrides_siblings()) {
+                Ok(vec![keywords::ref_::compile(object, reference, &context)
+                    .expect("Should always return Some")?])
             } else {
                 let mut validators = Vec::with_capacity(object.len());
                 for (keyword, subschema) in object {
-                    if let Some(compilation_func) = context.config.draft().get_validator(keyword) {
+                    // Custom keywords take precedence over the built-in ones
+                    if let Some(factory) = context.config.keyword(keyword) {
+                        validators.push(keywords::custom::CustomKeywordValidator::compile(
+                            object, keyword, subschema, factory, &context,
+                        )?)
+                    } else if let Some(compilation_func) = draft.get_validator(keyword) {
                         if let Some(validator) = compilation_func(object, subschema, &context) {
                             validators.push(validator?)
                         }
+                    } else if context.config.collect_annotations() {
+                        if let Some(validator) =
+                            keywords::annotation::compile(draft, keyword, subschema, &context)
+                        {
+                            validators.push(validator?)
+                        }
                     }
                 }
                 Ok(validators)
             }
         }
-        _ => Err(CompilationError::SchemaError),
+        _ => Err(CompilationError::invalid_schema(
+            context.schema_path.clone(),
+            schema,
+        )),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::JSONSchema;
-    use crate::{error::ValidationError, schemas};
+    use super::{JSONSchema, OwnedJSONSchema};
+    use crate::{
+        error::{CompilationErrorKind, ValidationError, ValidationErrorKind},
+        resolver::Resolved,
+        schemas,
+    };
     use serde_json::{from_str, json, Value};
-    use std::{borrow::Cow, fs::File, io::Read, path::Path};
+    use std::{error::Error, fs::File, io::Read, path::Path};
+    use test_case::test_case;
     use url::Url;
 
     fn load(path: &str, idx: usize) -> Value {
@@ -155,9 +342,9 @@ mod tests {
         let schema = load("tests/suite/tests/draft7/ref.json", 4);
         let compiled = JSONSchema::compile(&schema).unwrap();
         let url = Url::parse("json-schema:///#/definitions/a").unwrap();
-        if let (resource, Cow::Borrowed(resolved)) = compiled
+        if let (resource, Resolved::Borrowed(resolved)) = compiled
             .resolver
-            .resolve_fragment(schemas::Draft::Draft7, &url, &schema)
+            .resolve_fragment(schemas::Draft::Draft7, &url)
             .unwrap()
         {
             assert_eq!(resource, Url::parse("json-schema:///").unwrap());
@@ -182,6 +369,72 @@ mod tests {
         assert!(compiled.is_err());
     }
 
+    #[test_case(&json!({"minItems": "3"}), Some("minItems"), "/minItems", &json!("3"))]
+    #[test_case(&json!({"properties": {"foo": {"type": 42}}}), Some("type"), "/properties/foo/type", &json!(42))]
+    #[test_case(&json!({"items": [{}, {"required": [1]}]}), Some("required"), "/items/1/required", &json!([1]))]
+    #[test_case(&json!({"$ref": 42}), Some("$ref"), "/$ref", &json!(42))]
+    #[test_case(&json!({"allOf": [{"not": 42}]}), None, "/allOf/0/not", &json!(42))]
+    fn invalid_value(schema: &Value, keyword: Option<&str>, schema_path: &str, value: &Value) {
+        let error = JSONSchema::compile(schema).expect_err("The schema is invalid");
+        assert_eq!(error.kind, CompilationErrorKind::InvalidValue);
+        assert_eq!(error.keyword.as_deref(), keyword);
+        assert_eq!(error.schema_path.to_string(), schema_path);
+        assert_eq!(error.value.as_ref(), Some(value));
+        assert!(error.source().is_none());
+    }
+
+    #[test_case(&json!({"properties": {"foo": {"pattern": "(bar"}}}), "pattern", "/properties/foo/pattern", &json!("(bar"))]
+    #[test_case(&json!({"patternProperties": {"(bar": {}}}), "patternProperties", "/patternProperties/(bar", &json!("(bar"))]
+    #[test_case(&json!({"additionalProperties": false, "patternProperties": {"(bar": {}}}), "patternProperties", "/patternProperties/(bar", &json!("(bar"))]
+    #[test_case(&json!({"properties": {"foo": {"patternProperties": {"a/(": {}}}}}), "patternProperties", "/properties/foo/patternProperties/a~1(", &json!("a/("))]
+    fn invalid_regex(schema: &Value, keyword: &str, schema_path: &str, value: &Value) {
+        let error = JSONSchema::compile(schema).expect_err("The schema is invalid");
+        assert!(matches!(
+            error.kind,
+            CompilationErrorKind::InvalidRegex { .. }
+        ));
+        assert_eq!(error.keyword.as_deref(), Some(keyword));
+        assert_eq!(error.schema_path.to_string(), schema_path);
+        assert_eq!(error.value.as_ref(), Some(value));
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn invalid_url() {
+        let schema = json!({"items": {"$ref": "http://example.com:99999/schema.json"}});
+        let error = JSONSchema::compile(&schema).expect_err("The schema is invalid");
+        assert_eq!(
+            error.kind,
+            CompilationErrorKind::InvalidURL {
+                error: url::ParseError::InvalidPort
+            }
+        );
+        assert_eq!(error.schema_path.to_string(), "/items/$ref");
+        assert_eq!(
+            error.to_string(),
+            "Schema compilation error in '$ref' at '/items/$ref': invalid URL: invalid port number"
+        );
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn unresolved_reference() {
+        let schema = json!({"items": {"$ref": "#/definitions/unknown"}});
+        let error = JSONSchema::options()
+            .should_resolve_references_eagerly(true)
+            .compile(&schema)
+            .expect_err("The reference is unresolvable");
+        let cause = error.source().expect("The cause is available");
+        assert!(cause.downcast_ref::<ValidationError>().is_some());
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Schema compilation error in '$ref' at '/items/$ref': unresolved reference '{}': {}",
+                "json-schema:///#/definitions/unknown", cause
+            )
+        );
+    }
+
     #[test]
     fn multiple_errors() {
         let schema = json!({"minProperties": 2, "propertyNames": {"minLength": 3}});
@@ -199,4 +452,268 @@ mod tests {
             r#"'"a"' is shorter than 3 characters"#
         );
     }
+
+    fn compile_owned() -> OwnedJSONSchema {
+        // The source document is dropped when the function returns
+        let schema = json!({
+            "$id": "http://example.com/root.json",
+            "properties": {
+                "node": {"$ref": "#/definitions/node"},
+                "leaf": {"$ref": "#leaf"}
+            },
+            "definitions": {
+                "node": {"type": "object", "properties": {"child": {"$ref": "#/definitions/node"}}},
+                "leaf": {"$id": "#leaf", "type": "null"}
+            }
+        });
+        OwnedJSONSchema::compile(schema).unwrap()
+    }
+
+    #[test_case(&json!({"node": {"child": {}}, "leaf": null}), true)]
+    #[test_case(&json!({"node": {"child": 1}}), false)]
+    #[test_case(&json!({"leaf": 1}), false)]
+    fn owned_schema(instance: &Value, expected: bool) {
+        let compiled = compile_owned();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn owned_schema_is_shared() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<OwnedJSONSchema>();
+
+        let compiled = compile_owned();
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let compiled = compiled.clone();
+                std::thread::spawn(move || compiled.is_valid(&json!({"node": {"child": 1}})))
+            })
+            .collect();
+        for handle in handles {
+            assert!(!handle.join().unwrap());
+        }
+        assert_eq!(
+            compiled.schema()["$id"],
+            json!("http://example.com/root.json")
+        );
+    }
+
+    #[test_case(
+        &json!({"properties": {"a": {"default": 1}, "b": {"default": 2}}}),
+        json!({"b": 3}),
+        &json!({"a": 1, "b": 3});
+        "existing values are kept"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"properties": {"b": {"default": 1}}}}}),
+        json!({"a": {}}),
+        &json!({"a": {"b": 1}});
+        "nested object"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"default": {}, "properties": {"b": {"default": 1}}}}}),
+        json!({}),
+        &json!({"a": {"b": 1}});
+        "applied to inserted values"
+    )]
+    #[test_case(
+        &json!({"items": {"properties": {"a": {"default": 1}}}}),
+        json!([{}, {"a": 2}]),
+        &json!([{"a": 1}, {"a": 2}]);
+        "array items"
+    )]
+    #[test_case(
+        &json!({
+            "allOf": [{"$ref": "#/definitions/base"}],
+            "definitions": {"base": {"properties": {"a": {"default": 1}}}}
+        }),
+        json!({}),
+        &json!({"a": 1});
+        "through ref and allOf"
+    )]
+    #[test_case(
+        &json!({
+            "if": {"properties": {"kind": {"const": "tcp"}}},
+            "then": {"properties": {"port": {"default": 80}}},
+            "else": {"properties": {"path": {"default": "/tmp"}}}
+        }),
+        json!({"kind": "tcp"}),
+        &json!({"kind": "tcp", "port": 80});
+        "then branch"
+    )]
+    #[test_case(
+        &json!({
+            "if": {"properties": {"kind": {"const": "tcp"}}},
+            "then": {"properties": {"port": {"default": 80}}},
+            "else": {"properties": {"path": {"default": "/tmp"}}}
+        }),
+        json!({"kind": "unix"}),
+        &json!({"kind": "unix", "path": "/tmp"});
+        "else branch"
+    )]
+    #[test_case(
+        &json!({
+            "anyOf": [
+                {"required": ["a"], "properties": {"x": {"default": 1}}},
+                {"required": ["b"], "properties": {"y": {"default": 2}}}
+            ]
+        }),
+        json!({"b": true}),
+        &json!({"b": true, "y": 2});
+        "only valid anyOf branches"
+    )]
+    #[test_case(
+        &json!({
+            "oneOf": [
+                {"required": ["a"], "properties": {"x": {"default": 1}}},
+                {"required": ["b"], "properties": {"y": {"default": 2}}}
+            ]
+        }),
+        json!({"a": true}),
+        &json!({"a": true, "x": 1});
+        "only valid oneOf branches"
+    )]
+    #[test_case(
+        &json!({"not": {"properties": {"a": {"default": 1}}, "required": ["b"]}}),
+        json!({}),
+        &json!({});
+        "not is ignored"
+    )]
+    #[test_case(
+        &json!({
+            "properties": {"a": {"default": 1}},
+            "allOf": [{"properties": {"a": {"default": 2}}}]
+        }),
+        json!({}),
+        &json!({"a": 2});
+        "one default per property"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"default": 1}}, "additionalProperties": false}),
+        json!({}),
+        &json!({"a": 1});
+        "with additionalProperties"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"default": 1}}}),
+        json!([]),
+        &json!([]);
+        "not an object"
+    )]
+    #[test_case(
+        &json!({
+            "properties": {"server": {"$ref": "#/definitions/server", "default": {"tls": {}}}},
+            "definitions": {
+                "server": {"properties": {"tls": {"properties": {"port": {"default": 443}}}, "host": {"default": "localhost"}}}
+            }
+        }),
+        json!({}),
+        &json!({"server": {"host": "localhost", "tls": {"port": 443}}});
+        "nested defaults through references"
+    )]
+    #[test_case(
+        &json!({"properties": {"child": {"$ref": "#", "default": {}}, "name": {"default": "a"}}}),
+        json!({}),
+        &json!({"name": "a", "child": {"name": "a"}});
+        "recursive defaults"
+    )]
+    fn apply_defaults(schema: &Value, mut instance: Value, expected: &Value) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        compiled.apply_defaults(&mut instance);
+        assert_eq!(&instance, expected);
+    }
+
+    #[test_case(&json!({"type": "integer"}), json!("42"), &json!(42); "integer")]
+    #[test_case(&json!({"type": "integer"}), json!("-42"), &json!(-42); "negative integer")]
+    #[test_case(&json!({"type": "integer"}), json!("1e2"), &json!(100); "integer with exponent")]
+    #[test_case(&json!({"type": "integer"}), json!("-4.2E1"), &json!(-42); "integer with fraction and exponent")]
+    #[test_case(&json!({"type": "integer"}), json!("100.0"), &json!(100); "integer with zero fraction")]
+    #[test_case(&json!({"type": "number"}), json!("4.5"), &json!(4.5); "number")]
+    #[test_case(&json!({"type": "number"}), json!("42"), &json!(42); "integer number")]
+    #[test_case(&json!({"type": "boolean"}), json!("false"), &json!(false); "boolean")]
+    #[test_case(&json!({"type": "null"}), json!(""), &json!(null); "null")]
+    #[test_case(&json!({"type": ["boolean", "integer"]}), json!("1"), &json!(1); "multiple types")]
+    #[test_case(&json!({"type": ["string", "integer"]}), json!("1"), &json!("1"); "string is allowed")]
+    #[test_case(
+        &json!({"properties": {"a": {"type": "integer"}}}),
+        json!({"a": "1", "b": "2"}),
+        &json!({"a": 1, "b": "2"});
+        "properties"
+    )]
+    #[test_case(
+        &json!({"items": {"type": "boolean"}}),
+        json!(["true", "false"]),
+        &json!([true, false]);
+        "items"
+    )]
+    #[test_case(
+        &json!({
+            "properties": {"a": {"$ref": "#/definitions/port"}},
+            "definitions": {"port": {"type": "integer", "minimum": 1}}
+        }),
+        json!({"a": "80"}),
+        &json!({"a": 80});
+        "through ref"
+    )]
+    #[test_case(
+        &json!({"$schema": "http://json-schema.org/draft-04/schema#", "type": "integer"}),
+        json!("7"),
+        &json!(7);
+        "draft 4"
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"type": "integer"}, {"type": "boolean"}]}),
+        json!("42"),
+        &json!(42);
+        "anyOf"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"oneOf": [{"type": "integer"}, {"type": "boolean"}]}}}),
+        json!({"a": "true"}),
+        &json!({"a": true});
+        "oneOf"
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"type": "string", "maxLength": 1}, {"type": "integer", "minimum": 50}, {"type": "number"}]}),
+        json!("42"),
+        &json!(42);
+        "first branch valid after coercion"
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"type": "string"}, {"type": "integer"}]}),
+        json!("42"),
+        &json!("42");
+        "branch valid without coercion"
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"items": {"type": "integer"}}, {"items": {"type": "boolean"}}]}),
+        json!(["1", "2"]),
+        &json!([1, 2]);
+        "coercions inside branches"
+    )]
+    fn validate_coerced(schema: &Value, mut instance: Value, expected: &Value) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        assert!(compiled.validate_coerced(&mut instance).is_ok());
+        assert_eq!(&instance, expected);
+    }
+
+    #[test_case(&json!({"type": "integer"}), json!("4.5"); "not an integer")]
+    #[test_case(&json!({"type": "integer"}), json!("4.5e-1"); "not an integer with exponent")]
+    #[test_case(&json!({"type": "integer"}), json!("1e400"); "integer overflow")]
+    #[test_case(&json!({"type": "number"}), json!("NaN"); "not a finite number")]
+    #[test_case(&json!({"type": "boolean"}), json!("yes"); "not a boolean")]
+    #[test_case(&json!({"type": "null"}), json!("null"); "not empty")]
+    #[test_case(&json!({"type": "object"}), json!("{}"); "object")]
+    fn validate_coerced_errors(schema: &Value, mut instance: Value) {
+        let expected = instance.clone();
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let errors: Vec<_> = compiled
+            .validate_coerced(&mut instance)
+            .expect_err("Can't be coerced")
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].kind, ValidationErrorKind::Type { .. }));
+        assert_eq!(*errors[0].instance, expected);
+    }
 }
@@ -1,35 +1,236 @@
+#[cfg(any(feature = "reqwest", test))]
+use crate::cache::DiskCache;
+#[cfg(feature = "reqwest")]
 use crate::{
-    compilation::{compile_validators, context::CompilationContext, JSONSchema, DEFAULT_SCOPE},
+    cache::{Lookup, Response},
+    compilation::context::resource_of,
+    paths::PathChunk,
+    resolver::{find_references, find_schemas, SchemaResolverError},
+};
+use crate::{
+    compilation::{
+        compile_validators,
+        context::CompilationContext,
+        documents::{load_catalog, load_directory, Document, Known},
+        JSONSchema, OwnedJSONSchema, DEFAULT_SCOPE,
+    },
     content_encoding::{
         ContentEncodingCheckType, ContentEncodingConverterType,
         DEFAULT_CONTENT_ENCODING_CHECKS_AND_CONVERTERS,
     },
     content_media_type::{ContentMediaTypeCheckType, DEFAULT_CONTENT_MEDIA_TYPE_CHECKS},
-    error::CompilationError,
-    resolver::Resolver,
+    error::{CompilationError, LoadError, SchemaViolation},
+    keywords::{
+        custom::{Keyword, KeywordFactory},
+        format::FormatCheck,
+        ref_::{resolve_references, RefTargets, ReferenceCollector},
+    },
+    paths::JSONPointer,
+    resolver::{Resolved, Resolver, SchemaResolver},
     schemas,
 };
 use ahash::AHashMap;
-use serde_json::Value;
-use std::{borrow::Cow, fmt};
+#[cfg(feature = "reqwest")]
+use ahash::AHashSet;
+use serde_json::{Map, Value};
+use std::{
+    borrow::Cow,
+    env, fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 lazy_static::lazy_static! {
-    static ref META_SCHEMAS: AHashMap<String, Value> = {
-        let mut store = AHashMap::with_capacity(3);
+    static ref META_SCHEMAS: AHashMap<String, Arc<Value>> = {
+        let mut store = AHashMap::with_capacity(18);
         store.insert(
             "http://json-schema.org/draft-04/schema".to_string(),
-            serde_json::from_str(include_str!("../../meta_schemas/draft4.json")).expect("Valid schema!")
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft4.json")).expect("Valid schema!"))
         );
         store.insert(
             "http://json-schema.org/draft-06/schema".to_string(),
-            serde_json::from_str(include_str!("../../meta_schemas/draft6.json")).expect("Valid schema!")
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft6.json")).expect("Valid schema!"))
         );
         store.insert(
             "http://json-schema.org/draft-07/schema".to_string(),
-            serde_json::from_str(include_str!("../../meta_schemas/draft7.json")).expect("Valid schema!")
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft7.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2019-09/schema".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2019-09/schema.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2019-09/meta/applicator".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2019-09/meta/applicator.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2019-09/meta/content".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2019-09/meta/content.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2019-09/meta/core".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2019-09/meta/core.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2019-09/meta/format".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2019-09/meta/format.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2019-09/meta/meta-data".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2019-09/meta/meta-data.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2019-09/meta/validation".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2019-09/meta/validation.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2020-12/schema".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2020-12/schema.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2020-12/meta/applicator".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2020-12/meta/applicator.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2020-12/meta/content".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2020-12/meta/content.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2020-12/meta/core".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2020-12/meta/core.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2020-12/meta/format-annotation".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2020-12/meta/format-annotation.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2020-12/meta/meta-data".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2020-12/meta/meta-data.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2020-12/meta/unevaluated".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2020-12/meta/unevaluated.json")).expect("Valid schema!"))
+        );
+        store.insert(
+            "https://json-schema.org/draft/2020-12/meta/validation".to_string(),
+            Arc::new(serde_json::from_str(include_str!("../../meta_schemas/draft2020-12/meta/validation.json")).expect("Valid schema!"))
         );
         store
     };
+    static ref DRAFT4_META_SCHEMA: JSONSchema<'static> = compile_meta_schema(schemas::Draft::Draft4);
+    static ref DRAFT6_META_SCHEMA: JSONSchema<'static> = compile_meta_schema(schemas::Draft::Draft6);
+    static ref DRAFT7_META_SCHEMA: JSONSchema<'static> = compile_meta_schema(schemas::Draft::Draft7);
+    static ref DRAFT201909_META_SCHEMA: JSONSchema<'static> = compile_meta_schema(schemas::Draft::Draft201909);
+    static ref DRAFT202012_META_SCHEMA: JSONSchema<'static> = compile_meta_schema(schemas::Draft::Draft202012);
+}
+
+fn compile_meta_schema(draft: schemas::Draft) -> JSONSchema<'static> {
+    let schema = META_SCHEMAS
+        .get(draft.meta_schema_url())
+        .expect("All meta-schemas are embedded");
+    JSONSchema::options()
+        .with_draft(draft)
+        .with_meta_schemas()
+        .compile(schema)
+        .expect("Valid meta-schema")
+}
+
+/// Add identifiers of all schemas in `document` to `known` and HTTP(S) references to other
+/// documents to `pending`, together with their locations.
+#[cfg(feature = "reqwest")]
+fn collect_remote_references(
+    draft: schemas::Draft,
+    document: &Value,
+    base_url: &url::Url,
+    known: &mut AHashSet<String>,
+    pending: &mut Vec<(url::Url, JSONPointer)>,
+) -> Result<(), url::ParseError> {
+    find_schemas(
+        draft,
+        document,
+        base_url,
+        &mut vec![],
+        &mut |id, _, _, _| {
+            known.insert(id);
+        },
+    )?;
+    find_references(
+        draft,
+        document,
+        base_url,
+        &mut vec![],
+        &mut |mut url, path| {
+            if matches!(url.scheme(), "http" | "https") {
+                url.set_fragment(None);
+                let chunks: Vec<PathChunk> =
+                    path.iter().map(|chunk| chunk.as_ref().into()).collect();
+                pending.push((url, chunks.into()))
+            }
+        },
+    )
+}
+
+/// Load a document via HTTP(S) without blocking, using the cache if it is set.
+#[cfg(feature = "reqwest")]
+async fn fetch(
+    client: &reqwest::Client,
+    cache: Option<&DiskCache>,
+    url: &url::Url,
+) -> Result<Value, SchemaResolverError> {
+    let cache = match cache {
+        Some(cache) => cache,
+        None => {
+            let response = client.get(url.as_str()).send().await?;
+            return Ok(response.error_for_status()?.json().await?);
+        }
+    };
+    let revalidation = match cache.lookup(url)? {
+        Lookup::Fresh(document) => return Ok(document),
+        Lookup::Request(revalidation) => revalidation,
+    };
+    let mut request = client.get(url.as_str());
+    for (name, value) in revalidation.headers() {
+        request = request.header(name, value);
+    }
+    let response = match request
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+    {
+        Ok(response) if Response::is_modified(response.status()) => {
+            let headers = response.headers().clone();
+            response
+                .json()
+                .await
+                .map(|document| Response::modified(document, &headers))
+        }
+        Ok(_) => Ok(Response::NotModified),
+        Err(error) => Err(error),
+    };
+    revalidation.finish(response.map_err(Into::into))
+}
+
+/// Convert a file path to a `file://` URL. Relative paths are resolved against the current directory.
+pub(crate) fn path_to_url(path: &Path) -> Result<url::Url, url::ParseError> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir()
+            .map_err(|_| url::ParseError::RelativeUrlWithoutBase)?
+            .join(path)
+    };
+    url::Url::from_file_path(path).map_err(|_| url::ParseError::RelativeUrlWithoutBase)
+}
+
+/// Compiled meta-schemas are shared between all `JSONSchema` instances.
+fn meta_schema(draft: schemas::Draft) -> &'static JSONSchema<'static> {
+    match draft {
+        schemas::Draft::Draft4 => &DRAFT4_META_SCHEMA,
+        schemas::Draft::Draft6 => &DRAFT6_META_SCHEMA,
+        schemas::Draft::Draft7 => &DRAFT7_META_SCHEMA,
+        schemas::Draft::Draft201909 => &DRAFT201909_META_SCHEMA,
+        schemas::Draft::Draft202012 => &DRAFT202012_META_SCHEMA,
+    }
 }
 
 /// Full configuration to guide the `JSONSchema` compilation.
@@ -42,7 +243,27 @@ pub struct CompilationOptions {
     content_media_type_checks: AHashMap<&'static str, Option<ContentMediaTypeCheckType>>,
     content_encoding_checks_and_converters:
         AHashMap<&'static str, Option<(ContentEncodingCheckType, ContentEncodingConverterType)>>,
-    store: AHashMap<String, Value>,
+    store: AHashMap<String, Arc<Value>>,
+    // Files of the documents added via `with_directory` and `with_catalog`
+    locations: AHashMap<String, PathBuf>,
+    validate_schema: bool,
+    keywords: AHashMap<String, Arc<KeywordFactory>>,
+    formats: AHashMap<String, Arc<FormatCheck>>,
+    resolver: Option<Arc<dyn SchemaResolver>>,
+    #[cfg(any(feature = "reqwest", test))]
+    cache: Option<DiskCache>,
+    base_uri: Option<BaseUri>,
+    resolve_references_eagerly: bool,
+    collect_annotations: bool,
+    allow_file_references: bool,
+}
+
+/// The base URI of a schema without `$id`.
+#[derive(Debug, Clone)]
+enum BaseUri {
+    Url(url::Url),
+    // Converted to a `file://` URL during compilation
+    Path(PathBuf),
 }
 
 impl CompilationOptions {
@@ -52,6 +273,121 @@ impl CompilationOptions {
 
     /// Compile `schema` into `JSONSchema` using the currently defined options.
     pub fn compile<'a>(&self, schema: &'a Value) -> Result<JSONSchema<'a>, CompilationError> {
+        self.compile_document(Resolved::Borrowed(schema))
+    }
+
+    /// Compile `schema` into `OwnedJSONSchema` using the currently defined options.
+    ///
+    /// The compiled schema takes ownership of `schema`. Pass `Arc<Value>` to share the document
+    /// with other owners instead.
+    ///
+    /// ```rust
+    /// # use jsonschema::{JSONSchema, OwnedJSONSchema};
+    /// # use serde_json::json;
+    /// fn compile_price() -> OwnedJSONSchema {
+    ///     JSONSchema::options()
+    ///         .compile_owned(json!({"type": "number", "minimum": 0}))
+    ///         .expect("A valid schema")
+    /// }
+    ///
+    /// let compiled = compile_price();
+    /// assert!(compiled.is_valid(&json!(42)));
+    /// ```
+    pub fn compile_owned<S: Into<Arc<Value>>>(
+        &self,
+        schema: S,
+    ) -> Result<OwnedJSONSchema, CompilationError> {
+        let compiled = self.compile_document(Resolved::Shared(schema.into(), String::new()))?;
+        Ok(OwnedJSONSchema::new(compiled))
+    }
+
+    /// Compile `schema` into `JSONSchema` without blocking the current thread.
+    ///
+    /// All documents referenced via HTTP(S) are fetched with a non-blocking client before
+    /// building the validation tree, including the ones referenced from other fetched documents.
+    /// Therefore, validation never makes network requests. Documents from the store and schemas
+    /// identified by `$id` are not fetched. Prefetching is skipped if a custom `SchemaResolver`
+    /// is set, as it replaces the built-in HTTP(S) loading.
+    ///
+    /// Documents that can't be fetched are reported via `CompilationErrorKind::UnresolvedReference`.
+    #[cfg(feature = "reqwest")]
+    pub async fn compile_async<'a>(
+        &self,
+        schema: &'a Value,
+    ) -> Result<JSONSchema<'a>, CompilationError> {
+        if self.resolver.is_some() {
+            return self.compile(schema);
+        }
+        let mut config = self.clone();
+        config.prefetch(schema).await?;
+        config.compile(schema)
+    }
+
+    /// Fetch all remote documents referenced from `schema` and put them into the store.
+    #[cfg(feature = "reqwest")]
+    async fn prefetch(&mut self, schema: &Value) -> Result<(), CompilationError> {
+        let draft = self
+            .draft
+            .or_else(|| schemas::draft_from_schema(schema))
+            .unwrap_or_default();
+        // Fragment-less URLs of documents and schemas that are available without fetching
+        let mut known = AHashSet::new();
+        let mut pending = Vec::new();
+        let scope = resource_of(&self.scope_of(draft, schema)?);
+        collect_remote_references(draft, schema, &scope, &mut known, &mut pending)?;
+        for (id, document) in &self.store {
+            known.insert(id.clone());
+            if let Ok(base_url) = url::Url::parse(id) {
+                find_schemas(
+                    draft,
+                    document,
+                    &base_url,
+                    &mut vec![],
+                    &mut |id, _, _, _| {
+                        known.insert(id);
+                    },
+                )?;
+            }
+        }
+        let client = reqwest::Client::new();
+        while let Some((url, schema_path)) = pending.pop() {
+            if !known.insert(url.to_string()) {
+                continue;
+            }
+            let document = fetch(&client, self.cache.as_ref(), &url)
+                .await
+                .map_err(|error| {
+                    CompilationError::unresolved_reference(schema_path, url.as_str(), error)
+                })?;
+            // Fetched documents may use a different draft
+            let document_draft = schemas::draft_from_schema(&document).unwrap_or(draft);
+            collect_remote_references(document_draft, &document, &url, &mut known, &mut pending)?;
+            self.store.insert(url.to_string(), Arc::new(document));
+        }
+        Ok(())
+    }
+
+    /// The base URI of `schema`.
+    fn scope_of(
+        &self,
+        draft: schemas::Draft,
+        schema: &Value,
+    ) -> Result<url::Url, CompilationError> {
+        let base_uri = match &self.base_uri {
+            Some(BaseUri::Url(url)) => Some(url.clone()),
+            Some(BaseUri::Path(path)) => Some(path_to_url(path)?),
+            None => None,
+        };
+        Ok(match (schemas::id_of(draft, schema), base_uri) {
+            (Some(url), None) => url::Url::parse(url)?,
+            (Some(url), Some(base_uri)) => base_uri.join(url)?,
+            (None, Some(base_uri)) => base_uri,
+            (None, None) => DEFAULT_SCOPE.clone(),
+        })
+    }
+
+    fn compile_document<'a>(&self, root: Resolved<'a>) -> Result<JSONSchema<'a>, CompilationError> {
+        let schema: &Value = &root;
         // Draft is detected in the following precedence order:
         //   - Explicitly specified;
         //   - $schema field in the document;
@@ -72,22 +408,46 @@ impl CompilationOptions {
         let processed_config: Cow<'_, CompilationOptions> = Cow::Owned(config);
         let draft = processed_config.draft();
 
-        let scope = match schemas::id_of(draft, schema) {
-            Some(url) => url::Url::parse(url)?,
-            None => DEFAULT_SCOPE.clone(),
-        };
-        let resolver = Resolver::new(draft, &scope, schema, self.store.clone())?;
-        let context = CompilationContext::new(scope, processed_config);
+        if self.validate_schema {
+            if let Err(errors) = meta_schema(draft).validate(schema) {
+                return Err(CompilationError::meta_schema(
+                    errors.map(SchemaViolation::from).collect(),
+                ));
+            }
+        }
 
-        let mut validators = compile_validators(schema, &context)?;
+        let scope = self.scope_of(draft, schema)?;
+        let resolver = Resolver::new(
+            draft,
+            &scope,
+            root.clone(),
+            self.store.clone(),
+            self.resolver.clone(),
+            self.allow_file_references,
+        )?;
+        #[cfg(any(feature = "reqwest", test))]
+        let resolver = resolver.with_cache(self.cache.clone());
+        let context = CompilationContext::new(scope, processed_config, schema);
+
+        let references = ReferenceCollector::default();
+        let mut validators = if self.resolve_references_eagerly {
+            compile_validators(schema, &context.with_references(&references))?
+        } else {
+            compile_validators(schema, &context)?
+        };
         validators.shrink_to_fit();
 
-        Ok(JSONSchema {
-            schema,
+        let compiled = JSONSchema {
+            schema: root,
             resolver,
             validators,
             context,
-        })
+            references: RefTargets::default(),
+        };
+        if self.resolve_references_eagerly {
+            resolve_references(&compiled, references)?;
+        }
+        Ok(compiled)
     }
 
     /// Ensure that the schema is going to be compiled using the defined Draft.
@@ -292,7 +652,356 @@ impl CompilationOptions {
     /// The example above is taken from the Swagger 2.0 JSON schema.
     #[inline]
     pub fn with_meta_schemas(&mut self) -> &mut Self {
-        self.store.extend(META_SCHEMAS.clone().into_iter());
+        self.store.extend(
+            META_SCHEMAS
+                .iter()
+                .map(|(id, document)| (id.clone(), Arc::clone(document))),
+        );
+        self
+    }
+
+    /// Validate the input schema against the meta-schema of its draft before compilation.
+    /// All violations are reported in `CompilationErrorKind::MetaSchema`.
+    ///
+    /// ```rust
+    /// # use jsonschema::{CompilationErrorKind, JSONSchema};
+    /// # use serde_json::json;
+    /// let schema = json!({"minLength": "five", "type": "strnig"});
+    /// let error = JSONSchema::options()
+    ///     .should_validate_schema(true)
+    ///     .compile(&schema)
+    ///     .expect_err("The schema is invalid");
+    /// if let CompilationErrorKind::MetaSchema { violations } = error.kind {
+    ///     assert_eq!(violations.len(), 2);
+    /// }
+    /// ```
+    ///
+    /// It is disabled by default.
+    #[inline]
+    pub fn should_validate_schema(&mut self, validate: bool) -> &mut Self {
+        self.validate_schema = validate;
+        self
+    }
+
+    /// Register a custom `format` check. It takes precedence over the built-in check with the
+    /// same name, if any. Non-string instances are always valid.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"format": "e164-phone"});
+    /// let compiled = JSONSchema::options()
+    ///     .with_format("e164-phone", |value| {
+    ///         value.starts_with('+') && value[1..].chars().all(|c| c.is_ascii_digit())
+    ///     })
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// assert!(compiled.is_valid(&json!("+14155552671")));
+    /// assert!(!compiled.is_valid(&json!("4155552671")));
+    /// ```
+    #[inline]
+    pub fn with_format<N, F>(&mut self, name: N, format: F) -> &mut Self
+    where
+        N: Into<String>,
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.formats.insert(name.into(), Arc::new(format));
+        self
+    }
+
+    pub(crate) fn format(&self, name: &str) -> Option<&Arc<FormatCheck>> {
+        self.formats.get(name)
+    }
+
+    /// Register a custom keyword. The factory is called for every occurrence of the keyword in
+    /// the schema and receives the schema object that contains it, the keyword value and its
+    /// location. The returned validator is used alongside the built-in ones.
+    /// Custom keywords take precedence over built-in keywords with the same name.
+    ///
+    /// ```rust
+    /// # use jsonschema::{paths::JSONPointer, ErrorIterator, JSONSchema, Keyword, ValidationError};
+    /// # use serde_json::{json, Value};
+    /// struct Even {
+    ///     schema_path: JSONPointer,
+    /// }
+    ///
+    /// impl Keyword for Even {
+    ///     fn validate<'instance>(
+    ///         &self,
+    ///         instance: &'instance Value,
+    ///         instance_path: &JSONPointer,
+    ///     ) -> ErrorIterator<'instance> {
+    ///         let errors = if self.is_valid(instance) {
+    ///             vec![]
+    ///         } else {
+    ///             vec![ValidationError::custom(
+    ///                 self.schema_path.clone(),
+    ///                 instance_path.clone(),
+    ///                 instance,
+    ///                 format!("{} is not even", instance),
+    ///             )]
+    ///         };
+    ///         Box::new(errors.into_iter())
+    ///     }
+    ///
+    ///     fn is_valid(&self, instance: &Value) -> bool {
+    ///         instance.as_u64().map_or(true, |number| number % 2 == 0)
+    ///     }
+    /// }
+    ///
+    /// let schema = json!({"x-even": true});
+    /// let compiled = JSONSchema::options()
+    ///     .with_keyword("x-even", |_, _, schema_path| Ok(Box::new(Even { schema_path })))
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// assert!(compiled.is_valid(&json!(2)));
+    /// assert!(!compiled.is_valid(&json!(3)));
+    /// ```
+    #[inline]
+    pub fn with_keyword<N, F>(&mut self, name: N, factory: F) -> &mut Self
+    where
+        N: Into<String>,
+        F: for<'a> Fn(
+                &'a Map<String, Value>,
+                &'a Value,
+                JSONPointer,
+            ) -> Result<Box<dyn Keyword>, CompilationError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.keywords.insert(name.into(), Arc::new(factory));
+        self
+    }
+
+    pub(crate) fn keyword(&self, name: &str) -> Option<&Arc<KeywordFactory>> {
+        self.keywords.get(name)
+    }
+
+    /// Load referenced documents via a custom `SchemaResolver` instead of the built-in
+    /// HTTP(S) loader. It is used for all URL schemes, documents from the store take precedence.
+    ///
+    /// ```rust
+    /// # use jsonschema::{JSONSchema, SchemaResolver, SchemaResolverError};
+    /// # use serde_json::{json, Value};
+    /// # use url::Url;
+    /// struct ArtifactResolver;
+    ///
+    /// impl SchemaResolver for ArtifactResolver {
+    ///     fn resolve(&self, url: &Url) -> Result<Value, SchemaResolverError> {
+    ///         match url.as_str() {
+    ///             "artifact://schemas/name.json" => Ok(json!({"type": "string"})),
+    ///             _ => Err(format!("Unknown artifact: {}", url).into()),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let schema = json!({"$ref": "artifact://schemas/name.json"});
+    /// let compiled = JSONSchema::options()
+    ///     .with_resolver(ArtifactResolver)
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// assert!(compiled.is_valid(&json!("foo")));
+    /// assert!(!compiled.is_valid(&json!(42)));
+    /// ```
+    #[inline]
+    pub fn with_resolver(&mut self, resolver: impl SchemaResolver + 'static) -> &mut Self {
+        self.resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Persist documents loaded via HTTP(S) in `cache` and load them from it in the first place.
+    /// It is not used for documents loaded by a custom `SchemaResolver`.
+    ///
+    /// ```rust
+    /// # use jsonschema::{DiskCache, JSONSchema};
+    /// # let schema = serde_json::json!({});
+    /// // Only use documents that were cached before
+    /// let cache = DiskCache::new(".schema-cache").offline(true);
+    /// let compiled = JSONSchema::options()
+    ///     .with_cache(cache)
+    ///     .compile(&schema);
+    /// ```
+    #[cfg(any(feature = "reqwest", test))]
+    #[inline]
+    pub fn with_cache(&mut self, cache: DiskCache) -> &mut Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Set the location of the schema file. If the root schema has no `$id`, its base URI is
+    /// the `file://` URL of this path, so relative references, e.g. `common/address.json`,
+    /// are loaded from files next to the schema. A relative `$id` is resolved against it too.
+    /// It is a shortcut for `with_base_uri` with a `file://` URL that also enables
+    /// `should_allow_file_references`.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"$ref": "definitions.json#/definitions/name"});
+    /// let maybe_jsonschema = JSONSchema::options()
+    ///     .with_base_path("schemas/person.json")
+    ///     .compile(&schema);
+    /// ```
+    #[inline]
+    pub fn with_base_path(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        self.base_uri = Some(BaseUri::Path(path.as_ref().to_path_buf()));
+        self.allow_file_references = true;
+        self
+    }
+
+    /// Load documents referenced via `file://` URLs from the file system.
+    /// Otherwise such references are reported as `ValidationErrorKind::UnknownReferenceScheme`.
+    ///
+    /// It is disabled by default, because an untrusted schema could read any file available to
+    /// the process. `with_base_path` enables it.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"$ref": "file:///etc/schemas/definitions.json#/definitions/name"});
+    /// let maybe_jsonschema = JSONSchema::options()
+    ///     .should_allow_file_references(true)
+    ///     .compile(&schema);
+    /// ```
+    #[inline]
+    pub fn should_allow_file_references(&mut self, allow: bool) -> &mut Self {
+        self.allow_file_references = allow;
+        self
+    }
+
+    /// Set the base URI of the root schema. It is used instead of the default `json-schema:///`
+    /// if the schema has no `$id` and a relative `$id` is resolved against it.
+    /// References and absolute keyword locations in errors are relative to this URI.
+    ///
+    /// It allows schemas without `$id` to reference each other:
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// # use url::Url;
+    /// let schema = json!({"properties": {"id": {"$ref": "common.json#/definitions/id"}}});
+    /// let compiled = JSONSchema::options()
+    ///     .with_base_uri(Url::parse("https://example.com/schemas/order.json").unwrap())
+    ///     .with_document(
+    ///         "https://example.com/schemas/common.json".to_string(),
+    ///         json!({"definitions": {"id": {"type": "integer"}}}),
+    ///     )
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// assert!(compiled.is_valid(&json!({"id": 1})));
+    /// assert!(!compiled.is_valid(&json!({"id": "1"})));
+    /// ```
+    #[inline]
+    pub fn with_base_uri(&mut self, base_uri: url::Url) -> &mut Self {
+        self.base_uri = Some(BaseUri::Url(base_uri));
+        self
+    }
+
+    /// Resolve all references during compilation, including ones in referenced documents.
+    /// If any of them can't be resolved, compilation fails with
+    /// `CompilationErrorKind::UnresolvedReference` instead of reporting
+    /// `ValidationErrorKind::InvalidReference` on validation.
+    ///
+    /// Referenced schemas are compiled upfront as well, otherwise they are compiled on first use.
+    ///
+    /// ```rust
+    /// # use jsonschema::{CompilationErrorKind, JSONSchema};
+    /// # use serde_json::json;
+    /// let schema = json!({
+    ///     "properties": {"foo": {"$ref": "#/definitions/unknown"}},
+    ///     "definitions": {"known": {"type": "string"}}
+    /// });
+    /// let error = JSONSchema::options()
+    ///     .should_resolve_references_eagerly(true)
+    ///     .compile(&schema)
+    ///     .expect_err("The reference is invalid");
+    /// assert!(matches!(error.kind, CompilationErrorKind::UnresolvedReference { .. }));
+    /// ```
+    ///
+    /// It is disabled by default.
+    #[inline]
+    pub fn should_resolve_references_eagerly(&mut self, resolve: bool) -> &mut Self {
+        self.resolve_references_eagerly = resolve;
+        self
+    }
+
+    /// Compile annotation keywords, e.g. `title`, `description` or `default`, so they can be
+    /// collected via `Output::annotations`. Otherwise they are skipped during compilation.
+    ///
+    /// ```rust
+    /// # use jsonschema::JSONSchema;
+    /// # use serde_json::json;
+    /// let schema = json!({"properties": {"name": {"title": "Full name"}}});
+    /// let compiled = JSONSchema::options()
+    ///     .should_collect_annotations(true)
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// let instance = json!({"name": "Alice"});
+    /// let output = compiled.apply(&instance).annotations();
+    /// let titles: Vec<_> = output.at("/name").map(|annotation| &annotation.annotation).collect();
+    /// assert_eq!(titles, vec![&json!("Full name")]);
+    /// ```
+    ///
+    /// It is disabled by default.
+    #[inline]
+    pub fn should_collect_annotations(&mut self, collect: bool) -> &mut Self {
+        self.collect_annotations = collect;
+        self
+    }
+
+    pub(crate) const fn collect_annotations(&self) -> bool {
+        self.collect_annotations
+    }
+
+    /// Add all schemas from `directory` and its subdirectories to the store under their `$id`,
+    /// so they can refer to each other. Only files with the `.json` extension are loaded.
+    ///
+    /// Relative ids are resolved against the files' URLs. Schemas that can't be read, have no
+    /// `$id` or the same `$id` as another schema, including the ones already in the store, are
+    /// reported together via `LoadError`. In this case no schemas are added to the store.
+    ///
+    /// ```rust
+    /// # use jsonschema::{JSONSchema, LoadError};
+    /// # use serde_json::json;
+    /// fn compile_order(schema: &serde_json::Value) -> Result<(), LoadError> {
+    ///     let compiled = JSONSchema::options()
+    ///         .with_directory("schemas")?
+    ///         .compile(schema);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_directory(&mut self, directory: impl AsRef<Path>) -> Result<&mut Self, LoadError> {
+        let documents = load_directory(directory.as_ref(), self.draft, &self.known())?;
+        Ok(self.add_documents(documents))
+    }
+
+    /// Add all schemas listed in a SchemaStore-style catalog to the store under their `$id`.
+    /// The catalog is an object with the `schemas` array, where every item has the `url` property
+    /// with a `file://` URL or a path relative to the catalog:
+    ///
+    /// ```json
+    /// {"schemas": [{"name": "Order", "url": "order.json"}]}
+    /// ```
+    ///
+    /// Problems are reported in the same way as in `with_directory`.
+    pub fn with_catalog(&mut self, catalog: impl AsRef<Path>) -> Result<&mut Self, LoadError> {
+        let documents = load_catalog(catalog.as_ref(), self.draft, &self.known())?;
+        Ok(self.add_documents(documents))
+    }
+
+    fn known(&self) -> Known<'_> {
+        Known {
+            store: &self.store,
+            locations: &self.locations,
+        }
+    }
+
+    fn add_documents(&mut self, documents: Vec<Document>) -> &mut Self {
+        for (id, path, document) in documents {
+            self.store.insert(id.clone(), Arc::new(document));
+            self.locations.insert(id, path);
+        }
         self
     }
 
@@ -300,21 +1009,35 @@ impl CompilationOptions {
     /// calls to remote schemas via the `$ref` keyword.
     #[inline]
     pub fn with_document(&mut self, id: String, document: Value) -> &mut Self {
-        self.store.insert(id, document);
+        self.store.insert(id, Arc::new(document));
         self
     }
 }
 
 impl fmt::Debug for CompilationOptions {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt.debug_struct("CompilationConfig")
+        let mut debug = fmt.debug_struct("CompilationConfig");
+        debug
             .field("draft", &self.draft)
+            .field("validate_schema", &self.validate_schema)
+            .field("keywords", &self.keywords.keys().collect::<Vec<_>>())
+            .field("formats", &self.formats.keys().collect::<Vec<_>>())
+            .field("custom_resolver", &self.resolver.is_some())
+            .field("base_uri", &self.base_uri)
+            .field(
+                "resolve_references_eagerly",
+                &self.resolve_references_eagerly,
+            )
+            .field("collect_annotations", &self.collect_annotations)
+            .field("allow_file_references", &self.allow_file_references)
             .field("content_media_type", &self.content_media_type_checks.keys())
             .field(
                 "content_encoding",
                 &self.content_encoding_checks_and_converters.keys(),
-            )
-            .finish()
+            );
+        #[cfg(any(feature = "reqwest", test))]
+        debug.field("cache", &self.cache);
+        debug.finish()
     }
 }
 
@@ -322,9 +1045,14 @@ impl fmt::Debug for CompilationOptions {
 mod tests {
     use super::CompilationOptions;
     use crate::schemas::Draft;
-    use crate::JSONSchema;
+    use crate::{CompilationErrorKind, JSONSchema, SchemaResolver, SchemaResolverError};
     use serde_json::{json, Value};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
     use test_case::test_case;
+    use url::Url;
 
     #[test_case(Some(Draft::Draft4), &json!({}) => Draft::Draft4)]
     #[test_case(None, &json!({"$schema": "http://json-schema.org/draft-06/schema#"}) => Draft::Draft6)]
@@ -354,4 +1082,347 @@ mod tests {
         assert!(!compiled.is_valid(&json!("foo")));
         assert!(compiled.is_valid(&json!("foobar")));
     }
+
+    #[test_case(&json!({"minLength": 1, "$defs": {"foo": {"type": "integer"}}}), true)]
+    #[test_case(&json!({"minLength": -1}), false)]
+    #[test_case(&json!({"properties": {"foo": {"minContains": "1"}}}), false)]
+    #[test_case(&json!({"$anchor": "#foo"}), false)]
+    fn test_draft201909_meta_schema(instance: &Value, expected: bool) {
+        let schema = json!({"$ref": "https://json-schema.org/draft/2019-09/schema"});
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft201909)
+            .with_meta_schemas()
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test_case(&json!({"prefixItems": [{"type": "string"}], "$defs": {"foo": {"type": "integer"}}}), true)]
+    #[test_case(&json!({"prefixItems": {}}), false)]
+    #[test_case(&json!({"properties": {"foo": {"prefixItems": [{"minItems": -1}]}}}), false)]
+    #[test_case(&json!({"$dynamicAnchor": "#foo"}), false)]
+    fn test_draft202012_meta_schema(instance: &Value, expected: bool) {
+        let schema = json!({"$ref": "https://json-schema.org/draft/2020-12/schema"});
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft202012)
+            .with_meta_schemas()
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test_case(&json!({"minLength": 5}), None)]
+    #[test_case(&json!({"$schema": "http://json-schema.org/draft-04/schema#", "maximum": 5, "exclusiveMaximum": true}), None)]
+    #[test_case(&json!({"$schema": "https://json-schema.org/draft/2020-12/schema", "prefixItems": [{}]}), None)]
+    #[test_case(&json!({"minLength": "five"}), Some(&["/minLength"]))]
+    #[test_case(&json!({"type": "strnig"}), Some(&["/type"]))]
+    #[test_case(&json!({"$schema": "http://json-schema.org/draft-06/schema#", "exclusiveMaximum": true}), Some(&["/exclusiveMaximum"]))]
+    #[test_case(&json!({"properties": {"foo": {"minLength": -1}}, "required": "foo"}), Some(&["/properties/foo/minLength", "/required"]))]
+    fn test_validate_schema(schema: &Value, expected: Option<&[&str]>) {
+        let result = JSONSchema::options()
+            .should_validate_schema(true)
+            .compile(schema);
+        match expected {
+            None => assert!(result.is_ok()),
+            Some(expected) => {
+                let error = result.expect_err("Should fail");
+                if let CompilationErrorKind::MetaSchema { violations } = error.kind {
+                    let paths: Vec<String> = violations
+                        .iter()
+                        .map(|violation| violation.schema_path.to_string())
+                        .collect();
+                    assert_eq!(paths, expected)
+                } else {
+                    panic!("Should be a meta-schema error")
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_schema_disabled_by_default() {
+        // Annotations are not checked by the compiler
+        let schema = json!({"type": "string", "title": 5});
+        assert!(JSONSchema::compile(&schema).is_ok());
+        assert!(JSONSchema::options()
+            .should_validate_schema(true)
+            .compile(&schema)
+            .is_err());
+    }
+
+    #[test]
+    fn test_base_uri() {
+        let base_uri = Url::parse("https://example.com/schemas/order.json").unwrap();
+        let schema = json!({
+            "properties": {"id": {"$ref": "#/definitions/id"}},
+            "definitions": {"id": {"type": "integer"}}
+        });
+        let compiled = JSONSchema::options()
+            .with_base_uri(base_uri)
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!({"id": 1})));
+        let instance = json!({"id": "1"});
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(
+            errors[0]
+                .absolute_keyword_location
+                .as_ref()
+                .unwrap()
+                .as_str(),
+            "https://example.com/schemas/order.json#/definitions/id/type"
+        );
+    }
+
+    #[test]
+    fn test_base_uri_invalid_reference() {
+        let base_uri = Url::parse("https://example.com/schemas/order.json").unwrap();
+        let schema = json!({"$ref": "#/definitions/unknown"});
+        let compiled = JSONSchema::options()
+            .with_base_uri(base_uri)
+            .compile(&schema)
+            .unwrap();
+        let instance = json!(1);
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(
+            errors[0].to_string(),
+            "Invalid reference: https://example.com/schemas/order.json#/definitions/unknown"
+        );
+    }
+
+    #[test]
+    fn test_base_uri_relative_id() {
+        let base_uri = Url::parse("https://example.com/schemas/").unwrap();
+        let schema = json!({"$id": "order.json", "items": {"$ref": "common.json"}});
+        let compiled = JSONSchema::options()
+            .with_base_uri(base_uri)
+            .with_document(
+                "https://example.com/schemas/common.json".to_string(),
+                json!({"type": "integer"}),
+            )
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!([1])));
+        assert!(!compiled.is_valid(&json!(["1"])));
+    }
+
+    #[test]
+    fn test_base_uri_schemas_reference_each_other() {
+        // Both schemas have no `$id`
+        let order = json!({"properties": {"customer": {"$ref": "customer.json"}}});
+        let customer = json!({
+            "properties": {"orders": {"items": {"$ref": "order.json"}}},
+            "required": ["name"]
+        });
+        let compiled = JSONSchema::options()
+            .with_base_uri(Url::parse("https://example.com/order.json").unwrap())
+            .with_document("https://example.com/customer.json".to_string(), customer)
+            .compile(&order)
+            .unwrap();
+        assert!(compiled.is_valid(&json!({"customer": {"name": "Alice"}})));
+        assert!(!compiled.is_valid(&json!({"customer": {}})));
+        assert!(!compiled
+            .is_valid(&json!({"customer": {"name": "Alice", "orders": [{"customer": {}}]}})));
+    }
+
+    #[test_case(&json!({"properties": {"foo": {"$ref": "#/definitions/unknown"}}}), "/properties/foo/$ref", "json-schema:///#/definitions/unknown")]
+    #[test_case(&json!({"items": {"$ref": "#unknown"}}), "/items/$ref", "json-schema:///#unknown")]
+    #[test_case(&json!({"$ref": "unknown://schema.json"}), "/$ref", "unknown://schema.json")]
+    // The broken reference is inside the referenced document
+    #[test_case(&json!({"$ref": "http://example.com/schema.json"}), "/items/$ref", "http://example.com/schema.json#/definitions/unknown")]
+    fn test_unresolved_reference(schema: &Value, schema_path: &str, reference: &str) {
+        let error = JSONSchema::options()
+            .should_resolve_references_eagerly(true)
+            .with_document(
+                "http://example.com/schema.json".to_string(),
+                json!({"items": {"$ref": "#/definitions/unknown"}}),
+            )
+            .compile(schema)
+            .expect_err("The reference is unresolvable");
+        assert_eq!(error.keyword.as_deref(), Some("$ref"));
+        assert_eq!(error.schema_path.to_string(), schema_path);
+        if let CompilationErrorKind::UnresolvedReference {
+            reference: unresolved,
+            ..
+        } = error.kind
+        {
+            assert_eq!(unresolved, reference)
+        } else {
+            panic!("Should be an unresolved reference error")
+        }
+    }
+
+    #[test]
+    fn test_unresolved_reference_lazy() {
+        let schema = json!({"$ref": "#/definitions/unknown"});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        assert!(!compiled.is_valid(&json!(1)));
+    }
+
+    #[test_case(&json!({"foo": {"foo": {}}}), true)]
+    #[test_case(&json!({"foo": {"foo": 1}}), false)]
+    #[test_case(&json!({"bar": {"baz": {"baz": "a"}}}), true)]
+    #[test_case(&json!({"bar": {"baz": {"baz": 1}}}), false)]
+    fn test_resolve_references_eagerly_recursive(instance: &Value, expected: bool) {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "foo": {"$ref": "#"},
+                "bar": {"$ref": "#/definitions/bar"}
+            },
+            "definitions": {
+                "bar": {"type": "object", "properties": {"baz": {"$ref": "#/definitions/baz"}}},
+                "baz": {"anyOf": [{"type": "string"}, {"$ref": "#/definitions/bar"}]}
+            }
+        });
+        let compiled = JSONSchema::options()
+            .should_resolve_references_eagerly(true)
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn test_resolve_references_eagerly_loads_documents() {
+        struct CountingResolver(Arc<AtomicUsize>);
+        impl SchemaResolver for CountingResolver {
+            fn resolve(&self, url: &Url) -> Result<Value, SchemaResolverError> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                match url.as_str() {
+                    "db://name" => Ok(json!({"$ref": "db://string"})),
+                    "db://string" => Ok(json!({"type": "string"})),
+                    _ => Err("Not found".into()),
+                }
+            }
+        }
+        let calls = Arc::new(AtomicUsize::new(0));
+        let schema = json!({"properties": {"name": {"$ref": "db://name"}}});
+        let compiled = JSONSchema::options()
+            .should_resolve_references_eagerly(true)
+            .with_resolver(CountingResolver(Arc::clone(&calls)))
+            .compile(&schema)
+            .unwrap();
+        // Both documents are loaded during compilation
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(compiled.is_valid(&json!({"name": "foo"})));
+        assert!(!compiled.is_valid(&json!({"name": 1})));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_resolve_references_eagerly_invalid_target() {
+        let schema =
+            json!({"$ref": "#/definitions/foo", "definitions": {"foo": {"minLength": "five"}}});
+        let error = JSONSchema::options()
+            .should_resolve_references_eagerly(true)
+            .compile(&schema)
+            .expect_err("The referenced schema is invalid");
+        assert_eq!(error.kind, CompilationErrorKind::InvalidValue);
+        assert_eq!(error.keyword.as_deref(), Some("minLength"));
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_compile_async() {
+        let customer = mockito::mock("GET", "/async/customer.json")
+            .with_body(r#"{"properties": {"address": {"$ref": "address.json"}}}"#)
+            .expect(1)
+            .create();
+        let address = mockito::mock("GET", "/async/address.json")
+            .with_body(
+                r#"{"properties": {"city": {"$ref": "definitions.json#/definitions/city"}}}"#,
+            )
+            .expect(1)
+            .create();
+        let definitions = mockito::mock("GET", "/async/definitions.json")
+            .with_body(r#"{"definitions": {"city": {"type": "string"}}}"#)
+            .expect(1)
+            .create();
+        let base = format!("http://{}/async", mockito::server_address());
+        let schema = json!({
+            "properties": {
+                "customer": {"$ref": format!("{}/customer.json", base)},
+                "other": {"$ref": format!("{}/customer.json#/properties/address", base)}
+            }
+        });
+        let compiled = JSONSchema::options().compile_async(&schema).await.unwrap();
+        // All documents are fetched once during compilation, so validation doesn't block
+        customer.assert();
+        address.assert();
+        definitions.assert();
+        assert!(compiled.is_valid(&json!({"customer": {"address": {"city": "Prague"}}})));
+        assert!(!compiled.is_valid(&json!({"customer": {"address": {"city": 42}}})));
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_compile_async_only_schemas() {
+        // These values are not schemas, therefore there is nothing to fetch
+        let schema = json!({
+            "const": {"$ref": "http://unreachable/const.json"},
+            "default": {"$ref": "http://unreachable/default.json"},
+            "enum": [{"$ref": "http://unreachable/enum.json"}],
+            "properties": {"$ref": {"examples": [{"$ref": "http://unreachable/examples.json"}]}}
+        });
+        assert!(JSONSchema::options().compile_async(&schema).await.is_ok());
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_compile_async_document_draft() {
+        // `$defs` contains schemas only since Draft 2019-09
+        let document = mockito::mock("GET", "/async/drafts/document.json")
+            .with_body(
+                r##"{
+                    "$schema": "https://json-schema.org/draft/2019-09/schema",
+                    "$ref": "#/$defs/name",
+                    "$defs": {"name": {"$ref": "name.json"}}
+                }"##,
+            )
+            .expect(1)
+            .create();
+        let name = mockito::mock("GET", "/async/drafts/name.json")
+            .with_body(r#"{"type": "string"}"#)
+            .expect(1)
+            .create();
+        let schema = json!({
+            "$ref": format!("http://{}/async/drafts/document.json", mockito::server_address())
+        });
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft7)
+            .compile_async(&schema)
+            .await
+            .unwrap();
+        document.assert();
+        name.assert();
+        assert!(compiled.is_valid(&json!("Alice")));
+        assert!(!compiled.is_valid(&json!(42)));
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[tokio::test]
+    async fn test_compile_async_unresolved() {
+        let _missing = mockito::mock("GET", "/async/missing.json")
+            .with_status(404)
+            .create();
+        let reference = format!("http://{}/async/missing.json", mockito::server_address());
+        let schema = json!({"items": {"$ref": format!("{}#/definitions/foo", reference)}});
+        let error = JSONSchema::options()
+            .compile_async(&schema)
+            .await
+            .expect_err("The document is missing");
+        assert_eq!(error.schema_path.to_string(), "/items/$ref");
+        if let CompilationErrorKind::UnresolvedReference {
+            reference: unresolved,
+            ..
+        } = error.kind
+        {
+            assert_eq!(unresolved, reference)
+        } else {
+            panic!("Should be an unresolved reference error")
+        }
+    }
 }
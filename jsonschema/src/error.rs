@@ -1,46 +1,365 @@
 //! Error types
 
-use crate::primitive_type::{PrimitiveType, PrimitiveTypesBitMap};
+use crate::{
+    paths::{JSONPointer, PathChunk},
+    primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+    resolver::SchemaResolverError,
+};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
 use serde_json::{Map, Number, Value};
 use std::{
     borrow::Cow,
+    cmp::Reverse,
     error, fmt,
     fmt::{Error, Formatter},
     io,
     iter::{empty, once},
+    path::PathBuf,
     str::Utf8Error,
     string::FromUtf8Error,
 };
+use url::Url;
 
 /// The error type that happens when the input schema is not valid.
 ///
 /// It includes cases when during validation a reference is resolved into an invalid schema,
 /// which we can't know upfront because schemas can be in remote locations.
 #[derive(Debug, PartialEq)]
-pub enum CompilationError {
-    /// Invalid schema structure
-    SchemaError,
+pub struct CompilationError {
+    /// Keyword that has an invalid value.
+    /// It is `None` if the error is not related to a specific keyword, e.g. an invalid base URI.
+    pub keyword: Option<String>,
+    /// Location of the invalid value within the schema, e.g. `/properties/foo/minItems`
+    pub schema_path: JSONPointer,
+    /// The invalid value, if the error is related to a specific keyword
+    pub value: Option<Value>,
+    /// Type of compilation error
+    pub kind: CompilationErrorKind,
 }
 
-impl error::Error for CompilationError {}
+/// Kinds of errors that may happen during compilation
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum CompilationErrorKind {
+    /// The keyword value has an unexpected type or structure.
+    InvalidValue,
+    /// Invalid regular expression, e.g. in `pattern` or `patternProperties`.
+    InvalidRegex { error: regex::Error },
+    /// Invalid URL, e.g. in `$id` or `$ref`.
+    InvalidURL { error: url::ParseError },
+    /// The schema doesn't conform to the meta-schema of its draft.
+    MetaSchema { violations: Vec<SchemaViolation> },
+    /// A reference can't be resolved, e.g. its target doesn't exist or can't be loaded.
+    UnresolvedReference {
+        reference: String,
+        cause: SchemaResolverError,
+    },
+}
 
-impl fmt::Display for CompilationError {
+/// Causes of unresolved references are compared by their messages.
+impl PartialEq for CompilationErrorKind {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (CompilationErrorKind::InvalidValue, CompilationErrorKind::InvalidValue) => true,
+            (
+                CompilationErrorKind::InvalidRegex { error: left },
+                CompilationErrorKind::InvalidRegex { error: right },
+            ) => left == right,
+            (
+                CompilationErrorKind::InvalidURL { error: left },
+                CompilationErrorKind::InvalidURL { error: right },
+            ) => left == right,
+            (
+                CompilationErrorKind::MetaSchema { violations: left },
+                CompilationErrorKind::MetaSchema { violations: right },
+            ) => left == right,
+            (
+                CompilationErrorKind::UnresolvedReference {
+                    reference: left,
+                    cause: left_cause,
+                },
+                CompilationErrorKind::UnresolvedReference {
+                    reference: right,
+                    cause: right_cause,
+                },
+            ) => left == right && left_cause.to_string() == right_cause.to_string(),
+            _ => false,
+        }
+    }
+}
+
+/// A place where the input schema doesn't conform to its meta-schema.
+#[derive(Debug, PartialEq)]
+pub struct SchemaViolation {
+    /// Location of the invalid value within the input schema, e.g. `/properties/foo/minLength`
+    pub schema_path: JSONPointer,
+    /// Location of the failed keyword within the meta-schema, e.g. `/properties/minLength/type`
+    pub meta_schema_path: JSONPointer,
+    /// Description of the violation, e.g. `'"five"' is not of type 'integer'`
+    pub message: String,
+}
+
+impl From<ValidationError<'_>> for SchemaViolation {
+    #[inline]
+    fn from(error: ValidationError<'_>) -> Self {
+        SchemaViolation {
+            message: error.to_string(),
+            schema_path: error.instance_path,
+            meta_schema_path: error.schema_path,
+        }
+    }
+}
+
+impl fmt::Display for SchemaViolation {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "Schema compilation error")
+        write!(f, "'{}': {}", self.schema_path, self.message)
+    }
+}
+
+impl CompilationError {
+    fn new(schema_path: JSONPointer, value: &Value, kind: CompilationErrorKind) -> Self {
+        // The keyword is the last chunk that points to the invalid value
+        let keyword = match schema_path.chunks().last() {
+            Some(PathChunk::Name(name)) => Some(name.clone()),
+            _ => None,
+        };
+        CompilationError {
+            keyword,
+            schema_path,
+            value: Some(value.clone()),
+            kind,
+        }
+    }
+    /// The keyword value has an unexpected type or structure.
+    ///
+    /// It is intended for custom keywords that can't be compiled from the given value.
+    #[inline]
+    pub fn invalid_value(schema_path: JSONPointer, value: &Value) -> Self {
+        Self::new(schema_path, value, CompilationErrorKind::InvalidValue)
+    }
+    /// The schema itself is invalid, e.g. it is neither an object nor a boolean.
+    pub(crate) fn invalid_schema(schema_path: JSONPointer, value: &Value) -> Self {
+        CompilationError {
+            keyword: None,
+            schema_path,
+            value: Some(value.clone()),
+            kind: CompilationErrorKind::InvalidValue,
+        }
+    }
+    pub(crate) fn invalid_regex(
+        schema_path: JSONPointer,
+        value: &Value,
+        error: regex::Error,
+    ) -> Self {
+        Self::new(
+            schema_path,
+            value,
+            CompilationErrorKind::InvalidRegex { error },
+        )
+    }
+    /// Set the keyword explicitly if the error points inside its value, e.g. to a pattern
+    /// in `patternProperties`.
+    pub(crate) fn with_keyword(mut self, keyword: &str) -> Self {
+        self.keyword = Some(keyword.to_string());
+        self
+    }
+    pub(crate) fn invalid_url(
+        schema_path: JSONPointer,
+        value: &Value,
+        error: url::ParseError,
+    ) -> Self {
+        Self::new(
+            schema_path,
+            value,
+            CompilationErrorKind::InvalidURL { error },
+        )
+    }
+    pub(crate) fn unresolved_reference(
+        schema_path: JSONPointer,
+        reference: &str,
+        cause: SchemaResolverError,
+    ) -> Self {
+        Self::new(
+            schema_path,
+            &Value::String(reference.to_string()),
+            CompilationErrorKind::UnresolvedReference {
+                reference: reference.to_string(),
+                cause,
+            },
+        )
+    }
+    pub(crate) fn meta_schema(violations: Vec<SchemaViolation>) -> Self {
+        CompilationError {
+            keyword: None,
+            schema_path: JSONPointer::default(),
+            value: None,
+            kind: CompilationErrorKind::MetaSchema { violations },
+        }
     }
 }
 
-impl From<regex::Error> for CompilationError {
+impl error::Error for CompilationError {
     #[inline]
-    fn from(_: regex::Error) -> Self {
-        CompilationError::SchemaError
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.kind {
+            CompilationErrorKind::InvalidValue | CompilationErrorKind::MetaSchema { .. } => None,
+            CompilationErrorKind::UnresolvedReference { cause, .. } => Some(cause.as_ref()),
+            CompilationErrorKind::InvalidRegex { error } => Some(error),
+            CompilationErrorKind::InvalidURL { error } => Some(error),
+        }
     }
 }
+
+impl fmt::Display for CompilationError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "Schema compilation error")?;
+        if let Some(keyword) = &self.keyword {
+            write!(f, " in '{}'", keyword)?;
+        }
+        if !self.schema_path.is_empty() {
+            write!(f, " at '{}'", self.schema_path)?;
+        }
+        match &self.kind {
+            CompilationErrorKind::InvalidValue => {
+                if let Some(value) = &self.value {
+                    write!(f, ": {} is not a valid value", value)?;
+                }
+                Ok(())
+            }
+            CompilationErrorKind::InvalidRegex { error } => {
+                write!(f, ": invalid regular expression: {}", error)
+            }
+            CompilationErrorKind::InvalidURL { error } => write!(f, ": invalid URL: {}", error),
+            CompilationErrorKind::UnresolvedReference { reference, cause } => {
+                write!(f, ": unresolved reference '{}': {}", reference, cause)
+            }
+            CompilationErrorKind::MetaSchema { violations } => write!(
+                f,
+                ": the schema doesn't conform to its meta-schema: {}",
+                violations
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>()
+                    .join("; ")
+            ),
+        }
+    }
+}
+
 impl From<url::ParseError> for CompilationError {
     #[inline]
-    fn from(_: url::ParseError) -> Self {
-        CompilationError::SchemaError
+    fn from(error: url::ParseError) -> Self {
+        CompilationError {
+            keyword: None,
+            schema_path: JSONPointer::default(),
+            value: None,
+            kind: CompilationErrorKind::InvalidURL { error },
+        }
+    }
+}
+
+/// The error type that happens when documents can't be loaded into the store, e.g. via
+/// `CompilationOptions::with_directory`.
+///
+/// All documents are checked before it is returned, so it lists every problematic document.
+#[derive(Debug)]
+pub struct LoadError {
+    /// Documents that can't be loaded.
+    pub errors: Vec<DocumentError>,
+}
+
+/// A document that can't be loaded into the store.
+#[derive(Debug)]
+pub struct DocumentError {
+    /// Location of the document.
+    pub path: PathBuf,
+    /// Type of the problem.
+    pub kind: DocumentErrorKind,
+}
+
+/// Kinds of problems with documents that are loaded into the store.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum DocumentErrorKind {
+    /// The document can't be read.
+    FileNotFound { error: io::Error },
+    /// The document is not valid JSON.
+    JSONParse { error: serde_json::Error },
+    /// The document has no `$id`, so other schemas can't refer to it.
+    MissingId,
+    /// `$id` of the document is not a valid URL.
+    InvalidId { id: String, error: url::ParseError },
+    /// Another document has the same `$id`. `other` is its file or `None` if it is not loaded
+    /// from a file, e.g. it is added via `with_document` or it is a built-in meta-schema.
+    DuplicateId { id: String, other: Option<PathBuf> },
+    /// The catalog doesn't have the expected structure or refers to a document in an unsupported
+    /// location.
+    InvalidCatalog { message: String },
+}
+
+impl DocumentError {
+    pub(crate) const fn new(path: PathBuf, kind: DocumentErrorKind) -> Self {
+        DocumentError { path, kind }
+    }
+}
+
+impl error::Error for LoadError {}
+
+impl fmt::Display for LoadError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            f,
+            "Failed to load documents: {}",
+            self.errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join("; ")
+        )
+    }
+}
+
+impl error::Error for DocumentError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.kind {
+            DocumentErrorKind::FileNotFound { error } => Some(error),
+            DocumentErrorKind::JSONParse { error } => Some(error),
+            DocumentErrorKind::InvalidId { error, .. } => Some(error),
+            DocumentErrorKind::MissingId
+            | DocumentErrorKind::DuplicateId { .. }
+            | DocumentErrorKind::InvalidCatalog { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for DocumentError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "'{}': ", self.path.display())?;
+        match &self.kind {
+            DocumentErrorKind::FileNotFound { error } => write!(f, "{}", error),
+            DocumentErrorKind::JSONParse { error } => write!(f, "invalid JSON: {}", error),
+            DocumentErrorKind::MissingId => write!(f, "the schema has no '$id'"),
+            DocumentErrorKind::InvalidId { id, error } => {
+                write!(f, "invalid '$id' '{}': {}", id, error)
+            }
+            DocumentErrorKind::DuplicateId {
+                id,
+                other: Some(other),
+            } => {
+                write!(f, "'$id' '{}' is already used by '{}'", id, other.display())
+            }
+            DocumentErrorKind::DuplicateId { id, other: None } => {
+                write!(f, "'$id' '{}' is already used by another document", id)
+            }
+            DocumentErrorKind::InvalidCatalog { message } => {
+                write!(f, "invalid catalog: {}", message)
+            }
+        }
     }
 }
 
@@ -51,6 +370,14 @@ pub struct ValidationError<'a> {
     pub instance: Cow<'a, Value>,
     /// Type of validation error
     pub kind: ValidationErrorKind,
+    /// Path to the value that failed validation
+    pub instance_path: JSONPointer,
+    /// Path to the keyword that failed validation, e.g. `/properties/foo/$ref/required`.
+    /// References are not resolved in this path, they are kept as `$ref` chunks.
+    pub schema_path: JSONPointer,
+    /// Absolute location of the failed keyword after all references are resolved,
+    /// e.g. `http://example.com/schema.json#/definitions/foo/required`.
+    pub absolute_keyword_location: Option<Url>,
 }
 
 /// An iterator over instances of `ValidationError` that represent validation error for the
@@ -75,6 +402,79 @@ pub struct ValidationError<'a> {
 /// ```
 pub type ErrorIterator<'a> = Box<dyn Iterator<Item = ValidationError<'a>> + Sync + Send + 'a>;
 
+/// Pick the most relevant error, e.g. to show a single message for an instance.
+///
+/// Errors closer to the instance root are preferred, as well as errors from keywords other than
+/// `anyOf` and `oneOf`, which are weak because they don't say what exactly is wrong.
+/// Failed `anyOf` and `oneOf` are replaced with the most relevant error from their subschemas:
+/// the deepest one, as the instance likely was meant to match the subschema that got further,
+/// and preferably not a `type` mismatch. If errors from different subschemas are equally
+/// relevant, the `anyOf` / `oneOf` error itself is returned.
+///
+/// ```rust
+/// # use jsonschema::{best_match, JSONSchema};
+/// # use serde_json::json;
+/// let schema = json!({
+///     "anyOf": [
+///         {"type": "string"},
+///         {"type": "object", "properties": {"name": {"type": "string"}}}
+///     ]
+/// });
+/// let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+/// let instance = json!({"name": 42});
+/// let errors = compiled.validate(&instance).expect_err("Invalid instance");
+/// let error = best_match(errors).expect("At least one error");
+/// assert_eq!(error.instance_path.to_string(), "/name");
+/// ```
+pub fn best_match<'a, I>(errors: I) -> Option<ValidationError<'a>>
+where
+    I: IntoIterator<Item = ValidationError<'a>>,
+{
+    let mut best = errors.into_iter().min_by_key(|error| {
+        (
+            error.instance_path.chunks().len(),
+            is_weak_match(&error.kind),
+        )
+    })?;
+    while let ValidationErrorKind::AnyOf { context }
+    | ValidationErrorKind::OneOfNotValid { context } = &mut best.kind
+    {
+        let mut ranked: Vec<_> = context
+            .iter()
+            .enumerate()
+            .flat_map(|(branch, errors)| {
+                errors.iter().enumerate().map(move |(idx, error)| {
+                    let relevance = (
+                        Reverse(error.instance_path.chunks().len()),
+                        matches!(error.kind, ValidationErrorKind::Type { .. }),
+                        is_weak_match(&error.kind),
+                    );
+                    (relevance, branch, idx)
+                })
+            })
+            .collect();
+        ranked.sort_unstable();
+        match ranked.as_slice() {
+            [first, second, ..] if first.0 == second.0 && first.1 != second.1 => break,
+            [(_, branch, idx), ..] => {
+                let (branch, idx) = (*branch, *idx);
+                best = context.swap_remove(branch).swap_remove(idx);
+            }
+            [] => break,
+        }
+    }
+    Some(best)
+}
+
+const fn is_weak_match(kind: &ValidationErrorKind) -> bool {
+    matches!(
+        kind,
+        ValidationErrorKind::AnyOf { .. }
+            | ValidationErrorKind::OneOfNotValid { .. }
+            | ValidationErrorKind::OneOfMultipleValid { .. }
+    )
+}
+
 // Empty iterator means no error happened
 pub(crate) fn no_error<'a>() -> ErrorIterator<'a> {
     Box::new(empty())
@@ -85,13 +485,17 @@ pub(crate) fn error(instance: ValidationError) -> ErrorIterator {
 }
 
 /// Kinds of errors that may happen during validation
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[allow(missing_docs)]
 pub enum ValidationErrorKind {
     /// The input array contain more items than expected.
     AdditionalItems { limit: usize },
     /// The input value is not valid under any of the given schemas.
-    AnyOf,
+    /// `context` contains errors of each subschema, in the order of subschemas.
+    AnyOf {
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        context: Vec<Vec<ValidationError<'static>>>,
+    },
     /// The input value doesn't match expected constant.
     Constant { expected_value: Value },
     /// The input array doesn't contain items conforming to the specified schema.
@@ -109,19 +513,36 @@ pub enum ValidationErrorKind {
     /// Everything is invalid for `false` schema.
     FalseSchema,
     /// If the referenced file is not found during ref resolution.
-    FileNotFound { error: io::Error },
+    FileNotFound {
+        #[serde(serialize_with = "serialize_display")]
+        error: io::Error,
+    },
     /// When the input doesn't match to the specified format.
-    Format { format: &'static str },
+    Format { format: Cow<'static, str> },
     /// May happen in `contentEncoding` validation if `base64` encoded data is invalid.
-    FromUtf8 { error: FromUtf8Error },
+    FromUtf8 {
+        #[serde(serialize_with = "serialize_display")]
+        error: FromUtf8Error,
+    },
     /// Invalid UTF-8 string during percent encoding when resolving happens
-    Utf8 { error: Utf8Error },
+    Utf8 {
+        #[serde(serialize_with = "serialize_display")]
+        error: Utf8Error,
+    },
     /// May happen during ref resolution when remote document is not a valid JSON.
-    JSONParse { error: serde_json::Error },
+    JSONParse {
+        #[serde(serialize_with = "serialize_display")]
+        error: serde_json::Error,
+    },
     /// `ref` value is not valid.
     InvalidReference { reference: String },
     /// Invalid URL, e.g. invalid port number or IP address
-    InvalidURL { error: url::ParseError },
+    InvalidURL {
+        #[serde(serialize_with = "serialize_display")]
+        error: url::ParseError,
+    },
+    /// Too many items in an array match the `contains` subschema.
+    MaxContains { limit: u64 },
     /// Too many items in an array.
     MaxItems { limit: u64 },
     /// Value is too large.
@@ -130,6 +551,8 @@ pub enum ValidationErrorKind {
     MaxLength { limit: u64 },
     /// Too many properties in an object.
     MaxProperties { limit: u64 },
+    /// Too few items in an array match the `contains` subschema.
+    MinContains { limit: u64 },
     /// Too few items in an array.
     MinItems { limit: u64 },
     /// Value is too small.
@@ -141,64 +564,136 @@ pub enum ValidationErrorKind {
     /// When some number is not a multiple of another number.
     MultipleOf { multiple_of: f64 },
     /// Negated schema failed validation.
+    /// It has no nested errors, because the error means that `schema` is valid.
     Not { schema: Value },
     /// The given schema is valid under more than one of the given schemas.
-    OneOfMultipleValid,
+    /// `matched` contains indices of all valid subschemas.
+    OneOfMultipleValid { matched: Vec<usize> },
     /// The given schema is not valid under any on the given schemas.
-    OneOfNotValid,
+    /// `context` contains errors of each subschema, in the order of subschemas.
+    OneOfNotValid {
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        context: Vec<Vec<ValidationError<'static>>>,
+    },
     /// When the input doesn't match to a pattern.
     Pattern { pattern: String },
     /// When a required property is missing.
     Required { property: String },
     /// Any error that happens during network request via `reqwest` crate
     #[cfg(any(feature = "reqwest", test))]
-    Reqwest { error: reqwest::Error },
+    Reqwest {
+        #[serde(serialize_with = "serialize_display")]
+        error: reqwest::Error,
+    },
+    /// A custom `SchemaResolver` failed to load a document.
+    Resolver {
+        url: Url,
+        #[serde(serialize_with = "serialize_display")]
+        error: SchemaResolverError,
+    },
     /// Resolved schema failed to compile.
     Schema,
     /// When the input value doesn't match one or multiple required types.
     Type { kind: TypeKind },
+    /// The input array has items that are not evaluated by any subschema and not allowed.
+    UnevaluatedItems { unexpected: Vec<usize> },
+    /// The input object has properties that are not evaluated by any subschema and not allowed.
+    UnevaluatedProperties { unexpected: Vec<String> },
     /// When the input array has non-unique elements.
     UniqueItems,
     /// Reference contains unknown scheme.
     UnknownReferenceScheme { scheme: String },
 
+    /// A custom keyword failed validation.
+    Custom { message: String },
+
     /// Unexpected error. This usually represent a bug into the validation
     Unexpected { validator_representation: String },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 #[allow(missing_docs)]
 pub enum TypeKind {
     Single(PrimitiveType),
     Multiple(PrimitiveTypesBitMap),
 }
 
+/// Errors from other crates are serialized via their `Display` implementations.
+fn serialize_display<T: fmt::Display, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
 /// Shortcuts for creation of specific error kinds.
 impl<'a> ValidationError<'a> {
+    /// Locate the error and errors of subschemas in `context` within the schema at `location`,
+    /// where they are created, and prefix their schema paths with `schema_path`.
+    pub(crate) fn rebase(&mut self, location: &Url, schema_path: &JSONPointer) {
+        if self.absolute_keyword_location.is_none() {
+            self.absolute_keyword_location = Some(self.schema_path.to_url(location));
+        }
+        if !schema_path.is_empty() {
+            self.schema_path = schema_path.extend_with(self.schema_path.chunks());
+        }
+        if let ValidationErrorKind::AnyOf { context }
+        | ValidationErrorKind::OneOfNotValid { context } = &mut self.kind
+        {
+            for error in context.iter_mut().flatten() {
+                error.rebase(location, schema_path);
+            }
+        }
+    }
+
     pub(crate) fn into_owned(self) -> ValidationError<'static> {
         ValidationError {
+            instance_path: self.instance_path,
+            schema_path: self.schema_path,
+            absolute_keyword_location: self.absolute_keyword_location,
             instance: Cow::Owned(self.instance.into_owned()),
             kind: self.kind,
         }
     }
 
-    pub(crate) fn additional_items(instance: &'a Value, limit: usize) -> ValidationError<'a> {
+    pub(crate) fn additional_items(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: usize,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::AdditionalItems { limit },
         }
     }
-    pub(crate) fn any_of(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn any_of(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        context: Vec<Vec<ValidationError<'static>>>,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::AnyOf,
+            kind: ValidationErrorKind::AnyOf { context },
         }
     }
     pub(crate) fn constant_array(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
         instance: &'a Value,
         expected_value: &[Value],
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Array(expected_value.to_vec()),
@@ -206,18 +701,30 @@ impl<'a> ValidationError<'a> {
         }
     }
     pub(crate) fn constant_boolean(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
         instance: &'a Value,
         expected_value: bool,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Bool(expected_value),
             },
         }
     }
-    pub(crate) fn constant_null(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn constant_null(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Null,
@@ -225,10 +732,15 @@ impl<'a> ValidationError<'a> {
         }
     }
     pub(crate) fn constant_number(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
         instance: &'a Value,
         expected_value: &Number,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Number(expected_value.clone()),
@@ -236,10 +748,15 @@ impl<'a> ValidationError<'a> {
         }
     }
     pub(crate) fn constant_object(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
         instance: &'a Value,
         expected_value: &Map<String, Value>,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::Object(expected_value.clone()),
@@ -247,180 +764,404 @@ impl<'a> ValidationError<'a> {
         }
     }
     pub(crate) fn constant_string(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
         instance: &'a Value,
         expected_value: &str,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: Value::String(expected_value.to_string()),
             },
         }
     }
-    pub(crate) fn contains(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn contains(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Contains,
         }
     }
-    pub(crate) fn content_encoding(instance: &'a Value, encoding: &str) -> ValidationError<'a> {
+    pub(crate) fn content_encoding(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        encoding: &str,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::ContentEncoding {
                 content_encoding: encoding.to_string(),
             },
         }
     }
-    pub(crate) fn content_media_type(instance: &'a Value, media_type: &str) -> ValidationError<'a> {
+    pub(crate) fn content_media_type(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        media_type: &str,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::ContentMediaType {
                 content_media_type: media_type.to_string(),
             },
         }
     }
-    pub(crate) fn enumeration(instance: &'a Value, options: &Value) -> ValidationError<'a> {
+    pub(crate) fn enumeration(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        options: &Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Enum {
                 options: options.clone(),
             },
         }
     }
-    pub(crate) fn exclusive_maximum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn exclusive_maximum(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: f64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::ExclusiveMaximum { limit },
         }
     }
-    pub(crate) fn exclusive_minimum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn exclusive_minimum(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: f64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::ExclusiveMinimum { limit },
         }
     }
-    pub(crate) fn false_schema(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn false_schema(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::FalseSchema,
         }
     }
     pub(crate) fn file_not_found(error: io::Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::FileNotFound { error },
         }
     }
-    pub(crate) fn format(instance: &'a Value, format: &'static str) -> ValidationError<'a> {
+    pub(crate) fn format(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        format: impl Into<Cow<'static, str>>,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::Format { format },
+            kind: ValidationErrorKind::Format {
+                format: format.into(),
+            },
         }
     }
     pub(crate) fn from_utf8(error: FromUtf8Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::FromUtf8 { error },
         }
     }
     pub(crate) fn json_parse(error: serde_json::Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::JSONParse { error },
         }
     }
     pub(crate) fn invalid_reference(reference: String) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::InvalidReference { reference },
         }
     }
     pub(crate) fn invalid_url(error: url::ParseError) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::InvalidURL { error },
         }
     }
-    pub(crate) fn max_items(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn max_contains(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::MaxContains { limit },
+        }
+    }
+    pub(crate) fn max_items(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MaxItems { limit },
         }
     }
-    pub(crate) fn maximum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn maximum(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: f64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Maximum { limit },
         }
     }
-    pub(crate) fn max_length(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn max_length(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MaxLength { limit },
         }
     }
-    pub(crate) fn max_properties(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn max_properties(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MaxProperties { limit },
         }
     }
-    pub(crate) fn min_items(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn min_contains(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::MinContains { limit },
+        }
+    }
+    pub(crate) fn min_items(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MinItems { limit },
         }
     }
-    pub(crate) fn minimum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn minimum(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: f64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Minimum { limit },
         }
     }
-    pub(crate) fn min_length(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn min_length(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MinLength { limit },
         }
     }
-    pub(crate) fn min_properties(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn min_properties(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MinProperties { limit },
         }
     }
-    pub(crate) fn multiple_of(instance: &'a Value, multiple_of: f64) -> ValidationError<'a> {
+    pub(crate) fn multiple_of(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        multiple_of: f64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MultipleOf { multiple_of },
         }
     }
-    pub(crate) fn not(instance: &'a Value, schema: Value) -> ValidationError<'a> {
+    pub(crate) fn not(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        schema: Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Not { schema },
         }
     }
-    pub(crate) fn one_of_multiple_valid(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn one_of_multiple_valid(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        matched: Vec<usize>,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::OneOfMultipleValid,
+            kind: ValidationErrorKind::OneOfMultipleValid { matched },
         }
     }
-    pub(crate) fn one_of_not_valid(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn one_of_not_valid(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        context: Vec<Vec<ValidationError<'static>>>,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
-            kind: ValidationErrorKind::OneOfNotValid,
+            kind: ValidationErrorKind::OneOfNotValid { context },
         }
     }
-    pub(crate) fn pattern(instance: &'a Value, pattern: String) -> ValidationError<'a> {
+    pub(crate) fn pattern(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        pattern: String,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Pattern { pattern },
         }
     }
-    pub(crate) fn required(instance: &'a Value, property: String) -> ValidationError<'a> {
+    pub(crate) fn required(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        property: String,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Required { property },
         }
@@ -428,21 +1169,41 @@ impl<'a> ValidationError<'a> {
     #[cfg(any(feature = "reqwest", test))]
     pub(crate) fn reqwest(error: reqwest::Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::Reqwest { error },
         }
     }
+    pub(crate) fn resolver(url: Url, error: SchemaResolverError) -> ValidationError<'a> {
+        ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
+            instance: Cow::Owned(Value::Null),
+            kind: ValidationErrorKind::Resolver { url, error },
+        }
+    }
     pub(crate) fn schema() -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::Schema,
         }
     }
     pub(crate) fn single_type_error(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
         instance: &'a Value,
         type_name: PrimitiveType,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Type {
                 kind: TypeKind::Single(type_name),
@@ -450,33 +1211,99 @@ impl<'a> ValidationError<'a> {
         }
     }
     pub(crate) fn multiple_type_error(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
         instance: &'a Value,
         types: PrimitiveTypesBitMap,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Type {
                 kind: TypeKind::Multiple(types),
             },
         }
     }
-    pub(crate) fn unique_items(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn unevaluated_items(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        unexpected: Vec<usize>,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::UnevaluatedItems { unexpected },
+        }
+    }
+    pub(crate) fn unevaluated_properties(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        unexpected: Vec<String>,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::UnevaluatedProperties { unexpected },
+        }
+    }
+    pub(crate) fn unique_items(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::UniqueItems,
         }
     }
     pub(crate) fn unknown_reference_scheme(scheme: String) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::UnknownReferenceScheme { scheme },
         }
     }
+    /// Error from a custom keyword, see `Keyword`.
+    ///
+    /// The message is used as the textual representation of the error.
+    #[inline]
+    pub fn custom(
+        schema_path: JSONPointer,
+        instance_path: JSONPointer,
+        instance: &'a Value,
+        message: impl Into<String>,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path,
+            schema_path,
+            absolute_keyword_location: None,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::Custom {
+                message: message.into(),
+            },
+        }
+    }
     /// Unexpected `ValidationError`
     ///
-    /// This validation error is the only `ValidationError` that can be created by external crates.
+    /// Along with `ValidationError::custom` it can be created by external crates.
     pub fn unexpected(instance: &'a Value, validator_representation: &str) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Unexpected {
                 validator_representation: validator_representation.to_string(),
@@ -485,6 +1312,9 @@ impl<'a> ValidationError<'a> {
     }
     pub(crate) fn utf8(error: Utf8Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::Utf8 { error },
         }
@@ -536,6 +1366,24 @@ impl From<reqwest::Error> for ValidationError<'_> {
     }
 }
 
+/// Errors nested in `anyOf` and `oneOf` failures are serialized with the same fields as
+/// `OutputUnit` errors.
+impl Serialize for ValidationError<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ValidationError", 5)?;
+        state.serialize_field("keywordLocation", &self.schema_path)?;
+        if let Some(location) = &self.absolute_keyword_location {
+            state.serialize_field("absoluteKeywordLocation", location)?;
+        } else {
+            state.skip_field("absoluteKeywordLocation")?;
+        }
+        state.serialize_field("instanceLocation", &self.instance_path)?;
+        state.serialize_field("error", &self.to_string())?;
+        state.serialize_field("kind", &self.kind)?;
+        state.end()
+    }
+}
+
 /// Textual representation of various validation errors.
 impl fmt::Display for ValidationError<'_> {
     #[allow(clippy::too_many_lines)] // The function is long but it does formatting only
@@ -543,6 +1391,9 @@ impl fmt::Display for ValidationError<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match &self.kind {
             ValidationErrorKind::Schema => write!(f, "Schema error"),
+            ValidationErrorKind::Resolver { url, error } => {
+                write!(f, "Failed to resolve {}: {}", url, error)
+            }
             ValidationErrorKind::JSONParse { error } => write!(f, "{}", error),
             #[cfg(any(feature = "reqwest", test))]
             ValidationErrorKind::Reqwest { error } => write!(f, "{}", error),
@@ -582,7 +1433,7 @@ impl fmt::Display for ValidationError<'_> {
                     verb
                 )
             }
-            ValidationErrorKind::AnyOf | ValidationErrorKind::OneOfNotValid => write!(
+            ValidationErrorKind::AnyOf { .. } | ValidationErrorKind::OneOfNotValid { .. } => write!(
                 f,
                 "'{}' is not valid under any of the given schemas",
                 self.instance
@@ -644,6 +1495,20 @@ impl fmt::Display for ValidationError<'_> {
                 limit,
                 if *limit == 1 { "" } else { "s" }
             ),
+            ValidationErrorKind::MaxContains { limit } => write!(
+                f,
+                "{} contains more than {} matching item{}",
+                self.instance,
+                limit,
+                if *limit == 1 { "" } else { "s" }
+            ),
+            ValidationErrorKind::MinContains { limit } => write!(
+                f,
+                "{} contains less than {} matching item{}",
+                self.instance,
+                limit,
+                if *limit == 1 { "" } else { "s" }
+            ),
             ValidationErrorKind::MaxItems { limit } => write!(
                 f,
                 "{} has more than {} item{}",
@@ -675,7 +1540,7 @@ impl fmt::Display for ValidationError<'_> {
             ValidationErrorKind::Not { schema } => {
                 write!(f, "{} is not allowed for {}", schema, self.instance)
             }
-            ValidationErrorKind::OneOfMultipleValid => write!(
+            ValidationErrorKind::OneOfMultipleValid { .. } => write!(
                 f,
                 "'{}' is valid under more than one of the given schemas",
                 self.instance
@@ -689,6 +1554,35 @@ impl fmt::Display for ValidationError<'_> {
             ValidationErrorKind::MultipleOf { multiple_of } => {
                 write!(f, "{} is not a multiple of {}", self.instance, multiple_of)
             }
+            ValidationErrorKind::UnevaluatedItems { unexpected } => {
+                // It's safe to unwrap here as ValidationErrorKind::UnevaluatedItems is reported
+                // only for arrays
+                let items = self.instance.as_array().expect("Always valid");
+                let verb = if unexpected.len() == 1 { "was" } else { "were" };
+                write!(
+                    f,
+                    "Unevaluated items are not allowed ({} {} unexpected)",
+                    unexpected
+                        .iter()
+                        .map(|idx| items[*idx].to_string())
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    verb
+                )
+            }
+            ValidationErrorKind::UnevaluatedProperties { unexpected } => {
+                let verb = if unexpected.len() == 1 { "was" } else { "were" };
+                write!(
+                    f,
+                    "Unevaluated properties are not allowed ({} {} unexpected)",
+                    unexpected
+                        .iter()
+                        .map(|property| format!("'{}'", property))
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    verb
+                )
+            }
             ValidationErrorKind::UniqueItems => {
                 write!(f, "'{}' has non-unique elements", self.instance)
             }
@@ -707,6 +1601,7 @@ impl fmt::Display for ValidationError<'_> {
                     .collect::<Vec<String>>()
                     .join(", ")
             ),
+            ValidationErrorKind::Custom { message } => f.write_str(message),
             ValidationErrorKind::Unexpected { validator_representation } => write!(
                 f,
                 "Unexpected validation error. Usually this reflect a bug in the keywords implementation. Please make sure to report the problem to {}. Instance: {}, Validator: {}",
@@ -722,11 +1617,17 @@ impl fmt::Display for ValidationError<'_> {
 mod tests {
     use super::*;
     use serde_json::json;
+    use test_case::test_case;
 
     #[test]
     fn single_type_error() {
         let instance = json!(42);
-        let err = ValidationError::single_type_error(&instance, PrimitiveType::String);
+        let err = ValidationError::single_type_error(
+            JSONPointer::default(),
+            JSONPointer::default(),
+            &instance,
+            PrimitiveType::String,
+        );
         assert_eq!(err.to_string(), "'42' is not of type 'string'")
     }
 
@@ -734,9 +1635,59 @@ mod tests {
     fn multiple_types_error() {
         let instance = json!(42);
         let err = ValidationError::multiple_type_error(
+            JSONPointer::default(),
+            JSONPointer::default(),
             &instance,
             vec![PrimitiveType::String, PrimitiveType::Number].into(),
         );
         assert_eq!(err.to_string(), "'42' is not of types 'number', 'string'")
     }
+
+    #[test_case(
+        &json!({"anyOf": [{"type": "string"}, {"type": "object", "properties": {"name": {"type": "string"}}}]}),
+        &json!({"name": 42}),
+        "/anyOf/1/properties/name/type";
+        "deepest branch error"
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"type": "string"}, {"type": "integer"}]}),
+        &json!(null),
+        "/anyOf";
+        "equally relevant branches"
+    )]
+    #[test_case(
+        &json!({"oneOf": [{"type": "string"}, {"minimum": 5}]}),
+        &json!(1),
+        "/oneOf/1/minimum";
+        "type mismatches are less relevant"
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"type": "string"}, {"anyOf": [{"type": "null"}, {"minimum": 5}]}]}),
+        &json!(1),
+        "/anyOf/1/anyOf/1/minimum";
+        "nested branches"
+    )]
+    #[test_case(
+        &json!({"properties": {"a": {"minimum": 2}}, "required": ["b"]}),
+        &json!({"a": 1}),
+        "/required";
+        "shallow errors are preferred"
+    )]
+    #[test_case(
+        &json!({"anyOf": [{"type": "string"}], "minimum": 2}),
+        &json!(1),
+        "/minimum";
+        "weak errors are less relevant"
+    )]
+    fn best_match(schema: &Value, instance: &Value, expected: &str) {
+        let compiled = crate::JSONSchema::compile(schema).unwrap();
+        let errors = compiled.validate(instance).unwrap_err();
+        let error = super::best_match(errors).expect("Validation failed");
+        assert_eq!(error.schema_path.to_string(), expected);
+    }
+
+    #[test]
+    fn best_match_no_errors() {
+        assert!(super::best_match(empty()).is_none());
+    }
 }
@@ -5,7 +5,8 @@ use crate::{
         boolean::{FalseValidator, TrueValidator},
         format_validators, CompilationResult, Validators,
     },
-    validator::Validate,
+    paths::{InstancePath, JSONPointer},
+    validator::{annotate_subschema, Annotations, Evaluated, Validate},
 };
 use serde_json::{Map, Value};
 
@@ -40,15 +41,22 @@ impl Validate for AdditionalItemsObjectValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
             let errors: Vec<_> = items
                 .iter()
+                .enumerate()
                 .skip(self.items_count)
-                .flat_map(|item| {
+                .flat_map(|(idx, item)| {
+                    let instance_path = instance_path.push(idx);
                     self.validators
                         .iter()
-                        .flat_map(move |validator| validator.validate(schema, item))
+                        .flat_map(move |validator| validator.validate(schema, item, &instance_path))
                 })
                 .collect();
             Box::new(errors.into_iter())
@@ -56,6 +64,32 @@ impl Validate for AdditionalItemsObjectValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.items_from(instance, self.items_count)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate().skip(self.items_count) {
+                valid &= annotate_subschema(
+                    &self.validators,
+                    schema,
+                    item,
+                    &instance_path.push(idx),
+                    annotations,
+                );
+            }
+        }
+        valid
+    }
 }
 impl ToString for AdditionalItemsObjectValidator {
     fn to_string(&self) -> String {
@@ -65,11 +99,15 @@ impl ToString for AdditionalItemsObjectValidator {
 
 pub(crate) struct AdditionalItemsBooleanValidator {
     items_count: usize,
+    schema_path: JSONPointer,
 }
 impl AdditionalItemsBooleanValidator {
     #[inline]
-    pub(crate) fn compile(items_count: usize) -> CompilationResult {
-        Ok(Box::new(AdditionalItemsBooleanValidator { items_count }))
+    pub(crate) fn compile(items_count: usize, schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(AdditionalItemsBooleanValidator {
+            items_count,
+            schema_path,
+        }))
     }
 }
 impl Validate for AdditionalItemsBooleanValidator {
@@ -82,10 +120,17 @@ impl Validate for AdditionalItemsBooleanValidator {
         true
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
             if items.len() > self.items_count {
                 return error(ValidationError::additional_items(
+                    self.schema_path.clone(),
+                    instance_path.into(),
                     instance,
                     self.items_count,
                 ));
@@ -107,6 +152,7 @@ pub(crate) fn compile(
     context: &CompilationContext,
 ) -> Option<CompilationResult> {
     if let Some(items) = parent.get("items") {
+        let keyword_context = context.with_path("additionalItems");
         match items {
             Value::Object(_) => Some(TrueValidator::compile()),
             Value::Array(items) => {
@@ -115,12 +161,20 @@ pub(crate) fn compile(
                     Value::Object(_) => Some(AdditionalItemsObjectValidator::compile(
                         schema,
                         items_count,
-                        context,
+                        &keyword_context,
                     )),
-                    Value::Bool(true) => Some(TrueValidator::compile()),
-                    Value::Bool(false) => {
-                        Some(AdditionalItemsBooleanValidator::compile(items_count))
+                    Value::Bool(true) if context.config.draft().collects_annotations() => {
+                        Some(AdditionalItemsObjectValidator::compile(
+                            schema,
+                            items_count,
+                            &keyword_context,
+                        ))
                     }
+                    Value::Bool(true) => Some(TrueValidator::compile()),
+                    Value::Bool(false) => Some(AdditionalItemsBooleanValidator::compile(
+                        items_count,
+                        keyword_context.into_pointer(),
+                    )),
                     _ => None,
                 }
             }
@@ -128,10 +182,13 @@ pub(crate) fn compile(
                 if *value {
                     Some(TrueValidator::compile())
                 } else {
-                    Some(FalseValidator::compile())
+                    Some(FalseValidator::compile(keyword_context.into_pointer()))
                 }
             }
-            _ => Some(Err(CompilationError::SchemaError)),
+            _ => Some(Err(CompilationError::invalid_value(
+                context.as_pointer_with("items"),
+                items,
+            ))),
         }
     } else {
         Some(TrueValidator::compile())
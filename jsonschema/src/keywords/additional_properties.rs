@@ -9,8 +9,9 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
-    keywords::{format_validators, CompilationResult, Validators},
-    validator::Validate,
+    keywords::{format_validators, properties::property_defaults, CompilationResult, Validators},
+    paths::{InstancePath, JSONPointer},
+    validator::{annotate_subschema, Annotations, Evaluated, Validate},
 };
 use ahash::AHashMap;
 use regex::Regex;
@@ -57,17 +58,25 @@ macro_rules! is_valid_patterns {
 }
 
 macro_rules! validate {
-    ($validators:expr, $schema:ident, $value:ident) => {{
+    ($validators:expr, $schema:ident, $value:ident, $instance_path:expr) => {{
+        let instance_path = $instance_path;
         $validators
             .iter()
-            .flat_map(move |validator| validator.validate($schema, $value))
+            .flat_map(move |validator| validator.validate($schema, $value, instance_path))
     }};
 }
 
 macro_rules! disallow_property {
-    ($errors:ident, $property:ident) => {{
+    ($errors:ident, $property:ident, $instance_path:expr, $schema_path:expr) => {{
         let property_value = Value::String($property.to_string());
-        $errors.push(ValidationError::false_schema(&property_value).into_owned());
+        $errors.push(
+            ValidationError::false_schema(
+                $schema_path.clone(),
+                $instance_path.into(),
+                &property_value,
+            )
+            .into_owned(),
+        );
     }};
 }
 
@@ -75,13 +84,63 @@ fn compile_properties(
     map: &Map<String, Value>,
     context: &CompilationContext,
 ) -> Result<AHashMap<String, Validators>, CompilationError> {
+    let keyword_context = context.with_path("properties");
     let mut properties = AHashMap::with_capacity(map.len());
     for (key, subschema) in map {
-        properties.insert(key.clone(), compile_validators(subschema, context)?);
+        let property_context = keyword_context.with_path(key.as_str());
+        properties.insert(
+            key.clone(),
+            compile_validators(subschema, &property_context)?,
+        );
     }
     Ok(properties)
 }
 
+/// Collect annotations from `properties`, `patternProperties` and `additionalProperties`
+/// subschemas that apply to each property, and `default` values of missing properties.
+/// Without `additional` subschemas, properties that don't match other ones are not allowed.
+/// Returns whether the instance is valid.
+#[allow(clippy::too_many_arguments)]
+fn annotate_properties(
+    schema: &JSONSchema,
+    instance: &Value,
+    instance_path: &InstancePath,
+    annotations: &mut Annotations,
+    properties: Option<&AHashMap<String, Validators>>,
+    defaults: &[(String, Value)],
+    patterns: &[(Regex, Validators)],
+    additional: Option<&Validators>,
+) -> bool {
+    let mut valid = true;
+    if let Value::Object(item) = instance {
+        for (property, value) in item {
+            let property_path = instance_path.push(property.clone());
+            let mut has_match = false;
+            if let Some(validators) = properties.and_then(|properties| properties.get(property)) {
+                has_match = true;
+                valid &= annotate_subschema(validators, schema, value, &property_path, annotations);
+            }
+            for (re, validators) in patterns {
+                if re.is_match(property) {
+                    has_match = true;
+                    valid &=
+                        annotate_subschema(validators, schema, value, &property_path, annotations);
+                }
+            }
+            if !has_match {
+                valid &= match additional {
+                    Some(validators) => {
+                        annotate_subschema(validators, schema, value, &property_path, annotations)
+                    }
+                    None => false,
+                };
+            }
+        }
+    }
+    annotations.missing_properties(defaults, instance, instance_path);
+    valid
+}
+
 /// # Schema example
 ///
 /// ```json
@@ -104,7 +163,7 @@ impl AdditionalPropertiesValidator {
     #[inline]
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         Ok(Box::new(AdditionalPropertiesValidator {
-            validators: compile_validators(schema, context)?,
+            validators: compile_validators(schema, &context.with_path("additionalProperties"))?,
         }))
     }
 }
@@ -120,17 +179,50 @@ impl Validate for AdditionalPropertiesValidator {
         true
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             let errors: Vec<_> = item
-                .values()
-                .flat_map(|value| validate!(&self.validators, schema, value))
+                .iter()
+                .flat_map(|(property, value)| {
+                    let property_path = instance_path.push(property.clone());
+                    self.validators.iter().flat_map(move |validator| {
+                        validator.validate(schema, value, &property_path)
+                    })
+                })
                 .collect();
             Box::new(errors.into_iter())
         } else {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.all_properties(instance)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotate_properties(
+            schema,
+            instance,
+            instance_path,
+            annotations,
+            None,
+            &[],
+            &[],
+            Some(&self.validators),
+        )
+    }
 }
 
 impl ToString for AdditionalPropertiesValidator {
@@ -155,11 +247,13 @@ impl ToString for AdditionalPropertiesValidator {
 /// ```json
 /// {}
 /// ```
-pub(crate) struct AdditionalPropertiesFalseValidator {}
+pub(crate) struct AdditionalPropertiesFalseValidator {
+    schema_path: JSONPointer,
+}
 impl AdditionalPropertiesFalseValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(AdditionalPropertiesFalseValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(AdditionalPropertiesFalseValidator { schema_path }))
     }
 }
 impl Validate for AdditionalPropertiesFalseValidator {
@@ -171,14 +265,28 @@ impl Validate for AdditionalPropertiesFalseValidator {
         }
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
-            if let Some((_, value)) = item.iter().next() {
-                return error(ValidationError::false_schema(value));
+            if let Some((property, value)) = item.iter().next() {
+                let property_path = instance_path.push(property.clone());
+                return error(ValidationError::false_schema(
+                    self.schema_path.clone(),
+                    (&property_path).into(),
+                    value,
+                ));
             }
         }
         no_error()
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.all_properties(instance)
+    }
 }
 impl ToString for AdditionalPropertiesFalseValidator {
     fn to_string(&self) -> String {
@@ -206,6 +314,8 @@ impl ToString for AdditionalPropertiesFalseValidator {
 /// ```
 pub(crate) struct AdditionalPropertiesNotEmptyFalseValidator {
     properties: AHashMap<String, Validators>,
+    defaults: Vec<(String, Value)>,
+    schema_path: JSONPointer,
 }
 impl AdditionalPropertiesNotEmptyFalseValidator {
     #[inline]
@@ -213,8 +323,13 @@ impl AdditionalPropertiesNotEmptyFalseValidator {
         match properties {
             Value::Object(map) => Ok(Box::new(AdditionalPropertiesNotEmptyFalseValidator {
                 properties: compile_properties(map, context)?,
+                defaults: property_defaults(map),
+                schema_path: context.as_pointer_with("additionalProperties"),
             })),
-            _ => Err(CompilationError::SchemaError),
+            _ => Err(CompilationError::invalid_value(
+                context.as_pointer_with("properties"),
+                properties,
+            )),
         }
     }
 }
@@ -233,16 +348,22 @@ impl Validate for AdditionalPropertiesNotEmptyFalseValidator {
         true
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             let mut errors = vec![];
             for (property, value) in item {
+                let property_path = instance_path.push(property.clone());
                 if let Some(validators) = self.properties.get(property) {
                     // When a property is in `properties`, then it should be VALID
-                    errors.extend(validate!(validators, schema, value));
+                    errors.extend(validate!(validators, schema, value, &property_path));
                 } else {
                     // No extra properties are allowed
-                    disallow_property!(errors, property)
+                    disallow_property!(errors, property, &property_path, self.schema_path)
                 }
             }
             Box::new(errors.into_iter())
@@ -250,6 +371,29 @@ impl Validate for AdditionalPropertiesNotEmptyFalseValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.all_properties(instance)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotate_properties(
+            schema,
+            instance,
+            instance_path,
+            annotations,
+            Some(&self.properties),
+            &self.defaults,
+            &[],
+            None,
+        )
+    }
 }
 
 impl ToString for AdditionalPropertiesNotEmptyFalseValidator {
@@ -280,6 +424,7 @@ impl ToString for AdditionalPropertiesNotEmptyFalseValidator {
 pub(crate) struct AdditionalPropertiesNotEmptyValidator {
     validators: Validators,
     properties: AHashMap<String, Validators>,
+    defaults: Vec<(String, Value)>,
 }
 impl AdditionalPropertiesNotEmptyValidator {
     #[inline]
@@ -291,10 +436,14 @@ impl AdditionalPropertiesNotEmptyValidator {
         if let Value::Object(map) = properties {
             Ok(Box::new(AdditionalPropertiesNotEmptyValidator {
                 properties: compile_properties(map, context)?,
-                validators: compile_validators(schema, context)?,
+                defaults: property_defaults(map),
+                validators: compile_validators(schema, &context.with_path("additionalProperties"))?,
             }))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(
+                context.as_pointer_with("properties"),
+                properties,
+            ))
         }
     }
 }
@@ -316,14 +465,25 @@ impl Validate for AdditionalPropertiesNotEmptyValidator {
         true
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(map) = instance {
             let mut errors = vec![];
             for (property, value) in map {
+                let property_path = instance_path.push(property.clone());
                 if let Some(property_validators) = self.properties.get(property) {
-                    errors.extend(validate!(property_validators, schema, value))
+                    errors.extend(validate!(
+                        property_validators,
+                        schema,
+                        value,
+                        &property_path
+                    ))
                 } else {
-                    errors.extend(validate!(self.validators, schema, value))
+                    errors.extend(validate!(self.validators, schema, value, &property_path))
                 }
             }
             Box::new(errors.into_iter())
@@ -331,6 +491,29 @@ impl Validate for AdditionalPropertiesNotEmptyValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.all_properties(instance)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotate_properties(
+            schema,
+            instance,
+            instance_path,
+            annotations,
+            Some(&self.properties),
+            &self.defaults,
+            &[],
+            Some(&self.validators),
+        )
+    }
 }
 
 impl ToString for AdditionalPropertiesNotEmptyValidator {
@@ -375,7 +558,7 @@ impl AdditionalPropertiesWithPatternsValidator {
         context: &CompilationContext,
     ) -> CompilationResult {
         Ok(Box::new(AdditionalPropertiesWithPatternsValidator {
-            validators: compile_validators(schema, context)?,
+            validators: compile_validators(schema, &context.with_path("additionalProperties"))?,
             patterns,
         }))
     }
@@ -399,10 +582,16 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
         true
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             let mut errors = vec![];
             for (property, value) in item.iter() {
+                let property_path = instance_path.push(property.clone());
                 let mut has_match = false;
                 errors.extend(
                     self.patterns
@@ -410,11 +599,11 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
                         .filter(|(re, _)| re.is_match(property))
                         .flat_map(|(_, validators)| {
                             has_match = true;
-                            validate!(validators, schema, value)
+                            validate!(validators, schema, value, &property_path)
                         }),
                 );
                 if !has_match {
-                    errors.extend(validate!(self.validators, schema, value))
+                    errors.extend(validate!(self.validators, schema, value, &property_path))
                 }
             }
             Box::new(errors.into_iter())
@@ -422,6 +611,29 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.all_properties(instance)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotate_properties(
+            schema,
+            instance,
+            instance_path,
+            annotations,
+            None,
+            &[],
+            &self.patterns,
+            Some(&self.validators),
+        )
+    }
 }
 
 impl ToString for AdditionalPropertiesWithPatternsValidator {
@@ -456,12 +668,17 @@ impl ToString for AdditionalPropertiesWithPatternsValidator {
 /// ```
 pub(crate) struct AdditionalPropertiesWithPatternsFalseValidator {
     patterns: PatternedValidators,
+    schema_path: JSONPointer,
 }
 impl AdditionalPropertiesWithPatternsFalseValidator {
     #[inline]
-    pub(crate) fn compile(patterns: PatternedValidators) -> CompilationResult {
+    pub(crate) fn compile(
+        patterns: PatternedValidators,
+        schema_path: JSONPointer,
+    ) -> CompilationResult {
         Ok(Box::new(AdditionalPropertiesWithPatternsFalseValidator {
             patterns,
+            schema_path,
         }))
     }
 }
@@ -476,10 +693,16 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
         true
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             let mut errors = vec![];
             for (property, value) in item {
+                let property_path = instance_path.push(property.clone());
                 let mut has_match = false;
                 errors.extend(
                     self.patterns
@@ -487,11 +710,11 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
                         .filter(|(re, _)| re.is_match(property))
                         .flat_map(|(_, validators)| {
                             has_match = true;
-                            validate!(validators, schema, value)
+                            validate!(validators, schema, value, &property_path)
                         }),
                 );
                 if !has_match {
-                    disallow_property!(errors, property)
+                    disallow_property!(errors, property, &property_path, self.schema_path)
                 }
             }
             Box::new(errors.into_iter())
@@ -499,6 +722,29 @@ impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.all_properties(instance)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotate_properties(
+            schema,
+            instance,
+            instance_path,
+            annotations,
+            None,
+            &[],
+            &self.patterns,
+            None,
+        )
+    }
 }
 
 impl ToString for AdditionalPropertiesWithPatternsFalseValidator {
@@ -536,6 +782,7 @@ impl ToString for AdditionalPropertiesWithPatternsFalseValidator {
 pub(crate) struct AdditionalPropertiesWithPatternsNotEmptyValidator {
     validators: Validators,
     properties: AHashMap<String, Validators>,
+    defaults: Vec<(String, Value)>,
     patterns: PatternedValidators,
 }
 impl AdditionalPropertiesWithPatternsNotEmptyValidator {
@@ -549,13 +796,20 @@ impl AdditionalPropertiesWithPatternsNotEmptyValidator {
         if let Value::Object(map) = properties {
             Ok(Box::new(
                 AdditionalPropertiesWithPatternsNotEmptyValidator {
-                    validators: compile_validators(schema, context)?,
+                    validators: compile_validators(
+                        schema,
+                        &context.with_path("additionalProperties"),
+                    )?,
                     properties: compile_properties(map, context)?,
+                    defaults: property_defaults(map),
                     patterns,
                 },
             ))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(
+                context.as_pointer_with("properties"),
+                properties,
+            ))
         }
     }
 }
@@ -596,17 +850,25 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             let mut errors = vec![];
             for (property, value) in item.iter() {
+                let property_path = instance_path.push(property.clone());
                 if let Some(validators) = self.properties.get(property) {
-                    errors.extend(validate!(validators, schema, value));
+                    errors.extend(validate!(validators, schema, value, &property_path));
                     errors.extend(
                         self.patterns
                             .iter()
                             .filter(|(re, _)| re.is_match(property))
-                            .flat_map(|(_, validators)| validate!(validators, schema, value)),
+                            .flat_map(|(_, validators)| {
+                                validate!(validators, schema, value, &property_path)
+                            }),
                     );
                 } else {
                     let mut has_match = false;
@@ -616,11 +878,11 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyValidator {
                             .filter(|(re, _)| re.is_match(property))
                             .flat_map(|(_, validators)| {
                                 has_match = true;
-                                validate!(validators, schema, value)
+                                validate!(validators, schema, value, &property_path)
                             }),
                     );
                     if !has_match {
-                        errors.extend(validate!(self.validators, schema, value))
+                        errors.extend(validate!(self.validators, schema, value, &property_path))
                     }
                 }
             }
@@ -629,6 +891,29 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.all_properties(instance)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotate_properties(
+            schema,
+            instance,
+            instance_path,
+            annotations,
+            Some(&self.properties),
+            &self.defaults,
+            &self.patterns,
+            Some(&self.validators),
+        )
+    }
 }
 impl ToString for AdditionalPropertiesWithPatternsNotEmptyValidator {
     fn to_string(&self) -> String {
@@ -666,7 +951,9 @@ impl ToString for AdditionalPropertiesWithPatternsNotEmptyValidator {
 /// ```
 pub(crate) struct AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
     properties: AHashMap<String, Validators>,
+    defaults: Vec<(String, Value)>,
     patterns: PatternedValidators,
+    schema_path: JSONPointer,
 }
 impl AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
     #[inline]
@@ -679,11 +966,16 @@ impl AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
             Ok(Box::new(
                 AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
                     properties: compile_properties(map, context)?,
+                    defaults: property_defaults(map),
                     patterns,
+                    schema_path: context.as_pointer_with("additionalProperties"),
                 },
             ))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(
+                context.as_pointer_with("properties"),
+                properties,
+            ))
         }
     }
 }
@@ -713,18 +1005,26 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
         true
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             let mut errors = vec![];
             // No properties are allowed, except ones defined in `properties` or `patternProperties`
             for (property, value) in item.iter() {
+                let property_path = instance_path.push(property.clone());
                 if let Some(validators) = self.properties.get(property) {
-                    errors.extend(validate!(validators, schema, value));
+                    errors.extend(validate!(validators, schema, value, &property_path));
                     errors.extend(
                         self.patterns
                             .iter()
                             .filter(|(re, _)| re.is_match(property))
-                            .flat_map(|(_, validators)| validate!(validators, schema, value)),
+                            .flat_map(|(_, validators)| {
+                                validate!(validators, schema, value, &property_path)
+                            }),
                     );
                 } else {
                     let mut has_match = false;
@@ -734,11 +1034,11 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
                             .filter(|(re, _)| re.is_match(property))
                             .flat_map(|(_, validators)| {
                                 has_match = true;
-                                validate!(validators, schema, value)
+                                validate!(validators, schema, value, &property_path)
                             }),
                     );
                     if !has_match {
-                        disallow_property!(errors, property)
+                        disallow_property!(errors, property, &property_path, self.schema_path)
                     }
                 }
             }
@@ -747,6 +1047,29 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.all_properties(instance)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotate_properties(
+            schema,
+            instance,
+            instance_path,
+            annotations,
+            Some(&self.properties),
+            &self.defaults,
+            &self.patterns,
+            None,
+        )
+    }
 }
 
 impl ToString for AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
@@ -764,8 +1087,14 @@ pub(crate) fn compile(
     if let Some(patterns) = parent.get("patternProperties") {
         if let Value::Object(obj) = patterns {
             // Compile all patterns & their validators to avoid doing work in the `patternProperties` validator
-            let compiled_patterns = compile_patterns(obj, context).ok()?;
+            let compiled_patterns = match compile_patterns(obj, context) {
+                Ok(compiled_patterns) => compiled_patterns,
+                Err(error) => return Some(Err(error)),
+            };
             match schema {
+                Value::Bool(true) if context.config.draft().collects_annotations() => {
+                    Some(AdditionalPropertiesValidator::compile(schema, context))
+                }
                 Value::Bool(true) => None, // "additionalProperties" are "true" by default
                 Value::Bool(false) => {
                     if let Some(properties) = properties {
@@ -779,6 +1108,7 @@ pub(crate) fn compile(
                     } else {
                         Some(AdditionalPropertiesWithPatternsFalseValidator::compile(
                             compiled_patterns,
+                            context.as_pointer_with("additionalProperties"),
                         ))
                     }
                 }
@@ -800,10 +1130,16 @@ pub(crate) fn compile(
                 }
             }
         } else {
-            Some(Err(CompilationError::SchemaError))
+            Some(Err(CompilationError::invalid_value(
+                context.as_pointer_with("patternProperties"),
+                patterns,
+            )))
         }
     } else {
         match schema {
+            Value::Bool(true) if context.config.draft().collects_annotations() => {
+                Some(AdditionalPropertiesValidator::compile(schema, context))
+            }
             Value::Bool(true) => None, // "additionalProperties" are "true" by default
             Value::Bool(false) => {
                 if let Some(properties) = properties {
@@ -811,7 +1147,9 @@ pub(crate) fn compile(
                         properties, context,
                     ))
                 } else {
-                    Some(AdditionalPropertiesFalseValidator::compile())
+                    Some(AdditionalPropertiesFalseValidator::compile(
+                        context.as_pointer_with("additionalProperties"),
+                    ))
                 }
             }
             _ => {
@@ -833,17 +1171,20 @@ fn compile_patterns(
     obj: &Map<String, Value>,
     context: &CompilationContext,
 ) -> Result<PatternedValidators, CompilationError> {
+    let keyword_context = context.with_path("patternProperties");
     let mut compiled_patterns = Vec::with_capacity(obj.len());
     for (pattern, subschema) in obj {
-        if let Ok(compiled_pattern) = Regex::new(pattern) {
-            if let Ok(validators) = compile_validators(subschema, context) {
-                compiled_patterns.push((compiled_pattern, validators));
-            } else {
-                return Err(CompilationError::SchemaError);
-            }
-        } else {
-            return Err(CompilationError::SchemaError);
-        }
+        let pattern_context = keyword_context.with_path(pattern.as_str());
+        let compiled_pattern = Regex::new(pattern).map_err(|error| {
+            CompilationError::invalid_regex(
+                keyword_context.as_pointer_with(pattern.as_str()),
+                &Value::String(pattern.clone()),
+                error,
+            )
+            .with_keyword("patternProperties")
+        })?;
+        let validators = compile_validators(subschema, &pattern_context)?;
+        compiled_patterns.push((compiled_pattern, validators));
     }
     Ok(compiled_patterns)
 }
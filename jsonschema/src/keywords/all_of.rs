@@ -2,7 +2,8 @@ use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{CompilationError, ErrorIterator},
     keywords::{format_vec_of_validators, CompilationResult, Validators},
-    validator::Validate,
+    paths::InstancePath,
+    validator::{annotate_subschema, evaluate_subschema, Annotations, Evaluated, Validate},
 };
 use serde_json::{Map, Value};
 
@@ -14,14 +15,18 @@ impl AllOfValidator {
     #[inline]
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
+            let keyword_context = context.with_path("allOf");
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                let validators = compile_validators(item, context)?;
+            for (idx, item) in items.iter().enumerate() {
+                let validators = compile_validators(item, &keyword_context.with_path(idx))?;
                 schemas.push(validators)
             }
             Ok(Box::new(AllOfValidator { schemas }))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(
+                context.as_pointer_with("allOf"),
+                schema,
+            ))
         }
     }
 }
@@ -35,18 +40,48 @@ impl Validate for AllOfValidator {
         })
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         let errors: Vec<_> = self
             .schemas
             .iter()
             .flat_map(move |validators| {
                 validators
                     .iter()
-                    .flat_map(move |validator| validator.validate(schema, instance))
+                    .flat_map(move |validator| validator.validate(schema, instance, instance_path))
             })
             .collect();
         Box::new(errors.into_iter())
     }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        for validators in &self.schemas {
+            evaluate_subschema(validators, schema, instance, evaluated);
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        for validators in &self.schemas {
+            valid &= annotate_subschema(validators, schema, instance, instance_path, annotations);
+        }
+        valid
+    }
 }
 
 impl ToString for AllOfValidator {
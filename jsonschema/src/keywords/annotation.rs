@@ -0,0 +1,75 @@
+//! Annotation keywords, e.g. `title` or `default`. They don't affect validation and are compiled
+//! only if annotations are collected.
+use crate::{
+    compilation::{context::CompilationContext, JSONSchema},
+    error::{no_error, ErrorIterator},
+    keywords::CompilationResult,
+    output::Annotation,
+    paths::{InstancePath, JSONPointer},
+    schemas::Draft,
+    validator::{Annotations, Validate},
+};
+use serde_json::Value;
+
+pub(crate) struct AnnotationValidator {
+    keyword: String,
+    value: Value,
+    schema_path: JSONPointer,
+}
+
+impl Validate for AnnotationValidator {
+    fn is_valid(&self, _: &JSONSchema, _: &Value) -> bool {
+        true
+    }
+
+    fn validate<'a>(&self, _: &'a JSONSchema, _: &'a Value, _: &InstancePath) -> ErrorIterator<'a> {
+        no_error()
+    }
+
+    fn annotate(
+        &self,
+        _: &JSONSchema,
+        _: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotations.keywords.push(Annotation {
+            keyword_location: self.schema_path.clone(),
+            absolute_keyword_location: None,
+            instance_location: instance_path.into(),
+            annotation: self.value.clone(),
+        });
+        true
+    }
+}
+
+impl ToString for AnnotationValidator {
+    fn to_string(&self) -> String {
+        format!("{}: {}", self.keyword, self.value)
+    }
+}
+
+#[inline]
+pub(crate) fn compile(
+    draft: Draft,
+    keyword: &str,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    let is_annotation = match keyword {
+        "title" | "description" | "default" => true,
+        "examples" => !matches!(draft, Draft::Draft4),
+        "readOnly" | "writeOnly" => !matches!(draft, Draft::Draft4 | Draft::Draft6),
+        "deprecated" => matches!(draft, Draft::Draft201909 | Draft::Draft202012),
+        _ => false,
+    };
+    if is_annotation {
+        Some(Ok(Box::new(AnnotationValidator {
+            keyword: keyword.to_string(),
+            value: schema.clone(),
+            schema_path: context.as_pointer_with(keyword),
+        })))
+    } else {
+        None
+    }
+}
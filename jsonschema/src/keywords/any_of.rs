@@ -2,26 +2,35 @@ use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::{format_vec_of_validators, CompilationResult, Validators},
-    validator::Validate,
+    paths::{InstancePath, JSONPointer},
+    validator::{annotate_branches, evaluate_subschema, Annotations, Evaluated, Validate},
 };
 use serde_json::{Map, Value};
 
 pub(crate) struct AnyOfValidator {
     schemas: Vec<Validators>,
+    schema_path: JSONPointer,
 }
 
 impl AnyOfValidator {
     #[inline]
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
+            let keyword_context = context.with_path("anyOf");
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                let validators = compile_validators(item, context)?;
+            for (idx, item) in items.iter().enumerate() {
+                let validators = compile_validators(item, &keyword_context.with_path(idx))?;
                 schemas.push(validators)
             }
-            Ok(Box::new(AnyOfValidator { schemas }))
+            Ok(Box::new(AnyOfValidator {
+                schemas,
+                schema_path: keyword_context.into_pointer(),
+            }))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(
+                context.as_pointer_with("anyOf"),
+                schema,
+            ))
         }
     }
 }
@@ -39,13 +48,55 @@ impl Validate for AnyOfValidator {
         false
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::any_of(instance))
+            let context = self
+                .schemas
+                .iter()
+                .map(|validators| {
+                    validators
+                        .iter()
+                        .flat_map(|validator| validator.validate(schema, instance, instance_path))
+                        .map(ValidationError::into_owned)
+                        .collect()
+                })
+                .collect();
+            error(ValidationError::any_of(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                context,
+            ))
+        }
+    }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        for validators in &self.schemas {
+            evaluate_subschema(validators, schema, instance, evaluated);
         }
     }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotate_branches(&self.schemas, schema, instance, instance_path, annotations) > 0
+    }
 }
 
 impl ToString for AnyOfValidator {
@@ -61,3 +112,143 @@ pub(crate) fn compile(
 ) -> Option<CompilationResult> {
     Some(AnyOfValidator::compile(schema, context))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ValidationErrorKind, JSONSchema};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test]
+    fn branch_errors() {
+        let schema = json!({
+            "anyOf": [
+                {"type": "string"},
+                {"properties": {"foo": {"minimum": 2}}, "required": ["bar"]}
+            ]
+        });
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let instance = json!({"foo": 1});
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        if let ValidationErrorKind::AnyOf { context } = &errors[0].kind {
+            assert_eq!(context.len(), 2);
+            assert_eq!(context[0].len(), 1);
+            assert_eq!(context[0][0].schema_path.to_string(), "/anyOf/0/type");
+            let mut paths: Vec<_> = context[1]
+                .iter()
+                .map(|error| {
+                    (
+                        error.schema_path.to_string(),
+                        error.instance_path.to_string(),
+                    )
+                })
+                .collect();
+            paths.sort();
+            assert_eq!(
+                paths,
+                vec![
+                    (
+                        "/anyOf/1/properties/foo/minimum".to_string(),
+                        "/foo".to_string()
+                    ),
+                    ("/anyOf/1/required".to_string(), "".to_string())
+                ]
+            );
+        } else {
+            panic!("Unexpected error kind: {:?}", errors[0].kind)
+        }
+    }
+
+    #[test]
+    fn branch_errors_serialization() {
+        let schema = json!({"anyOf": [{"type": "string"}, {"minimum": 2}]});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let instance = json!(1);
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(
+            serde_json::to_value(&errors[0].kind).unwrap(),
+            json!({
+                "AnyOf": {
+                    "context": [
+                        [{
+                            "keywordLocation": "/anyOf/0/type",
+                            "absoluteKeywordLocation": "json-schema:///#/anyOf/0/type",
+                            "instanceLocation": "",
+                            "error": "'1' is not of type 'string'",
+                            "kind": {"Type": {"kind": {"Single": "string"}}}
+                        }],
+                        [{
+                            "keywordLocation": "/anyOf/1/minimum",
+                            "absoluteKeywordLocation": "json-schema:///#/anyOf/1/minimum",
+                            "instanceLocation": "",
+                            "error": "1 is less than the minimum of 2",
+                            "kind": {"Minimum": {"limit": 2.0}}
+                        }]
+                    ]
+                }
+            })
+        );
+    }
+
+    #[test_case(
+        &json!({"properties": {"foo": {"anyOf": [{"type": "string"}, {"minimum": 2}]}}}),
+        &json!({"foo": 1}),
+        &[
+            ("/properties/foo/anyOf/0/type", "json-schema:///#/properties/foo/anyOf/0/type"),
+            ("/properties/foo/anyOf/1/minimum", "json-schema:///#/properties/foo/anyOf/1/minimum"),
+        ];
+        "properties"
+    )]
+    #[test_case(
+        &json!({"$ref": "#/definitions/a", "definitions": {"a": {"anyOf": [{"type": "string"}, {"minimum": 2}]}}}),
+        &json!(1),
+        &[
+            ("/$ref/anyOf/0/type", "json-schema:///#/definitions/a/anyOf/0/type"),
+            ("/$ref/anyOf/1/minimum", "json-schema:///#/definitions/a/anyOf/1/minimum"),
+        ];
+        "reference"
+    )]
+    #[test_case(
+        &json!({
+            "properties": {"foo": {"$ref": "#/definitions/a"}},
+            "definitions": {
+                "a": {"anyOf": [{"$ref": "#/definitions/b"}, {"minimum": 2}]},
+                "b": {"type": "string"}
+            }
+        }),
+        &json!({"foo": 1}),
+        &[
+            ("/properties/foo/$ref/anyOf/0/$ref/type", "json-schema:///#/definitions/b/type"),
+            ("/properties/foo/$ref/anyOf/1/minimum", "json-schema:///#/definitions/a/anyOf/1/minimum"),
+        ];
+        "nested references"
+    )]
+    fn branch_error_locations(schema: &Value, instance: &Value, expected: &[(&str, &str)]) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let errors: Vec<_> = compiled.validate(instance).unwrap_err().collect();
+        if let ValidationErrorKind::AnyOf { context } = &errors[0].kind {
+            let locations: Vec<_> = context
+                .iter()
+                .flatten()
+                .map(|error| {
+                    (
+                        error.schema_path.to_string(),
+                        error
+                            .absolute_keyword_location
+                            .as_ref()
+                            .map(ToString::to_string)
+                            .unwrap_or_default(),
+                    )
+                })
+                .collect();
+            let expected: Vec<_> = expected
+                .iter()
+                .map(|(path, location)| (path.to_string(), location.to_string()))
+                .collect();
+            assert_eq!(locations, expected);
+        } else {
+            panic!("Unexpected error kind: {:?}", errors[0].kind)
+        }
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     compilation::JSONSchema,
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::Value;
@@ -18,7 +19,7 @@ impl Validate for TrueValidator {
         true
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, _: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&self, _: &'a JSONSchema, _: &'a Value, _: &InstancePath) -> ErrorIterator<'a> {
         no_error()
     }
 }
@@ -29,11 +30,13 @@ impl ToString for TrueValidator {
     }
 }
 
-pub(crate) struct FalseValidator {}
+pub(crate) struct FalseValidator {
+    schema_path: JSONPointer,
+}
 impl FalseValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(FalseValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(FalseValidator { schema_path }))
     }
 }
 impl Validate for FalseValidator {
@@ -41,8 +44,17 @@ impl Validate for FalseValidator {
         false
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
-        error(ValidationError::false_schema(instance))
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        error(ValidationError::false_schema(
+            self.schema_path.clone(),
+            instance_path.into(),
+            instance,
+        ))
     }
 }
 
@@ -53,10 +65,10 @@ impl ToString for FalseValidator {
 }
 
 #[inline]
-pub(crate) fn compile(value: bool) -> Option<CompilationResult> {
+pub(crate) fn compile(value: bool, schema_path: JSONPointer) -> Option<CompilationResult> {
     if value {
         Some(TrueValidator::compile())
     } else {
-        Some(FalseValidator::compile())
+        Some(FalseValidator::compile(schema_path))
     }
 }
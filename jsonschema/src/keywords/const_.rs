@@ -3,6 +3,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Number, Value};
@@ -10,22 +11,34 @@ use std::f64::EPSILON;
 
 struct ConstArrayValidator {
     value: Vec<Value>,
+    schema_path: JSONPointer,
 }
 impl ConstArrayValidator {
     #[inline]
-    pub(crate) fn compile(value: &[Value]) -> CompilationResult {
+    pub(crate) fn compile(value: &[Value], schema_path: JSONPointer) -> CompilationResult {
         Ok(Box::new(ConstArrayValidator {
             value: value.to_vec(),
+            schema_path,
         }))
     }
 }
 impl Validate for ConstArrayValidator {
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::constant_array(instance, &self.value))
+            error(ValidationError::constant_array(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                &self.value,
+            ))
         }
     }
 
@@ -53,20 +66,31 @@ impl ToString for ConstArrayValidator {
 
 struct ConstBooleanValidator {
     value: bool,
+    schema_path: JSONPointer,
 }
 impl ConstBooleanValidator {
     #[inline]
-    pub(crate) fn compile(value: bool) -> CompilationResult {
-        Ok(Box::new(ConstBooleanValidator { value }))
+    pub(crate) fn compile(value: bool, schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(ConstBooleanValidator { value, schema_path }))
     }
 }
 impl Validate for ConstBooleanValidator {
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::constant_boolean(instance, self.value))
+            error(ValidationError::constant_boolean(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                self.value,
+            ))
         }
     }
 
@@ -85,20 +109,31 @@ impl ToString for ConstBooleanValidator {
     }
 }
 
-struct ConstNullValidator {}
+struct ConstNullValidator {
+    schema_path: JSONPointer,
+}
 impl ConstNullValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(ConstNullValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(ConstNullValidator { schema_path }))
     }
 }
 impl Validate for ConstNullValidator {
     #[inline]
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::constant_null(instance))
+            error(ValidationError::constant_null(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+            ))
         }
     }
 
@@ -117,26 +152,35 @@ struct ConstNumberValidator {
     // This is saved in order to ensure that the error message is not altered by precision loss
     original_value: Number,
     value: f64,
+    schema_path: JSONPointer,
 }
 
 impl ConstNumberValidator {
     #[inline]
-    pub(crate) fn compile(original_value: &Number) -> CompilationResult {
+    pub(crate) fn compile(original_value: &Number, schema_path: JSONPointer) -> CompilationResult {
         Ok(Box::new(ConstNumberValidator {
             original_value: original_value.clone(),
             value: original_value
                 .as_f64()
                 .expect("A JSON number will always be representable as f64"),
+            schema_path,
         }))
     }
 }
 
 impl Validate for ConstNumberValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::constant_number(
+                self.schema_path.clone(),
+                instance_path.into(),
                 instance,
                 &self.original_value,
             ))
@@ -160,23 +204,38 @@ impl ToString for ConstNumberValidator {
 
 pub(crate) struct ConstObjectValidator {
     value: Map<String, Value>,
+    schema_path: JSONPointer,
 }
 
 impl ConstObjectValidator {
     #[inline]
-    pub(crate) fn compile(value: &Map<String, Value>) -> CompilationResult {
+    pub(crate) fn compile(
+        value: &Map<String, Value>,
+        schema_path: JSONPointer,
+    ) -> CompilationResult {
         Ok(Box::new(ConstObjectValidator {
             value: value.clone(),
+            schema_path,
         }))
     }
 }
 
 impl Validate for ConstObjectValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::constant_object(instance, &self.value))
+            error(ValidationError::constant_object(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                &self.value,
+            ))
         }
     }
 
@@ -204,23 +263,35 @@ impl ToString for ConstObjectValidator {
 
 pub(crate) struct ConstStringValidator {
     value: String,
+    schema_path: JSONPointer,
 }
 
 impl ConstStringValidator {
     #[inline]
-    pub(crate) fn compile(value: &str) -> CompilationResult {
+    pub(crate) fn compile(value: &str, schema_path: JSONPointer) -> CompilationResult {
         Ok(Box::new(ConstStringValidator {
             value: value.to_string(),
+            schema_path,
         }))
     }
 }
 
 impl Validate for ConstStringValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::constant_string(instance, &self.value))
+            error(ValidationError::constant_string(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                &self.value,
+            ))
         }
     }
 
@@ -243,14 +314,15 @@ impl ToString for ConstStringValidator {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
+    let schema_path = context.as_pointer_with("const");
     match schema {
-        Value::Array(items) => Some(ConstArrayValidator::compile(items)),
-        Value::Bool(item) => Some(ConstBooleanValidator::compile(*item)),
-        Value::Null => Some(ConstNullValidator::compile()),
-        Value::Number(item) => Some(ConstNumberValidator::compile(item)),
-        Value::Object(map) => Some(ConstObjectValidator::compile(map)),
-        Value::String(string) => Some(ConstStringValidator::compile(string)),
+        Value::Array(items) => Some(ConstArrayValidator::compile(items, schema_path)),
+        Value::Bool(item) => Some(ConstBooleanValidator::compile(*item, schema_path)),
+        Value::Null => Some(ConstNullValidator::compile(schema_path)),
+        Value::Number(item) => Some(ConstNumberValidator::compile(item, schema_path)),
+        Value::Object(map) => Some(ConstObjectValidator::compile(map, schema_path)),
+        Value::String(string) => Some(ConstStringValidator::compile(string, schema_path)),
     }
 }
@@ -1,20 +1,25 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
-    error::{error, no_error, ErrorIterator, ValidationError},
+    error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::{format_validators, CompilationResult, Validators},
-    validator::Validate,
+    paths::{InstancePath, JSONPointer},
+    schemas::Draft,
+    validator::{annotate_if_valid, Annotations, Evaluated, Validate},
 };
 use serde_json::{Map, Value};
 
 pub(crate) struct ContainsValidator {
     validators: Validators,
+    schema_path: JSONPointer,
 }
 
 impl ContainsValidator {
     #[inline]
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
+        let keyword_context = context.with_path("contains");
         Ok(Box::new(ContainsValidator {
-            validators: compile_validators(schema, context)?,
+            validators: compile_validators(schema, &keyword_context)?,
+            schema_path: keyword_context.into_pointer(),
         }))
     }
 }
@@ -37,7 +42,12 @@ impl Validate for ContainsValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
             for item in items {
                 if self
@@ -48,11 +58,60 @@ impl Validate for ContainsValidator {
                     return no_error();
                 }
             }
-            error(ValidationError::contains(instance))
+            error(ValidationError::contains(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+            ))
         } else {
             no_error()
         }
     }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate() {
+                if self
+                    .validators
+                    .iter()
+                    .all(|validator| validator.is_valid(schema, item))
+                {
+                    evaluated.items.insert(idx);
+                }
+            }
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        if let Value::Array(items) = instance {
+            let mut matches = 0;
+            for (idx, item) in items.iter().enumerate() {
+                if annotate_if_valid(
+                    &self.validators,
+                    schema,
+                    item,
+                    &instance_path.push(idx),
+                    annotations,
+                ) {
+                    matches += 1;
+                }
+            }
+            matches > 0
+        } else {
+            true
+        }
+    }
 }
 
 impl ToString for ContainsValidator {
@@ -61,11 +120,177 @@ impl ToString for ContainsValidator {
     }
 }
 
+/// `contains` with `minContains` and / or `maxContains` that were introduced in Draft 2019-09.
+pub(crate) struct ContainsRangeValidator {
+    validators: Validators,
+    min_contains: u64,
+    max_contains: Option<u64>,
+    schema_path: JSONPointer,
+    min_contains_path: JSONPointer,
+    max_contains_path: JSONPointer,
+}
+
+impl ContainsRangeValidator {
+    #[inline]
+    pub(crate) fn compile(
+        parent: &Map<String, Value>,
+        schema: &Value,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        let keyword_context = context.with_path("contains");
+        let min_contains_path = context.as_pointer_with("minContains");
+        let max_contains_path = context.as_pointer_with("maxContains");
+        let min_contains = match parent.get("minContains") {
+            Some(value) => value
+                .as_u64()
+                .ok_or_else(|| CompilationError::invalid_value(min_contains_path.clone(), value))?,
+            None => 1,
+        };
+        let max_contains = match parent.get("maxContains") {
+            Some(value) => Some(value.as_u64().ok_or_else(|| {
+                CompilationError::invalid_value(max_contains_path.clone(), value)
+            })?),
+            None => None,
+        };
+        let schema_path = context.as_pointer_with("contains");
+        Ok(Box::new(ContainsRangeValidator {
+            validators: compile_validators(schema, &keyword_context)?,
+            min_contains,
+            max_contains,
+            schema_path: if parent.contains_key("minContains") {
+                min_contains_path.clone()
+            } else {
+                schema_path
+            },
+            min_contains_path,
+            max_contains_path,
+        }))
+    }
+
+    fn count_matches(&self, schema: &JSONSchema, items: &[Value]) -> u64 {
+        items
+            .iter()
+            .filter(|item| {
+                self.validators
+                    .iter()
+                    .all(|validator| validator.is_valid(schema, item))
+            })
+            .count() as u64
+    }
+}
+
+impl Validate for ContainsRangeValidator {
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Array(items) = instance {
+            let matches = self.count_matches(schema, items);
+            matches >= self.min_contains && !matches!(self.max_contains, Some(max) if matches > max)
+        } else {
+            true
+        }
+    }
+
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::Array(items) = instance {
+            let matches = self.count_matches(schema, items);
+            if matches < self.min_contains {
+                if matches == 0 {
+                    return error(ValidationError::contains(
+                        self.schema_path.clone(),
+                        instance_path.into(),
+                        instance,
+                    ));
+                }
+                return error(ValidationError::min_contains(
+                    self.min_contains_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    self.min_contains,
+                ));
+            }
+            if let Some(max_contains) = self.max_contains {
+                if matches > max_contains {
+                    return error(ValidationError::max_contains(
+                        self.max_contains_path.clone(),
+                        instance_path.into(),
+                        instance,
+                        max_contains,
+                    ));
+                }
+            }
+        }
+        no_error()
+    }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate() {
+                if self
+                    .validators
+                    .iter()
+                    .all(|validator| validator.is_valid(schema, item))
+                {
+                    evaluated.items.insert(idx);
+                }
+            }
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        if let Value::Array(items) = instance {
+            let mut matches = 0;
+            for (idx, item) in items.iter().enumerate() {
+                if annotate_if_valid(
+                    &self.validators,
+                    schema,
+                    item,
+                    &instance_path.push(idx),
+                    annotations,
+                ) {
+                    matches += 1;
+                }
+            }
+            matches >= self.min_contains && !matches!(self.max_contains, Some(max) if matches > max)
+        } else {
+            true
+        }
+    }
+}
+
+impl ToString for ContainsRangeValidator {
+    fn to_string(&self) -> String {
+        format!("contains: {}", format_validators(&self.validators))
+    }
+}
+
 #[inline]
 pub(crate) fn compile(
-    _: &Map<String, Value>,
+    parent: &Map<String, Value>,
     schema: &Value,
     context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(ContainsValidator::compile(schema, context))
+    if matches!(
+        context.config.draft(),
+        Draft::Draft201909 | Draft::Draft202012
+    ) && (parent.contains_key("minContains") || parent.contains_key("maxContains"))
+    {
+        Some(ContainsRangeValidator::compile(parent, schema, context))
+    } else {
+        Some(ContainsValidator::compile(schema, context))
+    }
 }
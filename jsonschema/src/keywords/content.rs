@@ -1,10 +1,11 @@
-//! Validators for `contentMediaType` and `contentEncoding` keywords.
+//! Validators for `contentMediaType`, `contentEncoding` and `contentSchema` keywords.
 use crate::{
-    compilation::{context::CompilationContext, JSONSchema},
+    compilation::{compile_validators, context::CompilationContext, JSONSchema},
     content_encoding::{ContentEncodingCheckType, ContentEncodingConverterType},
     content_media_type::ContentMediaTypeCheckType,
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
-    keywords::CompilationResult,
+    keywords::{format_validators, CompilationResult, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -13,14 +14,20 @@ use serde_json::{Map, Value};
 pub(crate) struct ContentMediaTypeValidator {
     media_type: String,
     func: ContentMediaTypeCheckType,
+    schema_path: JSONPointer,
 }
 
 impl ContentMediaTypeValidator {
     #[inline]
-    pub(crate) fn compile(media_type: &str, func: ContentMediaTypeCheckType) -> CompilationResult {
+    pub(crate) fn compile(
+        media_type: &str,
+        func: ContentMediaTypeCheckType,
+        schema_path: JSONPointer,
+    ) -> CompilationResult {
         Ok(Box::new(ContentMediaTypeValidator {
             media_type: media_type.to_string(),
             func,
+            schema_path,
         }))
     }
 }
@@ -35,12 +42,19 @@ impl Validate for ContentMediaTypeValidator {
         }
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
             if (self.func)(item) {
                 no_error()
             } else {
                 error(ValidationError::content_media_type(
+                    self.schema_path.clone(),
+                    instance_path.into(),
                     instance,
                     &self.media_type,
                 ))
@@ -61,14 +75,20 @@ impl ToString for ContentMediaTypeValidator {
 pub(crate) struct ContentEncodingValidator {
     encoding: String,
     func: ContentEncodingCheckType,
+    schema_path: JSONPointer,
 }
 
 impl ContentEncodingValidator {
     #[inline]
-    pub(crate) fn compile(encoding: &str, func: ContentEncodingCheckType) -> CompilationResult {
+    pub(crate) fn compile(
+        encoding: &str,
+        func: ContentEncodingCheckType,
+        schema_path: JSONPointer,
+    ) -> CompilationResult {
         Ok(Box::new(ContentEncodingValidator {
             encoding: encoding.to_string(),
             func,
+            schema_path,
         }))
     }
 }
@@ -82,12 +102,22 @@ impl Validate for ContentEncodingValidator {
         }
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
             if (self.func)(item) {
                 no_error()
             } else {
-                error(ValidationError::content_encoding(instance, &self.encoding))
+                error(ValidationError::content_encoding(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    &self.encoding,
+                ))
             }
         } else {
             no_error()
@@ -107,6 +137,8 @@ pub(crate) struct ContentMediaTypeAndEncodingValidator {
     encoding: String,
     func: ContentMediaTypeCheckType,
     converter: ContentEncodingConverterType,
+    /// Location of the schema that contains both keywords.
+    schema_path: JSONPointer,
 }
 
 impl ContentMediaTypeAndEncodingValidator {
@@ -116,12 +148,14 @@ impl ContentMediaTypeAndEncodingValidator {
         encoding: &str,
         func: ContentMediaTypeCheckType,
         converter: ContentEncodingConverterType,
+        schema_path: JSONPointer,
     ) -> CompilationResult {
         Ok(Box::new(ContentMediaTypeAndEncodingValidator {
             media_type: media_type.to_string(),
             encoding: encoding.to_string(),
             func,
             converter,
+            schema_path,
         }))
     }
 }
@@ -139,21 +173,37 @@ impl Validate for ContentMediaTypeAndEncodingValidator {
         }
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
             match (self.converter)(item) {
-                Ok(None) => error(ValidationError::content_encoding(instance, &self.encoding)),
+                Ok(None) => error(ValidationError::content_encoding(
+                    self.schema_path.clone_with("contentEncoding"),
+                    instance_path.into(),
+                    instance,
+                    &self.encoding,
+                )),
                 Ok(Some(converted)) => {
                     if (self.func)(&converted) {
                         no_error()
                     } else {
                         error(ValidationError::content_media_type(
+                            self.schema_path.clone_with("contentMediaType"),
+                            instance_path.into(),
                             instance,
                             &self.media_type,
                         ))
                     }
                 }
-                Err(e) => error(e),
+                Err(mut e) => {
+                    e.schema_path = self.schema_path.clone_with("contentEncoding");
+                    e.instance_path = instance_path.into();
+                    error(e)
+                }
             }
         } else {
             no_error()
@@ -197,15 +247,26 @@ pub(crate) fn compile_media_type(
                             content_encoding,
                             func,
                             converter,
+                            context.schema_path.clone(),
                         ))
                     }
-                    _ => Some(Err(CompilationError::SchemaError)),
+                    _ => Some(Err(CompilationError::invalid_value(
+                        context.as_pointer_with("contentEncoding"),
+                        content_encoding,
+                    ))),
                 }
             } else {
-                Some(ContentMediaTypeValidator::compile(media_type, func))
+                Some(ContentMediaTypeValidator::compile(
+                    media_type,
+                    func,
+                    context.as_pointer_with("contentMediaType"),
+                ))
             }
         }
-        _ => Some(Err(CompilationError::SchemaError)),
+        _ => Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("contentMediaType"),
+            subschema,
+        ))),
     }
 }
 
@@ -229,8 +290,118 @@ pub(crate) fn compile_content_encoding(
                 Some(f) => f,
                 None => return None,
             };
-            Some(ContentEncodingValidator::compile(content_encoding, func))
+            Some(ContentEncodingValidator::compile(
+                content_encoding,
+                func,
+                context.as_pointer_with("contentEncoding"),
+            ))
+        }
+        _ => Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("contentEncoding"),
+            subschema,
+        ))),
+    }
+}
+
+/// Validator for `contentSchema` keyword.
+/// Decodes the input string as a JSON document and validates it against the subschema.
+pub(crate) struct ContentSchemaValidator {
+    converter: Option<ContentEncodingConverterType>,
+    validators: Validators,
+}
+
+impl ContentSchemaValidator {
+    #[inline]
+    pub(crate) fn compile(
+        schema: &Value,
+        converter: Option<ContentEncodingConverterType>,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        Ok(Box::new(ContentSchemaValidator {
+            converter,
+            validators: compile_validators(schema, &context.with_path("contentSchema"))?,
+        }))
+    }
+
+    /// Decoded content. Invalid encoding or JSON is reported by other content validators.
+    fn decode(&self, item: &str) -> Option<Value> {
+        match self.converter {
+            Some(converter) => match converter(item) {
+                Ok(Some(converted)) => serde_json::from_str(&converted).ok(),
+                Ok(None) | Err(_) => None,
+            },
+            None => serde_json::from_str(item).ok(),
         }
-        _ => Some(Err(CompilationError::SchemaError)),
     }
 }
+
+impl Validate for ContentSchemaValidator {
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::String(item) = instance {
+            if let Some(content) = self.decode(item) {
+                return self
+                    .validators
+                    .iter()
+                    .all(|validator| validator.is_valid(schema, &content));
+            }
+        }
+        true
+    }
+
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::String(item) = instance {
+            if let Some(content) = self.decode(item) {
+                let root = InstancePath::new();
+                // Locations inside the decoded document are appended to the location of the string
+                let location = JSONPointer::from(instance_path);
+                let errors: Vec<_> = self
+                    .validators
+                    .iter()
+                    .flat_map(|validator| validator.validate(schema, &content, &root))
+                    .map(|error| {
+                        let mut error = error.into_owned();
+                        error.instance_path = location.extend_with(error.instance_path.chunks());
+                        error
+                    })
+                    .collect();
+                return Box::new(errors.into_iter());
+            }
+        }
+        no_error()
+    }
+}
+
+impl ToString for ContentSchemaValidator {
+    fn to_string(&self) -> String {
+        format!("contentSchema: {}", format_validators(&self.validators))
+    }
+}
+
+#[inline]
+pub(crate) fn compile_content_schema(
+    schema: &Map<String, Value>,
+    subschema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    // Only JSON documents can be validated against a schema
+    if schema.get("contentMediaType").and_then(Value::as_str) != Some("application/json") {
+        return None;
+    }
+    let converter = match schema.get("contentEncoding") {
+        Some(Value::String(content_encoding)) => Some(
+            context
+                .config
+                .content_encoding_convert(content_encoding.as_str())?,
+        ),
+        Some(_) => return None,
+        None => None,
+    };
+    Some(ContentSchemaValidator::compile(
+        subschema, converter, context,
+    ))
+}
@@ -0,0 +1,260 @@
+//! User-defined keywords.
+use crate::{
+    compilation::{context::CompilationContext, JSONSchema},
+    error::{CompilationError, ErrorIterator},
+    keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
+    validator::Validate,
+};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// A validator for a user-defined keyword.
+///
+/// It is created by a factory registered via `CompilationOptions::with_keyword` and takes part
+/// in `JSONSchema::validate` and `JSONSchema::is_valid` in the same way as built-in keywords do.
+pub trait Keyword: Send + Sync {
+    /// Validate `instance` and return an iterator over errors.
+    /// Use `ValidationError::custom` to create them, e.g. `Box::new(std::iter::once(error))`,
+    /// and `Box::new(std::iter::empty())` if there are none.
+    fn validate<'instance>(
+        &self,
+        instance: &'instance Value,
+        instance_path: &JSONPointer,
+    ) -> ErrorIterator<'instance>;
+    /// The same as `validate`, but without constructing errors.
+    fn is_valid(&self, instance: &Value) -> bool;
+}
+
+/// A function that creates a `Keyword` validator from the schema object that contains the keyword,
+/// the keyword value and its location within the schema.
+pub type KeywordFactory = dyn for<'a> Fn(
+        &'a Map<String, Value>,
+        &'a Value,
+        JSONPointer,
+    ) -> Result<Box<dyn Keyword>, CompilationError>
+    + Send
+    + Sync;
+
+pub(crate) struct CustomKeywordValidator {
+    name: String,
+    value: Value,
+    inner: Box<dyn Keyword>,
+}
+impl CustomKeywordValidator {
+    #[inline]
+    pub(crate) fn compile(
+        parent: &Map<String, Value>,
+        name: &str,
+        schema: &Value,
+        factory: &Arc<KeywordFactory>,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        let inner = factory(parent, schema, context.as_pointer_with(name))?;
+        Ok(Box::new(CustomKeywordValidator {
+            name: name.to_string(),
+            value: schema.clone(),
+            inner,
+        }))
+    }
+}
+impl Validate for CustomKeywordValidator {
+    fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
+        self.inner.is_valid(instance)
+    }
+
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        self.inner.validate(instance, &instance_path.into())
+    }
+}
+impl ToString for CustomKeywordValidator {
+    fn to_string(&self) -> String {
+        format!("{}: {}", self.name, self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Only the public API is used here, as in external crates
+    use crate::{
+        paths::JSONPointer, CompilationError, ErrorIterator, JSONSchema, Keyword, ValidationError,
+    };
+    use serde_json::{json, Map, Value};
+    use std::iter;
+    use test_case::test_case;
+
+    /// Checks that numbers have at most the given number of decimal places.
+    struct CurrencyPrecision {
+        precision: u64,
+        schema_path: JSONPointer,
+    }
+    impl Keyword for CurrencyPrecision {
+        fn validate<'instance>(
+            &self,
+            instance: &'instance Value,
+            instance_path: &JSONPointer,
+        ) -> ErrorIterator<'instance> {
+            if self.is_valid(instance) {
+                Box::new(iter::empty())
+            } else {
+                Box::new(iter::once(ValidationError::custom(
+                    self.schema_path.clone(),
+                    instance_path.clone(),
+                    instance,
+                    format!(
+                        "{} has more than {} decimal places",
+                        instance, self.precision
+                    ),
+                )))
+            }
+        }
+
+        fn is_valid(&self, instance: &Value) -> bool {
+            if let Value::Number(number) = instance {
+                match number.to_string().split('.').nth(1) {
+                    Some(decimals) => decimals.len() as u64 <= self.precision,
+                    None => true,
+                }
+            } else {
+                true
+            }
+        }
+    }
+
+    fn currency_precision(
+        _: &Map<String, Value>,
+        value: &Value,
+        schema_path: JSONPointer,
+    ) -> Result<Box<dyn Keyword>, CompilationError> {
+        match value.as_u64() {
+            Some(precision) => Ok(Box::new(CurrencyPrecision {
+                precision,
+                schema_path,
+            })),
+            None => Err(CompilationError::invalid_value(schema_path, value)),
+        }
+    }
+
+    #[test_case(&json!(1.25), true)]
+    #[test_case(&json!(1.255), false)]
+    #[test_case(&json!({"price": 1.255}), false)]
+    #[test_case(&json!({"price": 1}), true)]
+    #[test_case(&json!({"price": "1.255"}), false; "not a number")]
+    fn custom_keyword(instance: &Value, expected: bool) {
+        let schema = json!({
+            "x-currency-precision": 2,
+            "properties": {"price": {"type": "number", "x-currency-precision": 2}}
+        });
+        let compiled = JSONSchema::options()
+            .with_keyword("x-currency-precision", currency_precision)
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn custom_keyword_error() {
+        let schema = json!({"properties": {"price": {"x-currency-precision": 2}}});
+        let compiled = JSONSchema::options()
+            .with_keyword("x-currency-precision", currency_precision)
+            .compile(&schema)
+            .unwrap();
+        let instance = json!({"price": 1.255});
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "1.255 has more than 2 decimal places"
+        );
+        assert_eq!(errors[0].instance_path.to_string(), "/price");
+        assert_eq!(
+            errors[0].schema_path.to_string(),
+            "/properties/price/x-currency-precision"
+        );
+    }
+
+    #[test]
+    fn custom_keyword_invalid_value() {
+        let schema = json!({"properties": {"price": {"x-currency-precision": "two"}}});
+        let error = JSONSchema::options()
+            .with_keyword("x-currency-precision", currency_precision)
+            .compile(&schema)
+            .expect_err("The keyword value is invalid");
+        assert_eq!(
+            error.schema_path.to_string(),
+            "/properties/price/x-currency-precision"
+        );
+    }
+
+    #[test]
+    fn custom_keyword_uses_parent() {
+        // `x-unique-by` requires array items to have unique values of the given property
+        struct UniqueBy {
+            property: String,
+            schema_path: JSONPointer,
+        }
+        impl Keyword for UniqueBy {
+            fn validate<'instance>(
+                &self,
+                instance: &'instance Value,
+                instance_path: &JSONPointer,
+            ) -> ErrorIterator<'instance> {
+                if self.is_valid(instance) {
+                    Box::new(iter::empty())
+                } else {
+                    Box::new(iter::once(ValidationError::custom(
+                        self.schema_path.clone(),
+                        instance_path.clone(),
+                        instance,
+                        format!("Items are not unique by '{}'", self.property),
+                    )))
+                }
+            }
+
+            fn is_valid(&self, instance: &Value) -> bool {
+                if let Value::Array(items) = instance {
+                    let mut seen = Vec::with_capacity(items.len());
+                    for item in items {
+                        let value = item.get(&self.property);
+                        if seen.contains(&value) {
+                            return false;
+                        }
+                        seen.push(value)
+                    }
+                }
+                true
+            }
+        }
+        let schema = json!({"type": "array", "x-unique-by": "id"});
+        let compiled = JSONSchema::options()
+            .with_keyword("x-unique-by", |parent, value, schema_path| {
+                // Only applies to arrays
+                assert_eq!(parent.get("type"), Some(&json!("array")));
+                match value {
+                    Value::String(property) => {
+                        let keyword: Box<dyn Keyword> = Box::new(UniqueBy {
+                            property: property.clone(),
+                            schema_path,
+                        });
+                        Ok(keyword)
+                    }
+                    _ => Err(CompilationError::invalid_value(schema_path, value)),
+                }
+            })
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!([{"id": 1}, {"id": 2}])));
+        let instance = json!([{"id": 1}, {"id": 1}]);
+        assert!(!compiled.is_valid(&instance));
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "Items are not unique by 'id'");
+        assert_eq!(errors[0].schema_path.to_string(), "/x-unique-by");
+    }
+}
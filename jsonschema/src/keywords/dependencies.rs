@@ -4,7 +4,8 @@ use crate::{
     keywords::{
         format_key_value_validators, required::RequiredValidator, CompilationResult, Validators,
     },
-    validator::Validate,
+    paths::InstancePath,
+    validator::{annotate_subschema, evaluate_subschema, Annotations, Evaluated, Validate},
 };
 use serde_json::{Map, Value};
 
@@ -16,17 +17,25 @@ impl DependenciesValidator {
     #[inline]
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Object(map) = schema {
+            let keyword_context = context.with_path("dependencies");
             let mut dependencies = Vec::with_capacity(map.len());
             for (key, subschema) in map {
+                let item_context = keyword_context.with_path(key.as_str());
                 let s = match subschema {
-                    Value::Array(_) => vec![RequiredValidator::compile(subschema)?],
-                    _ => compile_validators(subschema, context)?,
+                    Value::Array(_) => vec![RequiredValidator::compile(
+                        subschema,
+                        item_context.into_pointer(),
+                    )?],
+                    _ => compile_validators(subschema, &item_context)?,
                 };
                 dependencies.push((key.clone(), s))
             }
             Ok(Box::new(DependenciesValidator { dependencies }))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(
+                context.as_pointer_with("dependencies"),
+                schema,
+            ))
         }
     }
 }
@@ -47,16 +56,21 @@ impl Validate for DependenciesValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             let errors: Vec<_> = self
                 .dependencies
                 .iter()
                 .filter(|(property, _)| item.contains_key(property))
                 .flat_map(move |(_, validators)| {
-                    validators
-                        .iter()
-                        .flat_map(move |validator| validator.validate(schema, instance))
+                    validators.iter().flat_map(move |validator| {
+                        validator.validate(schema, instance, instance_path)
+                    })
                 })
                 .collect();
             // TODO. custom error message for "required" case
@@ -65,6 +79,45 @@ impl Validate for DependenciesValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        if let Value::Object(item) = instance {
+            for (property, validators) in &self.dependencies {
+                if item.contains_key(property) {
+                    evaluate_subschema(validators, schema, instance, evaluated);
+                }
+            }
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Object(item) = instance {
+            for (property, validators) in &self.dependencies {
+                if item.contains_key(property) {
+                    valid &= annotate_subschema(
+                        validators,
+                        schema,
+                        instance,
+                        instance_path,
+                        annotations,
+                    );
+                }
+            }
+        }
+        valid
+    }
 }
 
 impl ToString for DependenciesValidator {
@@ -84,3 +137,219 @@ pub(crate) fn compile(
 ) -> Option<CompilationResult> {
     Some(DependenciesValidator::compile(schema, context))
 }
+
+pub(crate) struct DependentRequiredValidator {
+    dependencies: Vec<(String, Validators)>,
+}
+
+impl DependentRequiredValidator {
+    #[inline]
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
+        if let Value::Object(map) = schema {
+            let keyword_context = context.with_path("dependentRequired");
+            let mut dependencies = Vec::with_capacity(map.len());
+            for (key, subschema) in map {
+                let item_context = keyword_context.with_path(key.as_str());
+                if let Value::Array(_) = subschema {
+                    dependencies.push((
+                        key.clone(),
+                        vec![RequiredValidator::compile(
+                            subschema,
+                            item_context.into_pointer(),
+                        )?],
+                    ))
+                } else {
+                    return Err(CompilationError::invalid_value(
+                        context.as_pointer_with("dependentRequired"),
+                        schema,
+                    ));
+                }
+            }
+            Ok(Box::new(DependentRequiredValidator { dependencies }))
+        } else {
+            Err(CompilationError::invalid_value(
+                context.as_pointer_with("dependentRequired"),
+                schema,
+            ))
+        }
+    }
+}
+
+impl Validate for DependentRequiredValidator {
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Object(item) = instance {
+            self.dependencies
+                .iter()
+                .filter(|(property, _)| item.contains_key(property))
+                .all(move |(_, validators)| {
+                    validators
+                        .iter()
+                        .all(move |validator| validator.is_valid(schema, instance))
+                })
+        } else {
+            true
+        }
+    }
+
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::Object(item) = instance {
+            let errors: Vec<_> = self
+                .dependencies
+                .iter()
+                .filter(|(property, _)| item.contains_key(property))
+                .flat_map(move |(_, validators)| {
+                    validators.iter().flat_map(move |validator| {
+                        validator.validate(schema, instance, instance_path)
+                    })
+                })
+                .collect();
+            Box::new(errors.into_iter())
+        } else {
+            no_error()
+        }
+    }
+}
+
+impl ToString for DependentRequiredValidator {
+    fn to_string(&self) -> String {
+        format!(
+            "dependentRequired: {{{}}}",
+            format_key_value_validators(&self.dependencies)
+        )
+    }
+}
+
+pub(crate) struct DependentSchemasValidator {
+    dependencies: Vec<(String, Validators)>,
+}
+
+impl DependentSchemasValidator {
+    #[inline]
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
+        if let Value::Object(map) = schema {
+            let keyword_context = context.with_path("dependentSchemas");
+            let mut dependencies = Vec::with_capacity(map.len());
+            for (key, subschema) in map {
+                let item_context = keyword_context.with_path(key.as_str());
+                dependencies.push((key.clone(), compile_validators(subschema, &item_context)?))
+            }
+            Ok(Box::new(DependentSchemasValidator { dependencies }))
+        } else {
+            Err(CompilationError::invalid_value(
+                context.as_pointer_with("dependentSchemas"),
+                schema,
+            ))
+        }
+    }
+}
+
+impl Validate for DependentSchemasValidator {
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Object(item) = instance {
+            self.dependencies
+                .iter()
+                .filter(|(property, _)| item.contains_key(property))
+                .all(move |(_, validators)| {
+                    validators
+                        .iter()
+                        .all(move |validator| validator.is_valid(schema, instance))
+                })
+        } else {
+            true
+        }
+    }
+
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::Object(item) = instance {
+            let errors: Vec<_> = self
+                .dependencies
+                .iter()
+                .filter(|(property, _)| item.contains_key(property))
+                .flat_map(move |(_, validators)| {
+                    validators.iter().flat_map(move |validator| {
+                        validator.validate(schema, instance, instance_path)
+                    })
+                })
+                .collect();
+            Box::new(errors.into_iter())
+        } else {
+            no_error()
+        }
+    }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        if let Value::Object(item) = instance {
+            for (property, validators) in &self.dependencies {
+                if item.contains_key(property) {
+                    evaluate_subschema(validators, schema, instance, evaluated);
+                }
+            }
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Object(item) = instance {
+            for (property, validators) in &self.dependencies {
+                if item.contains_key(property) {
+                    valid &= annotate_subschema(
+                        validators,
+                        schema,
+                        instance,
+                        instance_path,
+                        annotations,
+                    );
+                }
+            }
+        }
+        valid
+    }
+}
+
+impl ToString for DependentSchemasValidator {
+    fn to_string(&self) -> String {
+        format!(
+            "dependentSchemas: {{{}}}",
+            format_key_value_validators(&self.dependencies)
+        )
+    }
+}
+
+#[inline]
+pub(crate) fn compile_dependent_required(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    Some(DependentRequiredValidator::compile(schema, context))
+}
+
+#[inline]
+pub(crate) fn compile_dependent_schemas(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    Some(DependentSchemasValidator::compile(schema, context))
+}
@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::{helpers, CompilationResult},
+    paths::{InstancePath, JSONPointer},
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
     validator::Validate,
 };
@@ -13,11 +14,12 @@ pub(crate) struct EnumValidator {
     // Types that occur in items
     types: PrimitiveTypesBitMap,
     items: Vec<Value>,
+    schema_path: JSONPointer,
 }
 
 impl EnumValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, schema_path: JSONPointer) -> CompilationResult {
         if let Value::Array(items) = schema {
             let mut types = PrimitiveTypesBitMap::new();
             for item in items.iter() {
@@ -27,17 +29,28 @@ impl EnumValidator {
                 options: schema.clone(),
                 items: items.clone(),
                 types,
+                schema_path,
             }))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(schema_path, schema))
         }
     }
 }
 
 impl Validate for EnumValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if !self.is_valid(schema, instance) {
-            error(ValidationError::enumeration(instance, &self.options))
+            error(ValidationError::enumeration(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                &self.options,
+            ))
         } else {
             no_error()
         }
@@ -72,7 +85,8 @@ impl ToString for EnumValidator {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(EnumValidator::compile(schema))
+    let schema_path = context.as_pointer_with("enum");
+    Some(EnumValidator::compile(schema, schema_path))
 }
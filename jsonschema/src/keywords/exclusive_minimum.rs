@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use num_cmp::NumCmp;
@@ -9,12 +10,15 @@ use serde_json::{Map, Value};
 
 pub(crate) struct ExclusiveMinimumU64Validator {
     limit: u64,
+    schema_path: JSONPointer,
 }
 pub(crate) struct ExclusiveMinimumI64Validator {
     limit: i64,
+    schema_path: JSONPointer,
 }
 pub(crate) struct ExclusiveMinimumF64Validator {
     limit: f64,
+    schema_path: JSONPointer,
 }
 
 macro_rules! validate {
@@ -24,11 +28,14 @@ macro_rules! validate {
                 &self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
+                instance_path: &InstancePath,
             ) -> ErrorIterator<'a> {
                 if self.is_valid(schema, instance) {
                     no_error()
                 } else {
                     error(ValidationError::exclusive_minimum(
+                        self.schema_path.clone(),
+                        instance_path.into(),
                         instance,
                         self.limit as f64,
                     ))
@@ -75,11 +82,21 @@ impl Validate for ExclusiveMinimumF64Validator {
         true
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::exclusive_minimum(instance, self.limit))
+            error(ValidationError::exclusive_minimum(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                self.limit,
+            ))
         }
     }
 }
@@ -93,19 +110,39 @@ impl ToString for ExclusiveMinimumF64Validator {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    let schema_path = context.as_pointer_with("exclusiveMinimum");
+    compile_with_path(schema, schema_path)
+}
+
+/// Draft 4 expresses exclusive limits via boolean flags, therefore the keyword location
+/// may differ from `exclusiveMinimum`.
+#[inline]
+pub(crate) fn compile_with_path(
+    schema: &Value,
+    schema_path: JSONPointer,
 ) -> Option<CompilationResult> {
     if let Value::Number(limit) = schema {
         if let Some(limit) = limit.as_u64() {
-            Some(Ok(Box::new(ExclusiveMinimumU64Validator { limit })))
+            Some(Ok(Box::new(ExclusiveMinimumU64Validator {
+                limit,
+                schema_path,
+            })))
         } else if let Some(limit) = limit.as_i64() {
-            Some(Ok(Box::new(ExclusiveMinimumI64Validator { limit })))
+            Some(Ok(Box::new(ExclusiveMinimumI64Validator {
+                limit,
+                schema_path,
+            })))
         } else {
             let limit = limit.as_f64().expect("Always valid");
-            Some(Ok(Box::new(ExclusiveMinimumF64Validator { limit })))
+            Some(Ok(Box::new(ExclusiveMinimumF64Validator {
+                limit,
+                schema_path,
+            })))
         }
     } else {
-        Some(Err(CompilationError::SchemaError))
+        Some(Err(CompilationError::invalid_value(schema_path, schema)))
     }
 }
 
@@ -3,13 +3,14 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
     Draft,
 };
 use chrono::{DateTime, NaiveDate};
 use regex::Regex;
 use serde_json::{Map, Value};
-use std::{net::IpAddr, str::FromStr};
+use std::{net::IpAddr, str::FromStr, sync::Arc};
 use url::Url;
 
 lazy_static::lazy_static! {
@@ -34,10 +35,12 @@ lazy_static::lazy_static! {
 
 macro_rules! format_validator {
     ($validator:ident, $format_name:tt) => {
-        struct $validator {}
+        struct $validator {
+            schema_path: JSONPointer,
+        }
         impl $validator {
-            pub(crate) fn compile() -> CompilationResult {
-                Ok(Box::new($validator {}))
+            pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+                Ok(Box::new($validator { schema_path }))
             }
         }
 
@@ -51,10 +54,20 @@ macro_rules! format_validator {
 
 macro_rules! validate {
     ($format:expr) => {
-        fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        fn validate<'a>(
+            &self,
+            schema: &'a JSONSchema,
+            instance: &'a Value,
+            instance_path: &InstancePath,
+        ) -> ErrorIterator<'a> {
             if let Value::String(_item) = instance {
                 if !self.is_valid(schema, instance) {
-                    return error(ValidationError::format(instance, $format));
+                    return error(ValidationError::format(
+                        self.schema_path.clone(),
+                        instance_path.into(),
+                        instance,
+                        $format,
+                    ));
                 }
             }
             no_error()
@@ -278,6 +291,61 @@ impl Validate for URITemplateValidator {
     }
 }
 
+/// A user-defined check for the `format` keyword.
+pub(crate) type FormatCheck = dyn Fn(&str) -> bool + Send + Sync;
+
+struct CustomFormatValidator {
+    schema_path: JSONPointer,
+    format_name: String,
+    check: Arc<FormatCheck>,
+}
+impl CustomFormatValidator {
+    pub(crate) fn compile(
+        schema_path: JSONPointer,
+        format_name: String,
+        check: Arc<FormatCheck>,
+    ) -> CompilationResult {
+        Ok(Box::new(CustomFormatValidator {
+            schema_path,
+            format_name,
+            check,
+        }))
+    }
+}
+impl Validate for CustomFormatValidator {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::String(_item) = instance {
+            if !self.is_valid(schema, instance) {
+                return error(ValidationError::format(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    self.format_name.clone(),
+                ));
+            }
+        }
+        no_error()
+    }
+
+    fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
+        if let Value::String(item) = instance {
+            (self.check)(item)
+        } else {
+            true
+        }
+    }
+}
+impl ToString for CustomFormatValidator {
+    fn to_string(&self) -> String {
+        format!("format: {}", self.format_name)
+    }
+}
+
 #[inline]
 pub(crate) fn compile(
     _: &Map<String, Value>,
@@ -285,48 +353,65 @@ pub(crate) fn compile(
     context: &CompilationContext,
 ) -> Option<CompilationResult> {
     if let Value::String(format) = schema {
+        let schema_path = context.as_pointer_with("format");
+        if let Some(check) = context.config.format(format) {
+            return Some(CustomFormatValidator::compile(
+                schema_path,
+                format.clone(),
+                check.clone(),
+            ));
+        }
         let draft_version = context.config.draft();
+        let is_draft_7_or_later = matches!(
+            draft_version,
+            Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012
+        );
+        let is_draft_6_or_later = draft_version != Draft::Draft4;
         match format.as_str() {
-            "date-time" => Some(DateTimeValidator::compile()),
-            "date" => Some(DateValidator::compile()),
-            "email" => Some(EmailValidator::compile()),
-            "hostname" => Some(HostnameValidator::compile()),
-            "idn-email" => Some(IDNEmailValidator::compile()),
-            "idn-hostname" if draft_version == Draft::Draft7 => {
-                Some(IDNHostnameValidator::compile())
+            "date-time" => Some(DateTimeValidator::compile(schema_path)),
+            "date" => Some(DateValidator::compile(schema_path)),
+            "email" => Some(EmailValidator::compile(schema_path)),
+            "hostname" => Some(HostnameValidator::compile(schema_path)),
+            "idn-email" => Some(IDNEmailValidator::compile(schema_path)),
+            "idn-hostname" if is_draft_7_or_later => {
+                Some(IDNHostnameValidator::compile(schema_path))
             }
-            "ipv4" => Some(IpV4Validator::compile()),
-            "ipv6" => Some(IpV6Validator::compile()),
-            "iri-reference" if draft_version == Draft::Draft7 => {
-                Some(IRIReferenceValidator::compile())
+            "ipv4" => Some(IpV4Validator::compile(schema_path)),
+            "ipv6" => Some(IpV6Validator::compile(schema_path)),
+            "iri-reference" if is_draft_7_or_later => {
+                Some(IRIReferenceValidator::compile(schema_path))
             }
-            "iri" if draft_version == Draft::Draft7 => Some(IRIValidator::compile()),
-            "json-pointer" if draft_version == Draft::Draft6 || draft_version == Draft::Draft7 => {
-                Some(JSONPointerValidator::compile())
+            "iri" if is_draft_7_or_later => Some(IRIValidator::compile(schema_path)),
+            "json-pointer" if is_draft_6_or_later => {
+                Some(JSONPointerValidator::compile(schema_path))
             }
-            "regex" => Some(RegexValidator::compile()),
-            "relative-json-pointer" if draft_version == Draft::Draft7 => {
-                Some(RelativeJSONPointerValidator::compile())
+            "regex" => Some(RegexValidator::compile(schema_path)),
+            "relative-json-pointer" if is_draft_7_or_later => {
+                Some(RelativeJSONPointerValidator::compile(schema_path))
             }
-            "time" => Some(TimeValidator::compile()),
-            "uri-reference" if draft_version == Draft::Draft6 || draft_version == Draft::Draft7 => {
-                Some(URIReferenceValidator::compile())
+            "time" => Some(TimeValidator::compile(schema_path)),
+            "uri-reference" if is_draft_6_or_later => {
+                Some(URIReferenceValidator::compile(schema_path))
             }
-            "uri-template" if draft_version == Draft::Draft6 || draft_version == Draft::Draft7 => {
-                Some(URITemplateValidator::compile())
+            "uri-template" if is_draft_6_or_later => {
+                Some(URITemplateValidator::compile(schema_path))
             }
-            "uri" => Some(URIValidator::compile()),
+            "uri" => Some(URIValidator::compile(schema_path)),
             _ => None,
         }
     } else {
-        Some(Err(CompilationError::SchemaError))
+        Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("format"),
+            schema,
+        )))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::compilation::JSONSchema;
-    use serde_json::json;
+    use crate::{compilation::JSONSchema, error::ValidationErrorKind};
+    use serde_json::{json, Value};
+    use test_case::test_case;
 
     #[test]
     fn ignored_format() {
@@ -335,4 +420,51 @@ mod tests {
         let compiled = JSONSchema::compile(&schema).unwrap();
         assert!(compiled.is_valid(&instance))
     }
+
+    #[test_case("e164-phone", &json!("+14155552671"), true)]
+    #[test_case("e164-phone", &json!("4155552671"), false)]
+    #[test_case("e164-phone", &json!(42), true)]
+    // Built-in formats can be overridden
+    #[test_case("email", &json!("foo@example.com"), true)]
+    #[test_case("email", &json!("foo@localhost"), false)]
+    fn custom_format(format: &str, instance: &Value, expected: bool) {
+        let schema = json!({"format": format});
+        let compiled = JSONSchema::options()
+            .with_format("e164-phone", |value| {
+                value.starts_with('+')
+                    && value.len() <= 16
+                    && value[1..].chars().all(|c| c.is_ascii_digit())
+            })
+            .with_format("email", |value| {
+                value
+                    .split('@')
+                    .nth(1)
+                    .map_or(false, |domain| domain.contains('.'))
+            })
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn custom_format_error() {
+        let schema = json!({"properties": {"id": {"format": "internal-id"}}});
+        // Names of custom formats are not necessarily known at compile time
+        let name = String::from("internal-id");
+        let compiled = JSONSchema::options()
+            .with_format(name, |value| value.starts_with("ID-"))
+            .compile(&schema)
+            .unwrap();
+        let instance = json!({"id": "42"});
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        if let ValidationErrorKind::Format { format } = &errors[0].kind {
+            assert_eq!(format, "internal-id")
+        } else {
+            panic!("Should be a format error")
+        }
+        assert_eq!(errors[0].to_string(), r#"'"42"' is not a 'internal-id'"#);
+        assert_eq!(errors[0].schema_path.to_string(), "/properties/id/format");
+    }
 }
@@ -2,10 +2,62 @@ use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{no_error, ErrorIterator},
     keywords::{format_validators, CompilationResult, Validators},
-    validator::Validate,
+    paths::InstancePath,
+    validator::{
+        annotate_if_valid, annotate_subschema, evaluate_subschema, Annotations, Evaluated, Validate,
+    },
 };
 use serde_json::{Map, Value};
 
+pub(crate) struct IfValidator {
+    schema: Validators,
+}
+
+impl IfValidator {
+    #[inline]
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
+        Ok(Box::new(IfValidator {
+            schema: compile_validators(schema, &context.with_path("if"))?,
+        }))
+    }
+}
+
+impl Validate for IfValidator {
+    fn is_valid(&self, _: &JSONSchema, _: &Value) -> bool {
+        true
+    }
+
+    fn validate<'a>(&self, _: &'a JSONSchema, _: &'a Value, _: &InstancePath) -> ErrorIterator<'a> {
+        no_error()
+    }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        evaluate_subschema(&self.schema, schema, instance, evaluated);
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotate_if_valid(&self.schema, schema, instance, instance_path, annotations);
+        true
+    }
+}
+
+impl ToString for IfValidator {
+    fn to_string(&self) -> String {
+        format!("if: {}", format_validators(&self.schema))
+    }
+}
+
 pub(crate) struct IfThenValidator {
     schema: Validators,
     then_schema: Validators,
@@ -19,8 +71,8 @@ impl IfThenValidator {
         context: &CompilationContext,
     ) -> CompilationResult {
         Ok(Box::new(IfThenValidator {
-            schema: compile_validators(schema, context)?,
-            then_schema: compile_validators(then_schema, context)?,
+            schema: compile_validators(schema, &context.with_path("if"))?,
+            then_schema: compile_validators(then_schema, &context.with_path("then"))?,
         }))
     }
 }
@@ -40,7 +92,12 @@ impl Validate for IfThenValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self
             .schema
             .iter()
@@ -49,13 +106,44 @@ impl Validate for IfThenValidator {
             let errors: Vec<_> = self
                 .then_schema
                 .iter()
-                .flat_map(move |validator| validator.validate(schema, instance))
+                .flat_map(move |validator| validator.validate(schema, instance, instance_path))
                 .collect();
             Box::new(errors.into_iter())
         } else {
             no_error()
         }
     }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        if evaluate_subschema(&self.schema, schema, instance, evaluated) {
+            evaluate_subschema(&self.then_schema, schema, instance, evaluated);
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        if annotate_if_valid(&self.schema, schema, instance, instance_path, annotations) {
+            annotate_subschema(
+                &self.then_schema,
+                schema,
+                instance,
+                instance_path,
+                annotations,
+            )
+        } else {
+            true
+        }
+    }
 }
 
 impl ToString for IfThenValidator {
@@ -81,8 +169,8 @@ impl IfElseValidator {
         context: &CompilationContext,
     ) -> CompilationResult {
         Ok(Box::new(IfElseValidator {
-            schema: compile_validators(schema, context)?,
-            else_schema: compile_validators(else_schema, context)?,
+            schema: compile_validators(schema, &context.with_path("if"))?,
+            else_schema: compile_validators(else_schema, &context.with_path("else"))?,
         }))
     }
 }
@@ -102,7 +190,12 @@ impl Validate for IfElseValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self
             .schema
             .iter()
@@ -111,13 +204,44 @@ impl Validate for IfElseValidator {
             let errors: Vec<_> = self
                 .else_schema
                 .iter()
-                .flat_map(move |validator| validator.validate(schema, instance))
+                .flat_map(move |validator| validator.validate(schema, instance, instance_path))
                 .collect();
             Box::new(errors.into_iter())
         } else {
             no_error()
         }
     }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        if !evaluate_subschema(&self.schema, schema, instance, evaluated) {
+            evaluate_subschema(&self.else_schema, schema, instance, evaluated);
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        if annotate_if_valid(&self.schema, schema, instance, instance_path, annotations) {
+            true
+        } else {
+            annotate_subschema(
+                &self.else_schema,
+                schema,
+                instance,
+                instance_path,
+                annotations,
+            )
+        }
+    }
 }
 
 impl ToString for IfElseValidator {
@@ -145,9 +269,9 @@ impl IfThenElseValidator {
         context: &CompilationContext,
     ) -> CompilationResult {
         Ok(Box::new(IfThenElseValidator {
-            schema: compile_validators(schema, context)?,
-            then_schema: compile_validators(then_schema, context)?,
-            else_schema: compile_validators(else_schema, context)?,
+            schema: compile_validators(schema, &context.with_path("if"))?,
+            then_schema: compile_validators(then_schema, &context.with_path("then"))?,
+            else_schema: compile_validators(else_schema, &context.with_path("else"))?,
         }))
     }
 }
@@ -169,7 +293,12 @@ impl Validate for IfThenElseValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self
             .schema
             .iter()
@@ -178,18 +307,57 @@ impl Validate for IfThenElseValidator {
             let errors: Vec<_> = self
                 .then_schema
                 .iter()
-                .flat_map(move |validator| validator.validate(schema, instance))
+                .flat_map(move |validator| validator.validate(schema, instance, instance_path))
                 .collect();
             Box::new(errors.into_iter())
         } else {
             let errors: Vec<_> = self
                 .else_schema
                 .iter()
-                .flat_map(move |validator| validator.validate(schema, instance))
+                .flat_map(move |validator| validator.validate(schema, instance, instance_path))
                 .collect();
             Box::new(errors.into_iter())
         }
     }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        if evaluate_subschema(&self.schema, schema, instance, evaluated) {
+            evaluate_subschema(&self.then_schema, schema, instance, evaluated);
+        } else {
+            evaluate_subschema(&self.else_schema, schema, instance, evaluated);
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        if annotate_if_valid(&self.schema, schema, instance, instance_path, annotations) {
+            annotate_subschema(
+                &self.then_schema,
+                schema,
+                instance,
+                instance_path,
+                annotations,
+            )
+        } else {
+            annotate_subschema(
+                &self.else_schema,
+                schema,
+                instance,
+                instance_path,
+                annotations,
+            )
+        }
+    }
 }
 
 impl ToString for IfThenElseValidator {
@@ -220,6 +388,10 @@ pub(crate) fn compile(
         )),
         (None, Some(else_schema)) => Some(IfElseValidator::compile(schema, else_schema, context)),
         (Some(then_schema), None) => Some(IfThenValidator::compile(schema, then_schema, context)),
+        // A sole `if` affects only annotations
+        (None, None) if context.config.draft().collects_annotations() => {
+            Some(IfValidator::compile(schema, context))
+        }
         (None, None) => None,
     }
 }
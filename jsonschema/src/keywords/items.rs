@@ -1,11 +1,12 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
-    error::{no_error, ErrorIterator},
+    error::{no_error, CompilationError, ErrorIterator},
     keywords::{
         boolean::TrueValidator, format_validators, format_vec_of_validators, CompilationResult,
         Validators,
     },
-    validator::Validate,
+    paths::InstancePath,
+    validator::{annotate_subschema, Annotations, Evaluated, Validate},
 };
 use serde_json::{Map, Value};
 
@@ -15,9 +16,10 @@ pub(crate) struct ItemsArrayValidator {
 impl ItemsArrayValidator {
     #[inline]
     pub(crate) fn compile(schemas: &[Value], context: &CompilationContext) -> CompilationResult {
+        let keyword_context = context.with_path("items");
         let mut items = Vec::with_capacity(schemas.len());
-        for item in schemas {
-            let validators = compile_validators(item, context)?;
+        for (idx, item) in schemas.iter().enumerate() {
+            let validators = compile_validators(item, &keyword_context.with_path(idx))?;
             items.push(validators)
         }
         Ok(Box::new(ItemsArrayValidator { items }))
@@ -39,15 +41,22 @@ impl Validate for ItemsArrayValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
             let errors: Vec<_> = items
                 .iter()
                 .zip(self.items.iter())
-                .flat_map(move |(item, validators)| {
+                .enumerate()
+                .flat_map(move |(idx, (item, validators))| {
+                    let instance_path = instance_path.push(idx);
                     validators
                         .iter()
-                        .flat_map(move |validator| validator.validate(schema, item))
+                        .flat_map(move |validator| validator.validate(schema, item, &instance_path))
                 })
                 .collect();
             Box::new(errors.into_iter())
@@ -55,6 +64,32 @@ impl Validate for ItemsArrayValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.items_until(instance, self.items.len())
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Array(items) = instance {
+            for (idx, (item, validators)) in items.iter().zip(self.items.iter()).enumerate() {
+                valid &= annotate_subschema(
+                    validators,
+                    schema,
+                    item,
+                    &instance_path.push(idx),
+                    annotations,
+                );
+            }
+        }
+        valid
+    }
 }
 
 impl ToString for ItemsArrayValidator {
@@ -69,7 +104,8 @@ pub(crate) struct ItemsObjectValidator {
 impl ItemsObjectValidator {
     #[inline]
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
-        let validators = compile_validators(schema, context)?;
+        let keyword_context = context.with_path("items");
+        let validators = compile_validators(schema, &keyword_context)?;
         Ok(Box::new(ItemsObjectValidator { validators }))
     }
 }
@@ -86,15 +122,20 @@ impl Validate for ItemsObjectValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
             let errors: Vec<_> = self
                 .validators
                 .iter()
                 .flat_map(move |validator| {
-                    items
-                        .iter()
-                        .flat_map(move |item| validator.validate(schema, item))
+                    items.iter().enumerate().flat_map(move |(idx, item)| {
+                        validator.validate(schema, item, &instance_path.push(idx))
+                    })
                 })
                 .collect();
             Box::new(errors.into_iter())
@@ -102,6 +143,32 @@ impl Validate for ItemsObjectValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.items_from(instance, 0)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate() {
+                valid &= annotate_subschema(
+                    &self.validators,
+                    schema,
+                    item,
+                    &instance_path.push(idx),
+                    annotations,
+                );
+            }
+        }
+        valid
+    }
 }
 
 impl ToString for ItemsObjectValidator {
@@ -110,6 +177,96 @@ impl ToString for ItemsObjectValidator {
     }
 }
 
+/// `items` since Draft 2020-12. It applies only to items that are not covered by `prefixItems`.
+pub(crate) struct ItemsObjectSkipPrefixValidator {
+    validators: Validators,
+    skip_prefix: usize,
+}
+impl ItemsObjectSkipPrefixValidator {
+    #[inline]
+    pub(crate) fn compile(
+        schema: &Value,
+        skip_prefix: usize,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        let keyword_context = context.with_path("items");
+        let validators = compile_validators(schema, &keyword_context)?;
+        Ok(Box::new(ItemsObjectSkipPrefixValidator {
+            validators,
+            skip_prefix,
+        }))
+    }
+}
+impl Validate for ItemsObjectSkipPrefixValidator {
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Array(items) = instance {
+            items.iter().skip(self.skip_prefix).all(|item| {
+                self.validators
+                    .iter()
+                    .all(move |validator| validator.is_valid(schema, item))
+            })
+        } else {
+            true
+        }
+    }
+
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::Array(items) = instance {
+            let errors: Vec<_> = items
+                .iter()
+                .enumerate()
+                .skip(self.skip_prefix)
+                .flat_map(|(idx, item)| {
+                    let instance_path = instance_path.push(idx);
+                    self.validators
+                        .iter()
+                        .flat_map(move |validator| validator.validate(schema, item, &instance_path))
+                })
+                .collect();
+            Box::new(errors.into_iter())
+        } else {
+            no_error()
+        }
+    }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.items_from(instance, self.skip_prefix)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate().skip(self.skip_prefix) {
+                valid &= annotate_subschema(
+                    &self.validators,
+                    schema,
+                    item,
+                    &instance_path.push(idx),
+                    annotations,
+                );
+            }
+        }
+        valid
+    }
+}
+
+impl ToString for ItemsObjectSkipPrefixValidator {
+    fn to_string(&self) -> String {
+        format!("items: {}", format_validators(&self.validators))
+    }
+}
+
 #[inline]
 pub(crate) fn compile(
     _: &Map<String, Value>,
@@ -120,7 +277,7 @@ pub(crate) fn compile(
         Value::Array(items) => Some(ItemsArrayValidator::compile(items, context)),
         Value::Object(_) => Some(ItemsObjectValidator::compile(schema, context)),
         Value::Bool(value) => {
-            if *value {
+            if *value && !context.config.draft().collects_annotations() {
                 Some(TrueValidator::compile())
             } else {
                 Some(ItemsObjectValidator::compile(schema, context))
@@ -129,3 +286,27 @@ pub(crate) fn compile(
         _ => None,
     }
 }
+
+#[inline]
+pub(crate) fn compile_with_prefix(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::Object(_) | Value::Bool(_) => {
+            match parent.get("prefixItems").and_then(Value::as_array) {
+                Some(prefix_items) => Some(ItemsObjectSkipPrefixValidator::compile(
+                    schema,
+                    prefix_items.len(),
+                    context,
+                )),
+                None => Some(ItemsObjectValidator::compile(schema, context)),
+            }
+        }
+        _ => Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("items"),
+            schema,
+        ))),
+    }
+}
@@ -11,7 +11,7 @@ pub(crate) fn compile(
     context: &CompilationContext,
 ) -> Option<CompilationResult> {
     if let Some(Value::Bool(true)) = parent.get("exclusiveMaximum") {
-        exclusive_maximum::compile(parent, schema, context)
+        exclusive_maximum::compile_with_path(schema, context.as_pointer_with("maximum"))
     } else {
         maximum::compile(parent, schema, context)
     }
@@ -11,7 +11,7 @@ pub(crate) fn compile(
     context: &CompilationContext,
 ) -> Option<CompilationResult> {
     if let Some(Value::Bool(true)) = parent.get("exclusiveMinimum") {
-        exclusive_minimum::compile(parent, schema, context)
+        exclusive_minimum::compile_with_path(schema, context.as_pointer_with("minimum"))
     } else {
         minimum::compile(parent, schema, context)
     }
@@ -2,19 +2,21 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::{type_, CompilationResult},
+    paths::{InstancePath, JSONPointer},
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
-    validator::Validate,
+    validator::{Annotations, Validate},
 };
 use serde_json::{Map, Number, Value};
 use std::convert::TryFrom;
 
 pub(crate) struct MultipleTypesValidator {
     types: PrimitiveTypesBitMap,
+    schema_path: JSONPointer,
 }
 
 impl MultipleTypesValidator {
     #[inline]
-    pub(crate) fn compile(items: &[Value]) -> CompilationResult {
+    pub(crate) fn compile(items: &[Value], schema_path: JSONPointer) -> CompilationResult {
         let mut types = PrimitiveTypesBitMap::new();
         for item in items {
             match item {
@@ -22,13 +24,21 @@ impl MultipleTypesValidator {
                     if let Ok(primitive_type) = PrimitiveType::try_from(string.as_str()) {
                         types |= primitive_type;
                     } else {
-                        return Err(CompilationError::SchemaError);
+                        return Err(CompilationError::invalid_value(
+                            schema_path,
+                            &Value::Array(items.to_vec()),
+                        ));
                     }
                 }
-                _ => return Err(CompilationError::SchemaError),
+                _ => {
+                    return Err(CompilationError::invalid_value(
+                        schema_path,
+                        &Value::Array(items.to_vec()),
+                    ))
+                }
             }
         }
-        Ok(Box::new(MultipleTypesValidator { types }))
+        Ok(Box::new(MultipleTypesValidator { types, schema_path }))
     }
 }
 
@@ -46,13 +56,34 @@ impl Validate for MultipleTypesValidator {
             Value::String(_) => self.types.contains_type(PrimitiveType::String),
         }
     }
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::multiple_type_error(instance, self.types))
+            error(ValidationError::multiple_type_error(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                self.types,
+            ))
         }
     }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotations.coercion(self.types, instance, instance_path);
+        self.is_valid(schema, instance)
+    }
 }
 
 impl ToString for MultipleTypesValidator {
@@ -68,12 +99,14 @@ impl ToString for MultipleTypesValidator {
     }
 }
 
-pub(crate) struct IntegerTypeValidator {}
+pub(crate) struct IntegerTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl IntegerTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(IntegerTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(IntegerTypeValidator { schema_path }))
     }
 }
 
@@ -86,16 +119,38 @@ impl Validate for IntegerTypeValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
+                instance_path.into(),
                 instance,
                 PrimitiveType::Integer,
             ))
         }
     }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotations.coercion(
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Integer),
+            instance,
+            instance_path,
+        );
+        self.is_valid(schema, instance)
+    }
 }
 
 impl ToString for IntegerTypeValidator {
@@ -112,34 +167,38 @@ fn is_integer(num: &Number) -> bool {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
+    let schema_path = context.as_pointer_with("type");
     match schema {
-        Value::String(item) => compile_single_type(item.as_str()),
+        Value::String(item) => compile_single_type(item.as_str(), schema_path),
         Value::Array(items) => {
             if items.len() == 1 {
                 if let Some(Value::String(item)) = items.iter().next() {
-                    compile_single_type(item.as_str())
+                    compile_single_type(item.as_str(), schema_path)
                 } else {
-                    Some(Err(CompilationError::SchemaError))
+                    Some(Err(CompilationError::invalid_value(schema_path, schema)))
                 }
             } else {
-                Some(MultipleTypesValidator::compile(items))
+                Some(MultipleTypesValidator::compile(items, schema_path))
             }
         }
-        _ => Some(Err(CompilationError::SchemaError)),
+        _ => Some(Err(CompilationError::invalid_value(schema_path, schema))),
     }
 }
 
-fn compile_single_type(item: &str) -> Option<CompilationResult> {
+fn compile_single_type(item: &str, schema_path: JSONPointer) -> Option<CompilationResult> {
     match PrimitiveType::try_from(item) {
-        Ok(PrimitiveType::Array) => Some(type_::ArrayTypeValidator::compile()),
-        Ok(PrimitiveType::Boolean) => Some(type_::BooleanTypeValidator::compile()),
-        Ok(PrimitiveType::Integer) => Some(IntegerTypeValidator::compile()),
-        Ok(PrimitiveType::Null) => Some(type_::NullTypeValidator::compile()),
-        Ok(PrimitiveType::Number) => Some(type_::NumberTypeValidator::compile()),
-        Ok(PrimitiveType::Object) => Some(type_::ObjectTypeValidator::compile()),
-        Ok(PrimitiveType::String) => Some(type_::StringTypeValidator::compile()),
-        Err(()) => Some(Err(CompilationError::SchemaError)),
+        Ok(PrimitiveType::Array) => Some(type_::ArrayTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::Boolean) => Some(type_::BooleanTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::Integer) => Some(IntegerTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::Null) => Some(type_::NullTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::Number) => Some(type_::NumberTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::Object) => Some(type_::ObjectTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::String) => Some(type_::StringTypeValidator::compile(schema_path)),
+        Err(()) => Some(Err(CompilationError::invalid_value(
+            schema_path,
+            &Value::String(item.to_string()),
+        ))),
     }
 }
@@ -2,21 +2,23 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
 
 pub(crate) struct MaxItemsValidator {
     limit: u64,
+    schema_path: JSONPointer,
 }
 
 impl MaxItemsValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, schema_path: JSONPointer) -> CompilationResult {
         if let Some(limit) = schema.as_u64() {
-            Ok(Box::new(MaxItemsValidator { limit }))
+            Ok(Box::new(MaxItemsValidator { limit, schema_path }))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(schema_path, schema))
         }
     }
 }
@@ -31,10 +33,20 @@ impl Validate for MaxItemsValidator {
         true
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
             if (items.len() as u64) > self.limit {
-                return error(ValidationError::max_items(instance, self.limit));
+                return error(ValidationError::max_items(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    self.limit,
+                ));
             }
         }
         no_error()
@@ -51,7 +63,8 @@ impl ToString for MaxItemsValidator {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(MaxItemsValidator::compile(schema))
+    let schema_path = context.as_pointer_with("maxItems");
+    Some(MaxItemsValidator::compile(schema, schema_path))
 }
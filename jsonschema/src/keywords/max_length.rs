@@ -2,21 +2,23 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
 
 pub(crate) struct MaxLengthValidator {
     limit: u64,
+    schema_path: JSONPointer,
 }
 
 impl MaxLengthValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, schema_path: JSONPointer) -> CompilationResult {
         if let Some(limit) = schema.as_u64() {
-            Ok(Box::new(MaxLengthValidator { limit }))
+            Ok(Box::new(MaxLengthValidator { limit, schema_path }))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(schema_path, schema))
         }
     }
 }
@@ -31,10 +33,20 @@ impl Validate for MaxLengthValidator {
         true
     }
 
-    fn validate<'a>(&self, _schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
             if (item.chars().count() as u64) > self.limit {
-                return error(ValidationError::max_length(instance, self.limit));
+                return error(ValidationError::max_length(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    self.limit,
+                ));
             }
         }
         no_error()
@@ -51,7 +63,8 @@ impl ToString for MaxLengthValidator {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(MaxLengthValidator::compile(schema))
+    let schema_path = context.as_pointer_with("maxLength");
+    Some(MaxLengthValidator::compile(schema, schema_path))
 }
@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use num_cmp::NumCmp;
@@ -9,12 +10,15 @@ use serde_json::{Map, Value};
 
 pub(crate) struct MaximumU64Validator {
     limit: u64,
+    schema_path: JSONPointer,
 }
 pub(crate) struct MaximumI64Validator {
     limit: i64,
+    schema_path: JSONPointer,
 }
 pub(crate) struct MaximumF64Validator {
     limit: f64,
+    schema_path: JSONPointer,
 }
 
 macro_rules! validate {
@@ -24,11 +28,17 @@ macro_rules! validate {
                 &self,
                 schema: &'a JSONSchema,
                 instance: &'a Value,
+                instance_path: &InstancePath,
             ) -> ErrorIterator<'a> {
                 if self.is_valid(schema, instance) {
                     no_error()
                 } else {
-                    error(ValidationError::maximum(instance, self.limit as f64)) // do not cast
+                    error(ValidationError::maximum(
+                        self.schema_path.clone(),
+                        instance_path.into(),
+                        instance,
+                        self.limit as f64,
+                    )) // do not cast
                 }
             }
 
@@ -72,11 +82,21 @@ impl Validate for MaximumF64Validator {
         true
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::maximum(instance, self.limit))
+            error(ValidationError::maximum(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                self.limit,
+            ))
         }
     }
 }
@@ -90,19 +110,20 @@ impl ToString for MaximumF64Validator {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
+    let schema_path = context.as_pointer_with("maximum");
     if let Value::Number(limit) = schema {
         if let Some(limit) = limit.as_u64() {
-            Some(Ok(Box::new(MaximumU64Validator { limit })))
+            Some(Ok(Box::new(MaximumU64Validator { limit, schema_path })))
         } else if let Some(limit) = limit.as_i64() {
-            Some(Ok(Box::new(MaximumI64Validator { limit })))
+            Some(Ok(Box::new(MaximumI64Validator { limit, schema_path })))
         } else {
             let limit = limit.as_f64().expect("Always valid");
-            Some(Ok(Box::new(MaximumF64Validator { limit })))
+            Some(Ok(Box::new(MaximumF64Validator { limit, schema_path })))
         }
     } else {
-        Some(Err(CompilationError::SchemaError))
+        Some(Err(CompilationError::invalid_value(schema_path, schema)))
     }
 }
 
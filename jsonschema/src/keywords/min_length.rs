@@ -2,21 +2,23 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
 
 pub(crate) struct MinLengthValidator {
     limit: u64,
+    schema_path: JSONPointer,
 }
 
 impl MinLengthValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, schema_path: JSONPointer) -> CompilationResult {
         if let Some(limit) = schema.as_u64() {
-            Ok(Box::new(MinLengthValidator { limit }))
+            Ok(Box::new(MinLengthValidator { limit, schema_path }))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(schema_path, schema))
         }
     }
 }
@@ -31,10 +33,20 @@ impl Validate for MinLengthValidator {
         true
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
             if (item.chars().count() as u64) < self.limit {
-                return error(ValidationError::min_length(instance, self.limit));
+                return error(ValidationError::min_length(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    self.limit,
+                ));
             }
         }
         no_error()
@@ -51,7 +63,8 @@ impl ToString for MinLengthValidator {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(MinLengthValidator::compile(schema))
+    let schema_path = context.as_pointer_with("minLength");
+    Some(MinLengthValidator::compile(schema, schema_path))
 }
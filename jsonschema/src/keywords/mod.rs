@@ -1,11 +1,13 @@
 pub(crate) mod additional_items;
 pub(crate) mod additional_properties;
 pub(crate) mod all_of;
+pub(crate) mod annotation;
 pub(crate) mod any_of;
 pub(crate) mod boolean;
 pub(crate) mod const_;
 pub(crate) mod contains;
 pub(crate) mod content;
+pub(crate) mod custom;
 pub(crate) mod dependencies;
 pub(crate) mod enum_;
 pub(crate) mod exclusive_maximum;
@@ -28,11 +30,13 @@ pub(crate) mod not;
 pub(crate) mod one_of;
 pub(crate) mod pattern;
 pub(crate) mod pattern_properties;
+pub(crate) mod prefix_items;
 pub(crate) mod properties;
 pub(crate) mod property_names;
 pub(crate) mod ref_;
 pub(crate) mod required;
 pub(crate) mod type_;
+pub(crate) mod unevaluated;
 pub(crate) mod unique_items;
 use crate::{error, validator::Validate};
 
@@ -81,7 +85,7 @@ fn format_key_value_validators(validators: &[(String, Validators)]) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::compilation::JSONSchema;
+    use crate::{compilation::JSONSchema, schemas::Draft};
     use serde_json::{json, Value};
     use test_case::test_case;
 
@@ -279,4 +283,295 @@ mod tests {
         let compiled = JSONSchema::compile(schema).unwrap();
         assert!(compiled.is_valid(instance))
     }
+
+    #[test_case(&json!({"type": "string"}), &json!(1), "")]
+    #[test_case(&json!({"properties": {"foo": {"type": "string"}}}), &json!({"foo": 1}), "/foo")]
+    #[test_case(&json!({"properties": {"a/b": {"properties": {"~c": {"type": "string"}}}}}), &json!({"a/b": {"~c": 1}}), "/a~1b/~0c")]
+    #[test_case(&json!({"items": {"type": "string"}}), &json!(["a", 1]), "/1")]
+    #[test_case(&json!({"items": [{}, {"type": "string"}]}), &json!([1, 2]), "/1")]
+    #[test_case(&json!({"items": [{}], "additionalItems": {"type": "string"}}), &json!([1, 2]), "/1")]
+    #[test_case(&json!({"additionalProperties": {"type": "string"}}), &json!({"foo": 1}), "/foo")]
+    #[test_case(&json!({"additionalProperties": false}), &json!({"foo": 1}), "/foo")]
+    #[test_case(&json!({"additionalProperties": false, "properties": {"foo": {}}}), &json!({"foo": 1, "bar": 2}), "/bar")]
+    #[test_case(&json!({"additionalProperties": false, "patternProperties": {"^f": {"type": "string"}}}), &json!({"foo": 1}), "/foo")]
+    #[test_case(&json!({"patternProperties": {"^f": {"type": "string"}}}), &json!({"foo": 1}), "/foo")]
+    #[test_case(&json!({"dependencies": {"bar": {"properties": {"foo": {"type": "string"}}}}}), &json!({"foo": 1, "bar": 2}), "/foo")]
+    #[test_case(&json!({"contains": {"type": "string"}}), &json!([1]), "")]
+    #[test_case(&json!({"properties": {"foo": {"contains": {"type": "string"}}}}), &json!({"foo": [1]}), "/foo")]
+    #[test_case(&json!({"properties": {"foo": {"$ref": "#/definitions/bar"}}, "definitions": {"bar": {"items": {"type": "string"}}}}), &json!({"foo": ["a", 1]}), "/foo/1")]
+    fn instance_path(schema: &Value, instance: &Value, expected: &str) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let errors: Vec<_> = compiled
+            .validate(instance)
+            .expect_err(&format!(
+                "Validation error is expected. Schema=`{:?}` Instance=`{:?}`",
+                schema, instance
+            ))
+            .collect();
+        assert_eq!(errors[0].instance_path.to_string(), expected);
+    }
+
+    #[test_case(&json!({"type": "string"}), &json!(1), "/type")]
+    #[test_case(&json!({"properties": {"foo": {"type": "string"}}}), &json!({"foo": 1}), "/properties/foo/type")]
+    #[test_case(&json!({"properties": {"a/b": {"maxLength": 1}}}), &json!({"a/b": "ab"}), "/properties/a~1b/maxLength")]
+    #[test_case(&json!({"items": {"type": "string"}}), &json!(["a", 1]), "/items/type")]
+    #[test_case(&json!({"items": [{}, {"type": "string"}]}), &json!([1, 2]), "/items/1/type")]
+    #[test_case(&json!({"items": [{}], "additionalItems": {"type": "string"}}), &json!([1, 2]), "/additionalItems/type")]
+    #[test_case(&json!({"items": [{}], "additionalItems": false}), &json!([1, 2]), "/additionalItems")]
+    #[test_case(&json!({"additionalProperties": false}), &json!({"foo": 1}), "/additionalProperties")]
+    #[test_case(&json!({"additionalProperties": false, "properties": {"foo": {}}}), &json!({"foo": 1, "bar": 2}), "/additionalProperties")]
+    #[test_case(&json!({"additionalProperties": {"type": "string"}, "properties": {"foo": {}}}), &json!({"bar": 2}), "/additionalProperties/type")]
+    #[test_case(&json!({"additionalProperties": false, "patternProperties": {"^f": {"type": "string"}}}), &json!({"foo": 1}), "/patternProperties/^f/type")]
+    #[test_case(&json!({"patternProperties": {"^f": {"type": "string"}}}), &json!({"foo": 1}), "/patternProperties/^f/type")]
+    #[test_case(&json!({"dependencies": {"bar": ["foo"]}}), &json!({"bar": 2}), "/dependencies/bar")]
+    #[test_case(&json!({"dependencies": {"bar": {"required": ["foo"]}}}), &json!({"bar": 2}), "/dependencies/bar/required")]
+    #[test_case(&json!({"allOf": [{}, {"type": "string"}]}), &json!(1), "/allOf/1/type")]
+    #[test_case(&json!({"anyOf": [{"type": "string"}]}), &json!(1), "/anyOf")]
+    #[test_case(&json!({"oneOf": [{"type": "string"}]}), &json!(1), "/oneOf")]
+    #[test_case(&json!({"not": {"type": "string"}}), &json!("a"), "/not")]
+    #[test_case(&json!({"if": {"type": "string"}, "then": {"maxLength": 1}}), &json!("ab"), "/then/maxLength")]
+    #[test_case(&json!({"if": {"type": "string"}, "else": {"minimum": 1}}), &json!(0), "/else/minimum")]
+    #[test_case(&json!({"propertyNames": {"maxLength": 1}}), &json!({"ab": 1}), "/propertyNames/maxLength")]
+    #[test_case(&json!({"propertyNames": false}), &json!({"ab": 1}), "/propertyNames")]
+    #[test_case(&json!({"contains": {"type": "string"}}), &json!([1]), "/contains")]
+    #[test_case(&json!({"properties": {"foo": false}}), &json!({"foo": 1}), "/properties/foo")]
+    #[test_case(&json!({"properties": {"foo": {"$ref": "#/definitions/bar"}}, "definitions": {"bar": {"items": {"type": "string"}}}}), &json!({"foo": ["a", 1]}), "/properties/foo/$ref/items/type")]
+    #[test_case(&json!({"$ref": "#/definitions/a", "definitions": {"a": {"$ref": "#/definitions/b"}, "b": {"required": ["foo"]}}}), &json!({}), "/$ref/$ref/required")]
+    fn schema_path(schema: &Value, instance: &Value, expected: &str) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let errors: Vec<_> = compiled
+            .validate(instance)
+            .expect_err(&format!(
+                "Validation error is expected. Schema=`{:?}` Instance=`{:?}`",
+                schema, instance
+            ))
+            .collect();
+        assert_eq!(errors[0].schema_path.to_string(), expected);
+    }
+
+    #[test_case(&json!({"properties": {"foo": {"type": "string"}}}), &json!({"foo": 1}), "json-schema:///#/properties/foo/type")]
+    #[test_case(&json!({"$id": "http://example.com/root.json", "maxLength": 1}), &json!("ab"), "http://example.com/root.json#/maxLength")]
+    #[test_case(&json!({"properties": {"foo": {"$ref": "#/definitions/bar"}}, "definitions": {"bar": {"items": {"type": "string"}}}}), &json!({"foo": ["a", 1]}), "json-schema:///#/definitions/bar/items/type")]
+    #[test_case(&json!({"$ref": "#/definitions/a", "definitions": {"a": {"$ref": "#/definitions/b"}, "b": {"required": ["foo"]}}}), &json!({}), "json-schema:///#/definitions/b/required")]
+    #[test_case(&json!({"$ref": "#foo", "definitions": {"a": {"$id": "#foo", "minimum": 1}}}), &json!(0), "json-schema:///#/definitions/a/minimum")]
+    #[test_case(&json!({"$id": "http://example.com/root.json", "items": {"$ref": "item.json"}, "definitions": {"a": {"$id": "item.json", "type": "integer"}}}), &json!(["a"]), "http://example.com/item.json#/type")]
+    fn absolute_keyword_location(schema: &Value, instance: &Value, expected: &str) {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let errors: Vec<_> = compiled
+            .validate(instance)
+            .expect_err(&format!(
+                "Validation error is expected. Schema=`{:?}` Instance=`{:?}`",
+                schema, instance
+            ))
+            .collect();
+        assert_eq!(
+            errors[0]
+                .absolute_keyword_location
+                .as_ref()
+                .map(|location| location.as_str()),
+            Some(expected)
+        );
+    }
+
+    #[test_case(&json!({"contains": {"type": "string"}, "minContains": 2}), &json!(["a", "b", 1]), true)]
+    #[test_case(&json!({"contains": {"type": "string"}, "minContains": 2}), &json!(["a", 1]), false)]
+    #[test_case(&json!({"contains": {"type": "string"}, "minContains": 0}), &json!([1]), true)]
+    #[test_case(&json!({"contains": {"type": "string"}, "maxContains": 1}), &json!(["a", 1]), true)]
+    #[test_case(&json!({"contains": {"type": "string"}, "maxContains": 1}), &json!(["a", "b"]), false)]
+    #[test_case(&json!({"contains": {"type": "string"}, "maxContains": 1}), &json!([1]), false)]
+    #[test_case(&json!({"dependentRequired": {"foo": ["bar"]}}), &json!({"foo": 1, "bar": 2}), true)]
+    #[test_case(&json!({"dependentRequired": {"foo": ["bar"]}}), &json!({"foo": 1}), false)]
+    #[test_case(&json!({"dependentSchemas": {"foo": {"required": ["bar"]}}}), &json!({"foo": 1}), false)]
+    #[test_case(&json!({"dependencies": {"foo": ["bar"]}}), &json!({"foo": 1}), true)]
+    #[test_case(&json!({"$ref": "#/$defs/a", "maximum": 5, "$defs": {"a": {"minimum": 2}}}), &json!(6), false)]
+    #[test_case(&json!({"$ref": "#foo", "$defs": {"a": {"$anchor": "foo", "minimum": 2}}}), &json!(1), false)]
+    #[test_case(&json!({"contentMediaType": "application/json", "contentSchema": {"required": ["foo"]}}), &json!("{\"foo\": 1}"), true)]
+    #[test_case(&json!({"contentMediaType": "application/json", "contentSchema": {"required": ["foo"]}}), &json!("{}"), false)]
+    #[test_case(&json!({"contentMediaType": "application/json", "contentEncoding": "base64", "contentSchema": {"required": ["foo"]}}), &json!("e30="), false)]
+    #[test_case(&json!({"contentSchema": {"required": ["foo"]}}), &json!("{}"), true)]
+    fn draft201909(schema: &Value, instance: &Value, expected: bool) {
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft201909)
+            .compile(schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    // The extended tree refers to itself via `$recursiveRef`, hence the `children` items are
+    // validated against the extended schema instead of the base one
+    #[test_case(&json!({"children": [{"name": "a"}]}), true)]
+    #[test_case(&json!({"children": [{"name": 1}]}), false)]
+    #[test_case(&json!({"children": [{"children": [{"name": 1}]}]}), false)]
+    fn recursive_ref(instance: &Value, expected: bool) {
+        let schema = json!({
+            "$id": "http://example.com/extended.json",
+            "$recursiveAnchor": true,
+            "$ref": "tree.json",
+            "properties": {"name": {"type": "string"}}
+        });
+        let tree = json!({
+            "$id": "http://example.com/tree.json",
+            "$recursiveAnchor": true,
+            "properties": {
+                "children": {"items": {"$recursiveRef": "#"}}
+            }
+        });
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft201909)
+            .with_document("http://example.com/tree.json".to_string(), tree)
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test_case(&json!({"contains": {"type": "string"}, "minContains": 2}), &json!(["a", 1]), "/minContains", "[\"a\",1] contains less than 2 matching items")]
+    #[test_case(&json!({"contains": {"type": "string"}, "maxContains": 1}), &json!(["a", "b"]), "/maxContains", "[\"a\",\"b\"] contains more than 1 matching item")]
+    #[test_case(&json!({"contentMediaType": "application/json", "contentSchema": {"properties": {"foo": {"type": "string"}}}}), &json!("{\"foo\": 1}"), "/contentSchema/properties/foo/type", "'1' is not of type 'string'")]
+    fn draft201909_errors(schema: &Value, instance: &Value, schema_path: &str, message: &str) {
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft201909)
+            .compile(schema)
+            .unwrap();
+        let errors: Vec<_> = compiled
+            .validate(instance)
+            .expect_err("Validation error is expected")
+            .collect();
+        assert_eq!(errors[0].schema_path.to_string(), schema_path);
+        assert_eq!(errors[0].to_string(), message);
+    }
+
+    #[test_case(&json!({"prefixItems": [{"type": "integer"}, {"type": "string"}]}), &json!([1, "a", null]), true)]
+    #[test_case(&json!({"prefixItems": [{"type": "integer"}, {"type": "string"}]}), &json!([1, 2]), false)]
+    #[test_case(&json!({"prefixItems": [{"type": "integer"}], "items": {"type": "string"}}), &json!([1, "a", "b"]), true)]
+    #[test_case(&json!({"prefixItems": [{"type": "integer"}], "items": {"type": "string"}}), &json!([1, "a", 2]), false)]
+    #[test_case(&json!({"prefixItems": [{"type": "integer"}], "items": false}), &json!([1]), true)]
+    #[test_case(&json!({"prefixItems": [{"type": "integer"}], "items": false}), &json!([1, 2]), false)]
+    #[test_case(&json!({"items": {"type": "string"}}), &json!(["a", 1]), false)]
+    #[test_case(&json!({"items": false}), &json!([]), true)]
+    #[test_case(&json!({"items": {"type": "string"}, "additionalItems": false}), &json!(["a", "b"]), true)]
+    #[test_case(&json!({"$ref": "#foo", "$defs": {"a": {"$dynamicAnchor": "foo", "minimum": 2}}}), &json!(1), false)]
+    #[test_case(&json!({"$dynamicRef": "#/$defs/a", "$defs": {"a": {"minimum": 2}}}), &json!(1), false)]
+    #[test_case(&json!({"dependentRequired": {"foo": ["bar"]}}), &json!({"foo": 1}), false)]
+    #[test_case(&json!({"contains": {"type": "string"}, "maxContains": 1}), &json!(["a", "b"]), false)]
+    fn draft202012(schema: &Value, instance: &Value, expected: bool) {
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft202012)
+            .compile(schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn draft202012_items_array() {
+        let schema = json!({"items": [{"type": "string"}]});
+        let error = JSONSchema::options()
+            .with_draft(Draft::Draft202012)
+            .compile(&schema)
+            .expect_err("Array form of `items` is invalid");
+        assert_eq!(error.schema_path.to_string(), "/items");
+    }
+
+    // The strict tree is the outermost schema resource with the `node` dynamic anchor, hence
+    // the `children` items are validated against it instead of the base tree
+    #[test_case(&json!({"children": [{"name": "a"}]}), true)]
+    #[test_case(&json!({"children": [{"name": 1}]}), false)]
+    #[test_case(&json!({"children": [{"children": [{"name": 1}]}]}), false)]
+    fn dynamic_ref(instance: &Value, expected: bool) {
+        let schema = json!({
+            "$id": "http://example.com/strict-tree.json",
+            "$dynamicAnchor": "node",
+            "$ref": "tree.json",
+            "properties": {"name": {"type": "string"}}
+        });
+        let tree = json!({
+            "$id": "http://example.com/tree.json",
+            "$dynamicAnchor": "node",
+            "properties": {
+                "children": {"items": {"$dynamicRef": "#node"}}
+            }
+        });
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft202012)
+            .with_document("http://example.com/tree.json".to_string(), tree)
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    // Without a matching dynamic anchor in the initially resolved schema `$dynamicRef` behaves
+    // like `$ref`
+    #[test]
+    fn dynamic_ref_without_anchor() {
+        let schema = json!({
+            "$dynamicAnchor": "node",
+            "type": "object",
+            "properties": {"child": {"$dynamicRef": "#/$defs/node"}},
+            "$defs": {"node": {"$anchor": "node", "type": "string"}}
+        });
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft202012)
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!({"child": "a"})));
+        assert!(!compiled.is_valid(&json!({"child": {}})));
+    }
+
+    #[test_case(&json!({"allOf": [{"properties": {"foo": {}}}], "unevaluatedProperties": false}), &json!({"foo": 1}), true)]
+    #[test_case(&json!({"allOf": [{"properties": {"foo": {}}}], "unevaluatedProperties": false}), &json!({"foo": 1, "bar": 2}), false)]
+    #[test_case(&json!({"$ref": "#/$defs/a", "unevaluatedProperties": false, "$defs": {"a": {"properties": {"foo": {}}}}}), &json!({"foo": 1}), true)]
+    #[test_case(&json!({"$ref": "#/$defs/a", "unevaluatedProperties": false, "$defs": {"a": {"properties": {"foo": {}}}}}), &json!({"bar": 1}), false)]
+    #[test_case(&json!({"anyOf": [{"properties": {"foo": {"type": "string"}}}, {"properties": {"bar": {}}}], "unevaluatedProperties": false}), &json!({"foo": 1, "bar": 2}), false; "failed anyOf branches are not evaluated")]
+    #[test_case(&json!({"anyOf": [{"properties": {"foo": {"type": "string"}}}, {"properties": {"bar": {}}}], "unevaluatedProperties": false}), &json!({"foo": "a", "bar": 2}), true)]
+    #[test_case(&json!({"oneOf": [{"properties": {"foo": {"const": 1}}, "required": ["foo"]}, {"properties": {"bar": {}}, "required": ["bar"]}], "unevaluatedProperties": false}), &json!({"foo": 1}), true)]
+    #[test_case(&json!({"if": {"properties": {"foo": {"const": 1}}}, "then": {"properties": {"bar": {}}}, "else": {"properties": {"baz": {}}}, "unevaluatedProperties": false}), &json!({"foo": 1, "bar": 2}), true)]
+    #[test_case(&json!({"if": {"properties": {"foo": {"const": 1}}}, "then": {"properties": {"bar": {}}}, "else": {"properties": {"baz": {}}}, "unevaluatedProperties": false}), &json!({"foo": 2, "baz": 2}), false; "failed if is not evaluated")]
+    #[test_case(&json!({"if": {"properties": {"foo": {"const": 1}}}, "then": {"properties": {"bar": {}}}, "else": {"properties": {"baz": {}}}, "unevaluatedProperties": false}), &json!({"foo": 1, "baz": 2}), false)]
+    #[test_case(&json!({"patternProperties": {"^f": {}}, "unevaluatedProperties": false}), &json!({"foo": 1, "bar": 2}), false)]
+    #[test_case(&json!({"additionalProperties": true, "unevaluatedProperties": false}), &json!({"foo": 1}), true)]
+    #[test_case(&json!({"dependentSchemas": {"foo": {"properties": {"bar": {}}}}, "properties": {"foo": {}}, "unevaluatedProperties": false}), &json!({"foo": 1, "bar": 2}), true)]
+    #[test_case(&json!({"allOf": [{"unevaluatedProperties": true}], "unevaluatedProperties": false}), &json!({"foo": 1}), true)]
+    #[test_case(&json!({"unevaluatedProperties": {"type": "string"}, "properties": {"foo": {}}}), &json!({"foo": 1, "bar": "a"}), true)]
+    #[test_case(&json!({"unevaluatedProperties": {"type": "string"}, "properties": {"foo": {}}}), &json!({"foo": 1, "bar": 2}), false)]
+    #[test_case(&json!({"prefixItems": [{}], "unevaluatedItems": false}), &json!([1]), true)]
+    #[test_case(&json!({"prefixItems": [{}], "unevaluatedItems": false}), &json!([1, 2]), false)]
+    #[test_case(&json!({"allOf": [{"prefixItems": [{}]}, {"items": true}], "unevaluatedItems": false}), &json!([1, 2]), true)]
+    #[test_case(&json!({"contains": {"type": "string"}, "unevaluatedItems": false}), &json!(["a", "b"]), true)]
+    #[test_case(&json!({"contains": {"type": "string"}, "unevaluatedItems": false}), &json!(["a", 1]), false)]
+    #[test_case(&json!({"unevaluatedItems": {"type": "string"}}), &json!(["a", 1]), false)]
+    fn unevaluated(schema: &Value, instance: &Value, expected: bool) {
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft202012)
+            .compile(schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test_case(&json!({"properties": {"foo": {}}, "unevaluatedProperties": false}), &json!({"foo": 1, "bar": 2, "baz": 3}), "", "/unevaluatedProperties", "Unevaluated properties are not allowed ('bar', 'baz' were unexpected)")]
+    #[test_case(&json!({"unevaluatedProperties": {"type": "string"}}), &json!({"foo": 1}), "/foo", "/unevaluatedProperties/type", "'1' is not of type 'string'")]
+    #[test_case(&json!({"prefixItems": [{}], "unevaluatedItems": false}), &json!([1, 2]), "", "/unevaluatedItems", "Unevaluated items are not allowed (2 was unexpected)")]
+    fn unevaluated_errors(
+        schema: &Value,
+        instance: &Value,
+        instance_path: &str,
+        schema_path: &str,
+        message: &str,
+    ) {
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft202012)
+            .compile(schema)
+            .unwrap();
+        let errors: Vec<_> = compiled
+            .validate(instance)
+            .expect_err("Validation error is expected")
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path.to_string(), instance_path);
+        assert_eq!(errors[0].schema_path.to_string(), schema_path);
+        assert_eq!(errors[0].to_string(), message);
+    }
 }
@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -9,12 +10,16 @@ use std::f64::EPSILON;
 
 pub(crate) struct MultipleOfFloatValidator {
     multiple_of: f64,
+    schema_path: JSONPointer,
 }
 
 impl MultipleOfFloatValidator {
     #[inline]
-    pub(crate) fn compile(multiple_of: f64) -> CompilationResult {
-        Ok(Box::new(MultipleOfFloatValidator { multiple_of }))
+    pub(crate) fn compile(multiple_of: f64, schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(MultipleOfFloatValidator {
+            multiple_of,
+            schema_path,
+        }))
     }
 }
 
@@ -30,12 +35,22 @@ impl Validate for MultipleOfFloatValidator {
         true
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Number(item) = instance {
             let item = item.as_f64().expect("Always valid");
             let remainder = (item / self.multiple_of) % 1.;
             if !(remainder < EPSILON && remainder < (1. - EPSILON)) {
-                return error(ValidationError::multiple_of(instance, self.multiple_of));
+                return error(ValidationError::multiple_of(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    self.multiple_of,
+                ));
             }
         }
         no_error()
@@ -50,12 +65,16 @@ impl ToString for MultipleOfFloatValidator {
 
 pub(crate) struct MultipleOfIntegerValidator {
     multiple_of: f64,
+    schema_path: JSONPointer,
 }
 
 impl MultipleOfIntegerValidator {
     #[inline]
-    pub(crate) fn compile(multiple_of: f64) -> CompilationResult {
-        Ok(Box::new(MultipleOfIntegerValidator { multiple_of }))
+    pub(crate) fn compile(multiple_of: f64, schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(MultipleOfIntegerValidator {
+            multiple_of,
+            schema_path,
+        }))
     }
 }
 
@@ -76,7 +95,12 @@ impl Validate for MultipleOfIntegerValidator {
         true
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Number(item) = instance {
             let item = item.as_f64().expect("Always valid");
             let is_multiple = if item.fract() == 0. {
@@ -86,7 +110,12 @@ impl Validate for MultipleOfIntegerValidator {
                 remainder < EPSILON && remainder < (1. - EPSILON)
             };
             if !is_multiple {
-                return error(ValidationError::multiple_of(instance, self.multiple_of));
+                return error(ValidationError::multiple_of(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    self.multiple_of,
+                ));
             }
         }
         no_error()
@@ -102,16 +131,23 @@ impl ToString for MultipleOfIntegerValidator {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
     if let Value::Number(multiple_of) = schema {
+        let schema_path = context.as_pointer_with("multipleOf");
         let multiple_of = multiple_of.as_f64().expect("Always valid");
         if multiple_of.fract() == 0. {
-            Some(MultipleOfIntegerValidator::compile(multiple_of))
+            Some(MultipleOfIntegerValidator::compile(
+                multiple_of,
+                schema_path,
+            ))
         } else {
-            Some(MultipleOfFloatValidator::compile(multiple_of))
+            Some(MultipleOfFloatValidator::compile(multiple_of, schema_path))
         }
     } else {
-        Some(Err(CompilationError::SchemaError))
+        Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("multipleOf"),
+            schema,
+        )))
     }
 }
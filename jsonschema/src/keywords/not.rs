@@ -2,6 +2,7 @@ use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::{format_validators, CompilationResult, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -10,14 +11,17 @@ pub(crate) struct NotValidator {
     // needed only for error representation
     original: Value,
     validators: Validators,
+    schema_path: JSONPointer,
 }
 
 impl NotValidator {
     #[inline]
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
+        let keyword_context = context.with_path("not");
         Ok(Box::new(NotValidator {
             original: schema.clone(),
-            validators: compile_validators(schema, context)?,
+            validators: compile_validators(schema, &keyword_context)?,
+            schema_path: keyword_context.into_pointer(),
         }))
     }
 }
@@ -30,11 +34,21 @@ impl Validate for NotValidator {
             .all(|validator| validator.is_valid(schema, instance))
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::not(instance, self.original.clone()))
+            error(ValidationError::not(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                self.original.clone(),
+            ))
         }
     }
 }
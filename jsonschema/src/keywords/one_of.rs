@@ -2,25 +2,34 @@ use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::{format_vec_of_validators, CompilationResult, Validators},
-    validator::Validate,
+    paths::{InstancePath, JSONPointer},
+    validator::{annotate_branches, evaluate_subschema, Annotations, Evaluated, Validate},
 };
 use serde_json::{Map, Value};
 
 pub(crate) struct OneOfValidator {
     schemas: Vec<Validators>,
+    schema_path: JSONPointer,
 }
 
 impl OneOfValidator {
     #[inline]
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
+            let keyword_context = context.with_path("oneOf");
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                schemas.push(compile_validators(item, context)?)
+            for (idx, item) in items.iter().enumerate() {
+                schemas.push(compile_validators(item, &keyword_context.with_path(idx))?)
             }
-            Ok(Box::new(OneOfValidator { schemas }))
+            Ok(Box::new(OneOfValidator {
+                schemas,
+                schema_path: keyword_context.into_pointer(),
+            }))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(
+                context.as_pointer_with("oneOf"),
+                schema,
+            ))
         }
     }
 
@@ -64,17 +73,76 @@ impl Validate for OneOfValidator {
             false
         }
     }
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         let first_valid_idx = self.get_first_valid(schema, instance);
         if let Some(idx) = first_valid_idx {
             if self.are_others_valid(schema, instance, idx) {
-                return error(ValidationError::one_of_multiple_valid(instance));
+                let matched = self
+                    .schemas
+                    .iter()
+                    .enumerate()
+                    .skip(idx)
+                    .filter(|(_, validators)| {
+                        validators
+                            .iter()
+                            .all(|validator| validator.is_valid(schema, instance))
+                    })
+                    .map(|(idx, _)| idx)
+                    .collect();
+                return error(ValidationError::one_of_multiple_valid(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    matched,
+                ));
             }
             no_error()
         } else {
-            error(ValidationError::one_of_not_valid(instance))
+            let context = self
+                .schemas
+                .iter()
+                .map(|validators| {
+                    validators
+                        .iter()
+                        .flat_map(|validator| validator.validate(schema, instance, instance_path))
+                        .map(ValidationError::into_owned)
+                        .collect()
+                })
+                .collect();
+            error(ValidationError::one_of_not_valid(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                context,
+            ))
+        }
+    }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        for validators in &self.schemas {
+            evaluate_subschema(validators, schema, instance, evaluated);
         }
     }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotate_branches(&self.schemas, schema, instance, instance_path, annotations) == 1
+    }
 }
 
 impl ToString for OneOfValidator {
@@ -91,3 +159,102 @@ pub(crate) fn compile(
 ) -> Option<CompilationResult> {
     Some(OneOfValidator::compile(schema, context))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ValidationErrorKind, JSONSchema};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn error_kind(schema: &Value, instance: &Value) -> ValidationErrorKind {
+        let compiled = JSONSchema::compile(schema).unwrap();
+        let mut errors: Vec<_> = compiled.validate(instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        errors.pop().unwrap().kind
+    }
+
+    #[test]
+    fn branch_errors() {
+        let schema = json!({"oneOf": [{"type": "string"}, {"minimum": 6}, {"maximum": 3}]});
+        let kind = error_kind(&schema, &json!(5));
+        if let ValidationErrorKind::OneOfNotValid { context } = kind {
+            let paths: Vec<Vec<_>> = context
+                .iter()
+                .map(|errors| {
+                    errors
+                        .iter()
+                        .map(|error| error.schema_path.to_string())
+                        .collect()
+                })
+                .collect();
+            assert_eq!(
+                paths,
+                vec![
+                    vec!["/oneOf/0/type"],
+                    vec!["/oneOf/1/minimum"],
+                    vec!["/oneOf/2/maximum"]
+                ]
+            );
+        } else {
+            panic!("Unexpected error kind: {:?}", kind)
+        }
+    }
+
+    #[test_case(&json!(3), &[1, 2])]
+    #[test_case(&json!(1), &[0, 2])]
+    #[test_case(&json!(2), &[0, 1, 2])]
+    fn matched(instance: &Value, expected: &[usize]) {
+        let schema = json!({"oneOf": [{"maximum": 2}, {"minimum": 2}, {"type": "integer"}]});
+        let kind = error_kind(&schema, instance);
+        if let ValidationErrorKind::OneOfMultipleValid { matched } = kind {
+            assert_eq!(matched, expected);
+        } else {
+            panic!("Unexpected error kind: {:?}", kind)
+        }
+    }
+
+    #[test_case(
+        &json!({"properties": {"foo": {"oneOf": [{"type": "string"}, {"minimum": 2}]}}}),
+        &json!({"foo": 1}),
+        &[
+            ("/properties/foo/oneOf/0/type", "json-schema:///#/properties/foo/oneOf/0/type"),
+            ("/properties/foo/oneOf/1/minimum", "json-schema:///#/properties/foo/oneOf/1/minimum"),
+        ];
+        "properties"
+    )]
+    #[test_case(
+        &json!({"$ref": "#/definitions/a", "definitions": {"a": {"oneOf": [{"type": "string"}, {"$ref": "#/definitions/b"}]}, "b": {"minimum": 2}}}),
+        &json!(1),
+        &[
+            ("/$ref/oneOf/0/type", "json-schema:///#/definitions/a/oneOf/0/type"),
+            ("/$ref/oneOf/1/$ref/minimum", "json-schema:///#/definitions/b/minimum"),
+        ];
+        "reference"
+    )]
+    fn branch_error_locations(schema: &Value, instance: &Value, expected: &[(&str, &str)]) {
+        let kind = error_kind(schema, instance);
+        if let ValidationErrorKind::OneOfNotValid { context } = kind {
+            let locations: Vec<_> = context
+                .iter()
+                .flatten()
+                .map(|error| {
+                    (
+                        error.schema_path.to_string(),
+                        error
+                            .absolute_keyword_location
+                            .as_ref()
+                            .map(ToString::to_string)
+                            .unwrap_or_default(),
+                    )
+                })
+                .collect();
+            let expected: Vec<_> = expected
+                .iter()
+                .map(|(path, location)| (path.to_string(), location.to_string()))
+                .collect();
+            assert_eq!(locations, expected);
+        } else {
+            panic!("Unexpected error kind: {:?}", kind)
+        }
+    }
+}
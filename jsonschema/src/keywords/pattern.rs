@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use regex::{Captures, Regex};
@@ -16,29 +17,46 @@ lazy_static::lazy_static! {
 pub(crate) struct PatternValidator {
     original: String,
     pattern: Regex,
+    schema_path: JSONPointer,
 }
 
 impl PatternValidator {
     #[inline]
-    pub(crate) fn compile(pattern: &Value) -> CompilationResult {
+    pub(crate) fn compile(pattern: &Value, schema_path: JSONPointer) -> CompilationResult {
         match pattern {
             Value::String(item) => {
-                let pattern = convert_regex(item)?;
+                let pattern = match convert_regex(item) {
+                    Ok(pattern) => pattern,
+                    Err(error) => {
+                        return Err(CompilationError::invalid_regex(schema_path, pattern, error))
+                    }
+                };
                 Ok(Box::new(PatternValidator {
                     original: item.clone(),
                     pattern,
+                    schema_path,
                 }))
             }
-            _ => Err(CompilationError::SchemaError),
+            _ => Err(CompilationError::invalid_value(schema_path, pattern)),
         }
     }
 }
 
 impl Validate for PatternValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
             if !self.pattern.is_match(item) {
-                return error(ValidationError::pattern(instance, self.original.clone()));
+                return error(ValidationError::pattern(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    self.original.clone(),
+                ));
             }
         }
         no_error()
@@ -121,9 +139,10 @@ fn replace_control_group(captures: &Captures) -> String {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(PatternValidator::compile(schema))
+    let schema_path = context.as_pointer_with("pattern");
+    Some(PatternValidator::compile(schema, schema_path))
 }
 
 #[cfg(test)]
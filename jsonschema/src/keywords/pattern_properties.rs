@@ -2,7 +2,8 @@ use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator},
     keywords::{format_validators, CompilationResult, Validators},
-    validator::Validate,
+    paths::InstancePath,
+    validator::{annotate_subschema, Annotations, Evaluated, Validate},
 };
 use regex::Regex;
 use serde_json::{Map, Value};
@@ -15,16 +16,28 @@ impl PatternPropertiesValidator {
     #[inline]
     pub(crate) fn compile(properties: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Object(map) = properties {
+            let keyword_context = context.with_path("patternProperties");
             let mut patterns = Vec::with_capacity(map.len());
             for (pattern, subschema) in map {
+                let pattern_context = keyword_context.with_path(pattern.as_str());
                 patterns.push((
-                    Regex::new(pattern)?,
-                    compile_validators(subschema, context)?,
+                    Regex::new(pattern).map_err(|error| {
+                        CompilationError::invalid_regex(
+                            keyword_context.as_pointer_with(pattern.as_str()),
+                            &Value::String(pattern.clone()),
+                            error,
+                        )
+                        .with_keyword("patternProperties")
+                    })?,
+                    compile_validators(subschema, &pattern_context)?,
                 ));
             }
             Ok(Box::new(PatternPropertiesValidator { patterns }))
         } else {
-            Err(CompilationError::SchemaError)
+            Err(CompilationError::invalid_value(
+                context.as_pointer_with("patternProperties"),
+                properties,
+            ))
         }
     }
 }
@@ -46,7 +59,12 @@ impl Validate for PatternPropertiesValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             let errors: Vec<_> = self
                 .patterns
@@ -54,10 +72,11 @@ impl Validate for PatternPropertiesValidator {
                 .flat_map(move |(re, validators)| {
                     item.iter()
                         .filter(move |(key, _)| re.is_match(key))
-                        .flat_map(move |(_key, value)| {
-                            validators
-                                .iter()
-                                .flat_map(move |validator| validator.validate(schema, value))
+                        .flat_map(move |(key, value)| {
+                            let instance_path = instance_path.push(key.clone());
+                            validators.iter().flat_map(move |validator| {
+                                validator.validate(schema, value, &instance_path)
+                            })
                         })
                 })
                 .collect();
@@ -66,6 +85,43 @@ impl Validate for PatternPropertiesValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        if let Value::Object(item) = instance {
+            for key in item.keys() {
+                if self.patterns.iter().any(|(re, _)| re.is_match(key)) {
+                    evaluated.properties.insert(key);
+                }
+            }
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Object(item) = instance {
+            for (property, value) in item {
+                let property_path = instance_path.push(property.clone());
+                for (re, validators) in &self.patterns {
+                    if re.is_match(property) {
+                        valid &= annotate_subschema(
+                            validators,
+                            schema,
+                            value,
+                            &property_path,
+                            annotations,
+                        );
+                    }
+                }
+            }
+        }
+        valid
+    }
 }
 
 impl ToString for PatternPropertiesValidator {
@@ -0,0 +1,111 @@
+use crate::{
+    compilation::{compile_validators, context::CompilationContext, JSONSchema},
+    error::{no_error, CompilationError, ErrorIterator},
+    keywords::{format_vec_of_validators, CompilationResult, Validators},
+    paths::InstancePath,
+    validator::{annotate_subschema, Annotations, Evaluated, Validate},
+};
+use serde_json::{Map, Value};
+
+pub(crate) struct PrefixItemsValidator {
+    items: Vec<Validators>,
+}
+impl PrefixItemsValidator {
+    #[inline]
+    pub(crate) fn compile(schemas: &[Value], context: &CompilationContext) -> CompilationResult {
+        let keyword_context = context.with_path("prefixItems");
+        let mut items = Vec::with_capacity(schemas.len());
+        for (idx, item) in schemas.iter().enumerate() {
+            let validators = compile_validators(item, &keyword_context.with_path(idx))?;
+            items.push(validators)
+        }
+        Ok(Box::new(PrefixItemsValidator { items }))
+    }
+}
+impl Validate for PrefixItemsValidator {
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Array(items) = instance {
+            items
+                .iter()
+                .zip(self.items.iter())
+                .all(move |(item, validators)| {
+                    validators
+                        .iter()
+                        .all(move |validator| validator.is_valid(schema, item))
+                })
+        } else {
+            true
+        }
+    }
+
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::Array(items) = instance {
+            let errors: Vec<_> = items
+                .iter()
+                .zip(self.items.iter())
+                .enumerate()
+                .flat_map(move |(idx, (item, validators))| {
+                    let instance_path = instance_path.push(idx);
+                    validators
+                        .iter()
+                        .flat_map(move |validator| validator.validate(schema, item, &instance_path))
+                })
+                .collect();
+            Box::new(errors.into_iter())
+        } else {
+            no_error()
+        }
+    }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.items_until(instance, self.items.len())
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Array(items) = instance {
+            for (idx, (item, validators)) in items.iter().zip(self.items.iter()).enumerate() {
+                valid &= annotate_subschema(
+                    validators,
+                    schema,
+                    item,
+                    &instance_path.push(idx),
+                    annotations,
+                );
+            }
+        }
+        valid
+    }
+}
+
+impl ToString for PrefixItemsValidator {
+    fn to_string(&self) -> String {
+        format!("prefixItems: [{}]", format_vec_of_validators(&self.items))
+    }
+}
+
+#[inline]
+pub(crate) fn compile(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::Array(items) => Some(PrefixItemsValidator::compile(items, context)),
+        _ => Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("prefixItems"),
+            schema,
+        ))),
+    }
+}
@@ -2,12 +2,14 @@ use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator},
     keywords::{format_key_value_validators, CompilationResult, Validators},
-    validator::Validate,
+    paths::InstancePath,
+    validator::{annotate_subschema, Annotations, Evaluated, Validate},
 };
 use serde_json::{Map, Value};
 
 pub(crate) struct PropertiesValidator {
     properties: Vec<(String, Validators)>,
+    defaults: Vec<(String, Value)>,
 }
 
 impl PropertiesValidator {
@@ -15,13 +17,24 @@ impl PropertiesValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         match schema {
             Value::Object(map) => {
+                let keyword_context = context.with_path("properties");
                 let mut properties = Vec::with_capacity(map.len());
                 for (key, subschema) in map {
-                    properties.push((key.clone(), compile_validators(subschema, context)?));
+                    let property_context = keyword_context.with_path(key.as_str());
+                    properties.push((
+                        key.clone(),
+                        compile_validators(subschema, &property_context)?,
+                    ));
                 }
-                Ok(Box::new(PropertiesValidator { properties }))
+                Ok(Box::new(PropertiesValidator {
+                    properties,
+                    defaults: property_defaults(map),
+                }))
             }
-            _ => Err(CompilationError::SchemaError),
+            _ => Err(CompilationError::invalid_value(
+                context.as_pointer_with("properties"),
+                schema,
+            )),
         }
     }
 }
@@ -42,7 +55,12 @@ impl Validate for PropertiesValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             let errors: Vec<_> = self
                 .properties
@@ -50,9 +68,10 @@ impl Validate for PropertiesValidator {
                 .flat_map(move |(name, validators)| {
                     let option = item.get(name);
                     option.into_iter().flat_map(move |item| {
-                        validators
-                            .iter()
-                            .flat_map(move |validator| validator.validate(schema, item))
+                        let instance_path = instance_path.push(name.clone());
+                        validators.iter().flat_map(move |validator| {
+                            validator.validate(schema, item, &instance_path)
+                        })
                     })
                 })
                 .collect();
@@ -61,6 +80,41 @@ impl Validate for PropertiesValidator {
             no_error()
         }
     }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        if let Value::Object(item) = instance {
+            for key in item.keys() {
+                if self.properties.iter().any(|(name, _)| name == key) {
+                    evaluated.properties.insert(key);
+                }
+            }
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Object(item) = instance {
+            for (name, validators) in &self.properties {
+                if let Some(value) = item.get(name) {
+                    valid &= annotate_subschema(
+                        validators,
+                        schema,
+                        value,
+                        &instance_path.push(name.clone()),
+                        annotations,
+                    );
+                }
+            }
+        }
+        annotations.missing_properties(&self.defaults, instance, instance_path);
+        valid
+    }
 }
 
 impl ToString for PropertiesValidator {
@@ -72,6 +126,17 @@ impl ToString for PropertiesValidator {
     }
 }
 
+/// `default` values of property subschemas in the `properties` keyword.
+pub(crate) fn property_defaults(map: &Map<String, Value>) -> Vec<(String, Value)> {
+    map.iter()
+        .filter_map(|(key, subschema)| {
+            subschema
+                .get("default")
+                .map(|default| (key.clone(), default.clone()))
+        })
+        .collect()
+}
+
 #[inline]
 pub(crate) fn compile(
     parent: &Map<String, Value>,
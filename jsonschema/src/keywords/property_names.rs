@@ -2,6 +2,7 @@ use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::{format_validators, CompilationResult, Validators},
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
@@ -14,8 +15,9 @@ pub(crate) struct PropertyNamesObjectValidator {
 impl PropertyNamesObjectValidator {
     #[inline]
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
+        let keyword_context = context.with_path("propertyNames");
         Ok(Box::new(PropertyNamesObjectValidator {
-            validators: compile_validators(schema, context)?,
+            validators: compile_validators(schema, &keyword_context)?,
         }))
     }
 }
@@ -34,7 +36,12 @@ impl Validate for PropertyNamesObjectValidator {
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = &instance.borrow() {
             let errors: Vec<_> = self
                 .validators
@@ -43,7 +50,7 @@ impl Validate for PropertyNamesObjectValidator {
                     item.keys().flat_map(move |key| {
                         let wrapper = Value::String(key.to_string());
                         let errors: Vec<_> = validator
-                            .validate(schema, &wrapper)
+                            .validate(schema, &wrapper, instance_path)
                             .map(ValidationError::into_owned)
                             .collect();
                         errors.into_iter()
@@ -62,12 +69,15 @@ impl ToString for PropertyNamesObjectValidator {
     }
 }
 
-pub(crate) struct PropertyNamesBooleanValidator {}
+pub(crate) struct PropertyNamesBooleanValidator {
+    schema_path: JSONPointer,
+}
 
 impl PropertyNamesBooleanValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(PropertyNamesBooleanValidator {}))
+    pub(crate) fn compile(context: &CompilationContext) -> CompilationResult {
+        let schema_path = context.as_pointer_with("propertyNames");
+        Ok(Box::new(PropertyNamesBooleanValidator { schema_path }))
     }
 }
 
@@ -81,11 +91,20 @@ impl Validate for PropertyNamesBooleanValidator {
         true
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::false_schema(instance))
+            error(ValidationError::false_schema(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+            ))
         }
     }
 }
@@ -104,7 +123,7 @@ pub(crate) fn compile(
 ) -> Option<CompilationResult> {
     match schema {
         Value::Object(_) => Some(PropertyNamesObjectValidator::compile(schema, context)),
-        Value::Bool(false) => Some(PropertyNamesBooleanValidator::compile()),
+        Value::Bool(false) => Some(PropertyNamesBooleanValidator::compile(context)),
         _ => None,
     }
 }
@@ -1,96 +1,564 @@
 use crate::{
     compilation::{compile_validators, context::CompilationContext, JSONSchema},
-    error::{error, ErrorIterator, ValidationError},
+    error::{error, CompilationError, ErrorIterator, ValidationError},
     keywords::{CompilationResult, Validators},
-    validator::Validate,
+    paths::{InstancePath, JSONPointer, PathChunk},
+    resolver::Resolved,
+    schemas::dynamic_anchor_of,
+    validator::{annotate_subschema, evaluate_subschema, Annotations, Evaluated, Validate},
 };
-use parking_lot::RwLock;
-use serde_json::Value;
-use std::borrow::Cow;
+use ahash::AHashMap;
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use serde_json::{Map, Value};
+use std::{borrow::Cow, sync::Arc};
 use url::Url;
 
-pub(crate) struct RefValidator {
+/// Keywords that refer to other schemas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ReferenceKind {
+    /// `$ref` always points to `reference`.
+    Static,
+    /// `$recursiveRef` may point to the outermost schema with `"$recursiveAnchor": true`
+    /// instead of `reference`.
+    Recursive,
+    /// `$dynamicRef` may point to the outermost schema with the same `$dynamicAnchor`
+    /// instead of `reference`.
+    Dynamic,
+}
+
+impl ReferenceKind {
+    const fn keyword(self) -> &'static str {
+        match self {
+            ReferenceKind::Static => "$ref",
+            ReferenceKind::Recursive => "$recursiveRef",
+            ReferenceKind::Dynamic => "$dynamicRef",
+        }
+    }
+}
+
+/// References found during compilation that should be resolved right after it.
+pub(crate) type ReferenceCollector = Mutex<Vec<Arc<Reference>>>;
+
+/// Compiled targets of all references in a schema.
+/// References with the same key share the same target.
+pub(crate) type RefTargets = Mutex<AHashMap<ReferenceKey, Arc<RefTarget>>>;
+
+/// References with the same URL resolve to the same schema if they have the same dynamic scope.
+/// The scope contains only resources with dynamic anchors, so it is empty unless `$dynamicRef`
+/// targets in the referenced schema depend on it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ReferenceKey {
     reference: Url,
-    /// Precomputed validators.
-    /// They are behind a RwLock as is not possible to compute them
-    /// at compile time without risking infinite loops of references
-    /// and at the same time during validation we iterate over shared
-    /// references (&self) and not owned references (&mut self).
-    validators: RwLock<Option<Validators>>,
+    kind: ReferenceKind,
+    recursive_anchor: Option<Url>,
+    dynamic_scope: Vec<Url>,
+}
+
+/// A referenced schema. It is compiled once and shared by all references that point to it.
+#[derive(Debug, Default)]
+pub(crate) struct RefTarget {
+    /// Absolute location of the referenced schema and its validators.
+    /// Compiled on first use as compiling them upfront would risk infinite loops of references.
+    compiled: OnceCell<(Url, Validators)>,
+}
+
+pub(crate) struct RefValidator {
+    reference: Arc<Reference>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Reference {
+    key: ReferenceKey,
+    /// Location of this `$ref` keyword. It is a prefix for the locations of all errors
+    /// coming from the referenced schema.
+    schema_path: JSONPointer,
+    /// The shared target. It is looked up once, further reads are lock-free.
+    target: OnceCell<Arc<RefTarget>>,
 }
 
 impl RefValidator {
     #[inline]
-    pub(crate) fn compile(reference: &str, context: &CompilationContext) -> CompilationResult {
-        let reference = context.build_url(reference)?;
-        Ok(Box::new(RefValidator {
-            reference,
-            validators: RwLock::new(None),
-        }))
+    pub(crate) fn compile(
+        reference: &str,
+        kind: ReferenceKind,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        let schema_path = context.as_pointer_with(kind.keyword());
+        let reference = context.build_url(reference).map_err(|error| {
+            CompilationError::invalid_url(
+                schema_path.clone(),
+                &Value::String(reference.to_string()),
+                error,
+            )
+        })?;
+        let reference = Arc::new(Reference {
+            key: ReferenceKey {
+                reference,
+                kind,
+                recursive_anchor: context.recursive_anchor.as_deref().cloned(),
+                dynamic_scope: context.dynamic_scope.to_vec(),
+            },
+            schema_path,
+            target: OnceCell::new(),
+        });
+        if let Some(references) = context.references {
+            references.lock().push(Arc::clone(&reference))
+        }
+        Ok(Box::new(RefValidator { reference }))
+    }
+}
+
+impl Reference {
+    /// Absolute location of the resolved schema.
+    ///
+    /// If the reference contains a JSON pointer, then it is the location itself, but plain name
+    /// fragments (e.g. `#foo`) can't be extended with keyword paths and the actual position
+    /// of the subschema in the root document is used instead.
+    fn locate(&self, reference: &Url, schema: &JSONSchema, resolved: &Value) -> Url {
+        match reference.fragment() {
+            Some(fragment) if !fragment.is_empty() && !fragment.starts_with('/') => {
+                let mut chunks = Vec::new();
+                if find_location(&schema.schema, resolved, &mut chunks) {
+                    let mut base = schema.context.scope.clone().into_owned();
+                    base.set_fragment(None);
+                    JSONPointer::from(chunks).to_url(&base)
+                } else {
+                    reference.clone()
+                }
+            }
+            _ => reference.clone(),
+        }
+    }
+
+    /// The target shared with other references with the same key.
+    #[inline]
+    fn target(&self, schema: &JSONSchema) -> &RefTarget {
+        self.target.get_or_init(|| {
+            Arc::clone(
+                schema
+                    .references
+                    .lock()
+                    .entry(self.key.clone())
+                    .or_default(),
+            )
+        })
+    }
+
+    /// Find the referenced schema.
+    /// Returns the resolved reference, the scope of the referenced schema and the schema itself.
+    fn resolve<'a>(
+        &'a self,
+        schema: &'a JSONSchema,
+    ) -> Result<(Cow<'a, Url>, Url, Resolved<'a>), ValidationError<'a>> {
+        let draft = schema.context.config.draft();
+        let key = &self.key;
+        let mut reference = Cow::Borrowed(&key.reference);
+        let (mut scope, mut resolved) = schema.resolver.resolve_fragment(draft, &reference)?;
+        if let (ReferenceKind::Recursive, Some(anchor)) = (key.kind, &key.recursive_anchor) {
+            if has_recursive_anchor(&resolved) {
+                let (anchor_scope, anchor_schema) =
+                    schema.resolver.resolve_fragment(draft, anchor)?;
+                scope = anchor_scope;
+                resolved = anchor_schema;
+                reference = Cow::Borrowed(anchor);
+            }
+        }
+        if let (ReferenceKind::Dynamic, Some(name)) = (key.kind, key.reference.fragment()) {
+            if dynamic_anchor_of(draft, &resolved) == Some(name) {
+                if let Some((anchor, anchor_scope, anchor_schema)) = schema
+                    .resolver
+                    .resolve_dynamic_anchor(draft, name, &key.dynamic_scope)
+                {
+                    scope = anchor_scope;
+                    resolved = anchor_schema;
+                    reference = Cow::Owned(anchor);
+                }
+            }
+        }
+        Ok((reference, scope, resolved))
+    }
+
+    /// Compile the referenced schema. References found in it are added to `references`.
+    fn compile_target(
+        &self,
+        schema: &JSONSchema,
+        scope: Url,
+        resolved: &Value,
+        references: Option<&ReferenceCollector>,
+    ) -> Result<Validators, CompilationError> {
+        // The referenced schema is compiled separately, therefore the dynamic scope is
+        // passed down together with other compilation details
+        let config = &schema.context.config;
+        let draft = config.draft();
+        let mut resource_url = scope.clone();
+        resource_url.set_fragment(None);
+        let resource = schema.resolver.resolve_fragment(draft, &resource_url).ok();
+        let resource = resource.as_ref().map_or(resolved, |(_, resource)| resource);
+        let mut context = CompilationContext::new(scope, Cow::Borrowed(config), resource);
+        context.recursive_anchor = self.key.recursive_anchor.as_ref().map(Cow::Borrowed);
+        context.references = references;
+        if !self.key.dynamic_scope.is_empty() {
+            let mut dynamic_scope = self.key.dynamic_scope.clone();
+            for resource in context.dynamic_scope.iter() {
+                if !dynamic_scope.contains(resource) {
+                    dynamic_scope.push(resource.clone())
+                }
+            }
+            context.dynamic_scope = Cow::Owned(dynamic_scope);
+        }
+        compile_validators(resolved, &context)
     }
 
     /// Ensure that validators are built and built once.
     #[inline]
-    fn ensure_validators<'a>(&self, schema: &'a JSONSchema) -> Result<(), ValidationError<'a>> {
-        if self.validators.read().is_none() {
-            let (scope, resolved) = schema.resolver.resolve_fragment(
-                schema.context.config.draft(),
-                &self.reference,
-                schema.schema,
-            )?;
-            let context = CompilationContext::new(scope, Cow::Borrowed(&schema.context.config));
-            let validators = compile_validators(&resolved, &context)?;
-
-            // Inject the validators into self.validators
-            *self.validators.write() = Some(validators);
+    fn ensure_validators<'a>(
+        &self,
+        schema: &'a JSONSchema,
+    ) -> Result<&(Url, Validators), ValidationError<'a>> {
+        self.target(schema).compiled.get_or_try_init(|| {
+            let (reference, scope, resolved) = self.resolve(schema).map_err(|error| {
+                // The error can't borrow from `self`
+                error.into_owned()
+            })?;
+            let validators = self.compile_target(schema, scope, &resolved, None)?;
+            Ok((self.locate(&reference, schema, &resolved), validators))
+        })
+    }
+
+    /// The error for a reference that can't be resolved during compilation.
+    fn unresolved(&self, error: ValidationError) -> CompilationError {
+        CompilationError::unresolved_reference(
+            self.schema_path.clone(),
+            self.key.reference.as_str(),
+            Box::new(error.into_owned()),
+        )
+    }
+}
+
+/// Resolve and compile the targets of all collected references, including the ones found in
+/// the targets themselves. Every target is compiled once, other references to it,
+/// including recursive ones, share it.
+pub(crate) fn resolve_references(
+    schema: &JSONSchema,
+    references: ReferenceCollector,
+) -> Result<(), CompilationError> {
+    let mut pending = references.into_inner();
+    while let Some(reference) = pending.pop() {
+        let target = reference.target(schema);
+        if target.compiled.get().is_some() {
+            continue;
         }
-        Ok(())
+        let (resolved_reference, scope, resolved) = reference
+            .resolve(schema)
+            .map_err(|error| reference.unresolved(error))?;
+        let found = ReferenceCollector::default();
+        let validators = reference.compile_target(schema, scope, &resolved, Some(&found))?;
+        let location = reference.locate(&resolved_reference, schema, &resolved);
+        // The target is not shared with other threads yet
+        let _ = target.compiled.set((location, validators));
+        pending.extend(found.into_inner());
     }
+    Ok(())
 }
 
 impl Validate for RefValidator {
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
-        if self.ensure_validators(schema).is_err() {
-            false
-        } else {
-            self.validators
-                .read()
-                .as_ref()
-                .expect("ensure_validators guarantees the presence of the validators")
+        match self.reference.ensure_validators(schema) {
+            Ok((_, validators)) => validators
                 .iter()
-                .all(move |validator| validator.is_valid(schema, instance))
+                .all(move |validator| validator.is_valid(schema, instance)),
+            Err(_) => false,
         }
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
-        if let Err(err) = self.ensure_validators(schema) {
-            error(err)
-        } else {
-            Box::new(
-                self.validators
-                    .read()
-                    .as_ref()
-                    .expect("ensure_validators guarantees the presence of the validators")
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        match self.reference.ensure_validators(schema) {
+            Ok((location, validators)) => Box::new(
+                validators
                     .iter()
-                    .flat_map(move |validator| validator.validate(schema, instance))
+                    .flat_map(move |validator| validator.validate(schema, instance, instance_path))
+                    .map(|mut error| {
+                        // Paths are relative to the referenced schema here
+                        error.rebase(location, &self.reference.schema_path);
+                        error
+                    })
                     .collect::<Vec<_>>()
                     .into_iter(),
-            )
+            ),
+            Err(mut err) => {
+                err.schema_path = self.reference.schema_path.clone();
+                err.instance_path = instance_path.into();
+                error(err)
+            }
+        }
+    }
+
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        evaluated: &mut Evaluated<'a>,
+    ) {
+        if let Ok((_, validators)) = self.reference.ensure_validators(schema) {
+            evaluate_subschema(validators, schema, instance, evaluated);
+        }
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        if let Ok((location, validators)) = self.reference.ensure_validators(schema) {
+            let start = annotations.keywords.len();
+            let valid =
+                annotate_subschema(validators, schema, instance, instance_path, annotations);
+            // Paths are relative to the referenced schema here
+            for annotation in &mut annotations.keywords[start..] {
+                if annotation.absolute_keyword_location.is_none() {
+                    annotation.absolute_keyword_location =
+                        Some(annotation.keyword_location.to_url(location));
+                }
+                annotation.keyword_location = self
+                    .reference
+                    .schema_path
+                    .extend_with(annotation.keyword_location.chunks());
+            }
+            valid
+        } else {
+            false
         }
     }
 }
 
 impl ToString for RefValidator {
     fn to_string(&self) -> String {
-        format!("$ref: {}", self.reference)
+        format!(
+            "{}: {}",
+            self.reference.key.kind.keyword(),
+            self.reference.key.reference
+        )
     }
 }
 
+fn has_recursive_anchor(schema: &Value) -> bool {
+    matches!(schema.get("$recursiveAnchor"), Some(Value::Bool(true)))
+}
+
+/// Find a path to `target` within `document` by comparing their addresses.
+fn find_location(document: &Value, target: &Value, chunks: &mut Vec<PathChunk>) -> bool {
+    if std::ptr::eq(document, target) {
+        return true;
+    }
+    match document {
+        Value::Object(object) => {
+            for (key, value) in object {
+                chunks.push(key.as_str().into());
+                if find_location(value, target, chunks) {
+                    return true;
+                }
+                chunks.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (idx, item) in items.iter().enumerate() {
+                chunks.push(idx.into());
+                if find_location(item, target, chunks) {
+                    return true;
+                }
+                chunks.pop();
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
 #[inline]
 pub(crate) fn compile(
-    _: &Value,
-    reference: &str,
+    _: &Map<String, Value>,
+    schema: &Value,
     context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(RefValidator::compile(reference, context))
+    match schema {
+        Value::String(reference) => Some(RefValidator::compile(
+            reference,
+            ReferenceKind::Static,
+            context,
+        )),
+        _ => Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("$ref"),
+            schema,
+        ))),
+    }
+}
+
+#[inline]
+pub(crate) fn compile_recursive(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::String(reference) => Some(RefValidator::compile(
+            reference,
+            ReferenceKind::Recursive,
+            context,
+        )),
+        _ => Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("$recursiveRef"),
+            schema,
+        ))),
+    }
+}
+
+#[inline]
+pub(crate) fn compile_dynamic(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::String(reference) => Some(RefValidator::compile(
+            reference,
+            ReferenceKind::Dynamic,
+            context,
+        )),
+        _ => Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("$dynamicRef"),
+            schema,
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{schemas::Draft, JSONSchema};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    /// Two schema resources that refer to each other.
+    fn mutual_resources(id: &str, definitions: &str, anchor: &str) -> Value {
+        serde_json::from_str(
+            &r#"{
+                "ID": "http://example.com/root",
+                "allOf": [{"$ref": "a"}],
+                "DEFS": {
+                    "a": {"ID": "a", ANCHOR "type": "object", "properties": {"b": {"$ref": "b"}}},
+                    "b": {"ID": "b", ANCHOR "type": "object", "properties": {"a": {"$ref": "a"}}}
+                }
+            }"#
+            .replace("ID", id)
+            .replace("DEFS", definitions)
+            .replace("ANCHOR", anchor),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn shared_targets() {
+        let schema = json!({
+            "properties": {
+                "foo": {"$ref": "#/definitions/positive"},
+                "bar": {"$ref": "#/definitions/positive"},
+                "baz": {"items": {"$ref": "#/definitions/positive"}}
+            },
+            "definitions": {"positive": {"minimum": 0}}
+        });
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        assert!(compiled.references.lock().is_empty());
+        assert!(!compiled.is_valid(&json!({"foo": 1, "bar": 2, "baz": [3, -1]})));
+        // All references point to the same compiled schema
+        assert_eq!(compiled.references.lock().len(), 1);
+    }
+
+    #[test]
+    fn shared_targets_eager() {
+        let schema = json!({
+            "properties": {
+                "foo": {"$ref": "#/definitions/node"},
+                "bar": {"$ref": "#/definitions/node"}
+            },
+            "definitions": {
+                "node": {"properties": {"children": {"type": "array", "items": {"$ref": "#/definitions/node"}}}}
+            }
+        });
+        let compiled = JSONSchema::options()
+            .should_resolve_references_eagerly(true)
+            .compile(&schema)
+            .unwrap();
+        {
+            let references = compiled.references.lock();
+            assert_eq!(references.len(), 1);
+            assert!(references
+                .values()
+                .all(|target| target.compiled.get().is_some()));
+        }
+        assert!(compiled.is_valid(&json!({"foo": {"children": [{"children": []}]}})));
+        assert!(!compiled.is_valid(&json!({"bar": {"children": [{"children": 1}]}})));
+    }
+
+    #[test_case(Draft::Draft4, "id", "definitions")]
+    #[test_case(Draft::Draft6, "$id", "definitions")]
+    #[test_case(Draft::Draft7, "$id", "definitions")]
+    #[test_case(Draft::Draft201909, "$id", "$defs")]
+    #[test_case(Draft::Draft202012, "$id", "$defs")]
+    fn cross_resource_cycles_eager(draft: Draft, id: &str, definitions: &str) {
+        let schema = mutual_resources(id, definitions, "");
+        let compiled = JSONSchema::options()
+            .with_draft(draft)
+            .should_resolve_references_eagerly(true)
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.references.lock().len(), 2);
+        assert!(compiled.is_valid(&json!({"b": {"a": {"b": {}}}})));
+        assert!(!compiled.is_valid(&json!({"b": {"a": {"b": 1}}})));
+        // All targets are compiled already
+        assert_eq!(compiled.references.lock().len(), 2);
+    }
+
+    #[test]
+    fn cross_resource_cycles_are_compiled_once() {
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$id": "http://example.com/root",
+            "$ref": "a",
+            "$defs": {
+                "a": {"$id": "a", "properties": {"b": {"$ref": "b"}}},
+                "b": {"$id": "b", "properties": {"a": {"$ref": "a"}}}
+            }
+        });
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let instance = json!({"b": {"a": {"b": {"a": {"b": {"a": {}}}}}}});
+        assert!(compiled.is_valid(&instance));
+        // Targets don't depend on the resources they are referenced from
+        assert_eq!(compiled.references.lock().len(), 2);
+    }
+
+    #[test_case(false)]
+    #[test_case(true)]
+    fn cross_resource_cycles_with_dynamic_anchors(eager: bool) {
+        let schema = mutual_resources("$id", "$defs", r#""$dynamicAnchor": "node","#);
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft202012)
+            .should_resolve_references_eagerly(eager)
+            .compile(&schema)
+            .unwrap();
+        let mut instance = json!({});
+        for _ in 0..10 {
+            instance = json!({"a": {"b": instance}});
+        }
+        assert!(compiled.is_valid(&json!({"b": instance})));
+        // Every resource is in the dynamic scope at most once, so the number of targets
+        // doesn't grow with the depth of the instance
+        assert!(compiled.references.lock().len() <= 4);
+    }
 }
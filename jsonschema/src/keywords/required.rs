@@ -2,29 +2,34 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use serde_json::{Map, Value};
 
 pub(crate) struct RequiredValidator {
     required: Vec<String>,
+    schema_path: JSONPointer,
 }
 
 impl RequiredValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, schema_path: JSONPointer) -> CompilationResult {
         match schema {
             Value::Array(items) => {
                 let mut required = Vec::with_capacity(items.len());
                 for item in items {
                     match item {
                         Value::String(string) => required.push(string.clone()),
-                        _ => return Err(CompilationError::SchemaError),
+                        _ => return Err(CompilationError::invalid_value(schema_path, schema)),
                     }
                 }
-                Ok(Box::new(RequiredValidator { required }))
+                Ok(Box::new(RequiredValidator {
+                    required,
+                    schema_path,
+                }))
             }
-            _ => Err(CompilationError::SchemaError),
+            _ => Err(CompilationError::invalid_value(schema_path, schema)),
         }
     }
 }
@@ -40,11 +45,21 @@ impl Validate for RequiredValidator {
         }
     }
 
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        _: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             for property_name in &self.required {
                 if !item.contains_key(property_name) {
-                    return error(ValidationError::required(instance, property_name.clone()));
+                    return error(ValidationError::required(
+                        self.schema_path.clone(),
+                        instance_path.into(),
+                        instance,
+                        property_name.clone(),
+                    ));
                 }
             }
         }
@@ -62,7 +77,8 @@ impl ToString for RequiredValidator {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(RequiredValidator::compile(schema))
+    let schema_path = context.as_pointer_with("required");
+    Some(RequiredValidator::compile(schema, schema_path))
 }
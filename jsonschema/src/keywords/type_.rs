@@ -2,19 +2,21 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
-    validator::Validate,
+    validator::{Annotations, Validate},
 };
 use serde_json::{Map, Number, Value};
 use std::convert::TryFrom;
 
 pub(crate) struct MultipleTypesValidator {
     types: PrimitiveTypesBitMap,
+    schema_path: JSONPointer,
 }
 
 impl MultipleTypesValidator {
     #[inline]
-    pub(crate) fn compile(items: &[Value]) -> CompilationResult {
+    pub(crate) fn compile(items: &[Value], schema_path: JSONPointer) -> CompilationResult {
         let mut types = PrimitiveTypesBitMap::new();
         for item in items {
             match item {
@@ -22,13 +24,21 @@ impl MultipleTypesValidator {
                     if let Ok(primitive_type) = PrimitiveType::try_from(string.as_str()) {
                         types |= primitive_type;
                     } else {
-                        return Err(CompilationError::SchemaError);
+                        return Err(CompilationError::invalid_value(
+                            schema_path,
+                            &Value::Array(items.to_vec()),
+                        ));
                     }
                 }
-                _ => return Err(CompilationError::SchemaError),
+                _ => {
+                    return Err(CompilationError::invalid_value(
+                        schema_path,
+                        &Value::Array(items.to_vec()),
+                    ))
+                }
             }
         }
-        Ok(Box::new(MultipleTypesValidator { types }))
+        Ok(Box::new(MultipleTypesValidator { types, schema_path }))
     }
 }
 
@@ -46,13 +56,34 @@ impl Validate for MultipleTypesValidator {
             Value::String(_) => self.types.contains_type(PrimitiveType::String),
         }
     }
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::multiple_type_error(instance, self.types))
+            error(ValidationError::multiple_type_error(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+                self.types,
+            ))
         }
     }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotations.coercion(self.types, instance, instance_path);
+        self.is_valid(schema, instance)
+    }
 }
 
 impl ToString for MultipleTypesValidator {
@@ -68,12 +99,14 @@ impl ToString for MultipleTypesValidator {
     }
 }
 
-pub(crate) struct NullTypeValidator {}
+pub(crate) struct NullTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl NullTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(NullTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(NullTypeValidator { schema_path }))
     }
 }
 
@@ -81,16 +114,38 @@ impl Validate for NullTypeValidator {
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         instance.is_null()
     }
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
+                instance_path.into(),
                 instance,
                 PrimitiveType::Null,
             ))
         }
     }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotations.coercion(
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Null),
+            instance,
+            instance_path,
+        );
+        self.is_valid(schema, instance)
+    }
 }
 
 impl ToString for NullTypeValidator {
@@ -99,12 +154,14 @@ impl ToString for NullTypeValidator {
     }
 }
 
-pub(crate) struct BooleanTypeValidator {}
+pub(crate) struct BooleanTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl BooleanTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(BooleanTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(BooleanTypeValidator { schema_path }))
     }
 }
 
@@ -112,16 +169,38 @@ impl Validate for BooleanTypeValidator {
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         instance.is_boolean()
     }
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
+                instance_path.into(),
                 instance,
                 PrimitiveType::Boolean,
             ))
         }
     }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotations.coercion(
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Boolean),
+            instance,
+            instance_path,
+        );
+        self.is_valid(schema, instance)
+    }
 }
 
 impl ToString for BooleanTypeValidator {
@@ -130,12 +209,14 @@ impl ToString for BooleanTypeValidator {
     }
 }
 
-pub(crate) struct StringTypeValidator {}
+pub(crate) struct StringTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl StringTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(StringTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(StringTypeValidator { schema_path }))
     }
 }
 
@@ -144,11 +225,18 @@ impl Validate for StringTypeValidator {
         instance.is_string()
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
+                instance_path.into(),
                 instance,
                 PrimitiveType::String,
             ))
@@ -161,12 +249,14 @@ impl ToString for StringTypeValidator {
     }
 }
 
-pub(crate) struct ArrayTypeValidator {}
+pub(crate) struct ArrayTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl ArrayTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(ArrayTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(ArrayTypeValidator { schema_path }))
     }
 }
 
@@ -175,11 +265,18 @@ impl Validate for ArrayTypeValidator {
         instance.is_array()
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
+                instance_path.into(),
                 instance,
                 PrimitiveType::Array,
             ))
@@ -193,12 +290,14 @@ impl ToString for ArrayTypeValidator {
     }
 }
 
-pub(crate) struct ObjectTypeValidator {}
+pub(crate) struct ObjectTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl ObjectTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(ObjectTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(ObjectTypeValidator { schema_path }))
     }
 }
 
@@ -206,11 +305,18 @@ impl Validate for ObjectTypeValidator {
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         instance.is_object()
     }
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
+                instance_path.into(),
                 instance,
                 PrimitiveType::Object,
             ))
@@ -224,12 +330,14 @@ impl ToString for ObjectTypeValidator {
     }
 }
 
-pub(crate) struct NumberTypeValidator {}
+pub(crate) struct NumberTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl NumberTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(NumberTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(NumberTypeValidator { schema_path }))
     }
 }
 
@@ -237,28 +345,52 @@ impl Validate for NumberTypeValidator {
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         instance.is_number()
     }
-    fn validate<'a>(&self, config: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        config: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(config, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
+                instance_path.into(),
                 instance,
                 PrimitiveType::Number,
             ))
         }
     }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotations.coercion(
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Number),
+            instance,
+            instance_path,
+        );
+        self.is_valid(schema, instance)
+    }
 }
 impl ToString for NumberTypeValidator {
     fn to_string(&self) -> String {
         "type: number".to_string()
     }
 }
-pub(crate) struct IntegerTypeValidator {}
+pub(crate) struct IntegerTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl IntegerTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(IntegerTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(IntegerTypeValidator { schema_path }))
     }
 }
 
@@ -270,16 +402,38 @@ impl Validate for IntegerTypeValidator {
             false
         }
     }
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
+                instance_path.into(),
                 instance,
                 PrimitiveType::Integer,
             ))
         }
     }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        annotations.coercion(
+            PrimitiveTypesBitMap::new().add_type(PrimitiveType::Integer),
+            instance,
+            instance_path,
+        );
+        self.is_valid(schema, instance)
+    }
 }
 
 impl ToString for IntegerTypeValidator {
@@ -296,34 +450,38 @@ fn is_integer(num: &Number) -> bool {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
+    let schema_path = context.as_pointer_with("type");
     match schema {
-        Value::String(item) => compile_single_type(item.as_str()),
+        Value::String(item) => compile_single_type(item.as_str(), schema_path),
         Value::Array(items) => {
             if items.len() == 1 {
                 if let Some(Value::String(item)) = items.iter().next() {
-                    compile_single_type(item.as_str())
+                    compile_single_type(item.as_str(), schema_path)
                 } else {
-                    Some(Err(CompilationError::SchemaError))
+                    Some(Err(CompilationError::invalid_value(schema_path, schema)))
                 }
             } else {
-                Some(MultipleTypesValidator::compile(items))
+                Some(MultipleTypesValidator::compile(items, schema_path))
             }
         }
-        _ => Some(Err(CompilationError::SchemaError)),
+        _ => Some(Err(CompilationError::invalid_value(schema_path, schema))),
     }
 }
 
-fn compile_single_type(item: &str) -> Option<CompilationResult> {
+fn compile_single_type(item: &str, schema_path: JSONPointer) -> Option<CompilationResult> {
     match PrimitiveType::try_from(item) {
-        Ok(PrimitiveType::Array) => Some(ArrayTypeValidator::compile()),
-        Ok(PrimitiveType::Boolean) => Some(BooleanTypeValidator::compile()),
-        Ok(PrimitiveType::Integer) => Some(IntegerTypeValidator::compile()),
-        Ok(PrimitiveType::Null) => Some(NullTypeValidator::compile()),
-        Ok(PrimitiveType::Number) => Some(NumberTypeValidator::compile()),
-        Ok(PrimitiveType::Object) => Some(ObjectTypeValidator::compile()),
-        Ok(PrimitiveType::String) => Some(StringTypeValidator::compile()),
-        Err(()) => Some(Err(CompilationError::SchemaError)),
+        Ok(PrimitiveType::Array) => Some(ArrayTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::Boolean) => Some(BooleanTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::Integer) => Some(IntegerTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::Null) => Some(NullTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::Number) => Some(NumberTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::Object) => Some(ObjectTypeValidator::compile(schema_path)),
+        Ok(PrimitiveType::String) => Some(StringTypeValidator::compile(schema_path)),
+        Err(()) => Some(Err(CompilationError::invalid_value(
+            schema_path,
+            &Value::String(item.to_string()),
+        ))),
     }
 }
@@ -0,0 +1,393 @@
+use crate::{
+    compilation::{compile_validators, context::CompilationContext, JSONSchema},
+    error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    keywords::{format_validators, BoxedValidator, CompilationResult, Validators},
+    paths::{InstancePath, JSONPointer},
+    validator::{annotate_subschema, Annotations, Evaluated, Validate},
+};
+use serde_json::{Map, Value};
+
+/// Keywords from the same schema object are compiled separately to collect properties and items
+/// they evaluate. `$id` is excluded as it is already applied to the context.
+fn compile_siblings(
+    parent: &Map<String, Value>,
+    context: &CompilationContext,
+) -> Result<Validators, CompilationError> {
+    let siblings: Map<String, Value> = parent
+        .iter()
+        .filter(|(keyword, _)| {
+            !matches!(
+                keyword.as_str(),
+                "$id" | "unevaluatedProperties" | "unevaluatedItems"
+            )
+        })
+        .map(|(keyword, value)| (keyword.clone(), value.clone()))
+        .collect();
+    compile_validators(&Value::Object(siblings), context)
+}
+
+#[inline]
+fn evaluate_siblings<'a>(
+    siblings: &[BoxedValidator],
+    schema: &JSONSchema,
+    instance: &'a Value,
+) -> Evaluated<'a> {
+    let mut evaluated = Evaluated::default();
+    for validator in siblings {
+        validator.evaluate(schema, instance, &mut evaluated)
+    }
+    evaluated
+}
+
+pub(crate) struct UnevaluatedPropertiesValidator {
+    siblings: Validators,
+    validators: Validators,
+}
+impl UnevaluatedPropertiesValidator {
+    #[inline]
+    pub(crate) fn compile(
+        parent: &Map<String, Value>,
+        schema: &Value,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        Ok(Box::new(UnevaluatedPropertiesValidator {
+            siblings: compile_siblings(parent, context)?,
+            validators: compile_validators(schema, &context.with_path("unevaluatedProperties"))?,
+        }))
+    }
+}
+impl Validate for UnevaluatedPropertiesValidator {
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Object(item) = instance {
+            let evaluated = evaluate_siblings(&self.siblings, schema, instance);
+            item.iter()
+                .filter(|(property, _)| !evaluated.properties.contains(property.as_str()))
+                .all(|(_, value)| {
+                    self.validators
+                        .iter()
+                        .all(|validator| validator.is_valid(schema, value))
+                })
+        } else {
+            true
+        }
+    }
+
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::Object(item) = instance {
+            let evaluated = evaluate_siblings(&self.siblings, schema, instance);
+            let errors: Vec<_> = item
+                .iter()
+                .filter(|(property, _)| !evaluated.properties.contains(property.as_str()))
+                .flat_map(|(property, value)| {
+                    let instance_path = instance_path.push(property.clone());
+                    self.validators
+                        .iter()
+                        .flat_map(move |validator| {
+                            validator.validate(schema, value, &instance_path)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            Box::new(errors.into_iter())
+        } else {
+            no_error()
+        }
+    }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.all_properties(instance)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Object(item) = instance {
+            let evaluated = evaluate_siblings(&self.siblings, schema, instance);
+            for (property, value) in item {
+                if !evaluated.properties.contains(property.as_str()) {
+                    valid &= annotate_subschema(
+                        &self.validators,
+                        schema,
+                        value,
+                        &instance_path.push(property.clone()),
+                        annotations,
+                    );
+                }
+            }
+        }
+        valid
+    }
+}
+impl ToString for UnevaluatedPropertiesValidator {
+    fn to_string(&self) -> String {
+        format!(
+            "unevaluatedProperties: {}",
+            format_validators(&self.validators)
+        )
+    }
+}
+
+pub(crate) struct UnevaluatedPropertiesFalseValidator {
+    siblings: Validators,
+    schema_path: JSONPointer,
+}
+impl UnevaluatedPropertiesFalseValidator {
+    #[inline]
+    pub(crate) fn compile(
+        parent: &Map<String, Value>,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        Ok(Box::new(UnevaluatedPropertiesFalseValidator {
+            siblings: compile_siblings(parent, context)?,
+            schema_path: context.as_pointer_with("unevaluatedProperties"),
+        }))
+    }
+}
+impl Validate for UnevaluatedPropertiesFalseValidator {
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Object(item) = instance {
+            let evaluated = evaluate_siblings(&self.siblings, schema, instance);
+            item.keys()
+                .all(|property| evaluated.properties.contains(property.as_str()))
+        } else {
+            true
+        }
+    }
+
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::Object(item) = instance {
+            let evaluated = evaluate_siblings(&self.siblings, schema, instance);
+            let unexpected: Vec<String> = item
+                .keys()
+                .filter(|property| !evaluated.properties.contains(property.as_str()))
+                .cloned()
+                .collect();
+            if !unexpected.is_empty() {
+                return error(ValidationError::unevaluated_properties(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    unexpected,
+                ));
+            }
+        }
+        no_error()
+    }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.all_properties(instance)
+    }
+}
+impl ToString for UnevaluatedPropertiesFalseValidator {
+    fn to_string(&self) -> String {
+        "unevaluatedProperties: false".to_string()
+    }
+}
+
+pub(crate) struct UnevaluatedItemsValidator {
+    siblings: Validators,
+    validators: Validators,
+}
+impl UnevaluatedItemsValidator {
+    #[inline]
+    pub(crate) fn compile(
+        parent: &Map<String, Value>,
+        schema: &Value,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        Ok(Box::new(UnevaluatedItemsValidator {
+            siblings: compile_siblings(parent, context)?,
+            validators: compile_validators(schema, &context.with_path("unevaluatedItems"))?,
+        }))
+    }
+}
+impl Validate for UnevaluatedItemsValidator {
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Array(items) = instance {
+            let evaluated = evaluate_siblings(&self.siblings, schema, instance);
+            items
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| !evaluated.items.contains(idx))
+                .all(|(_, item)| {
+                    self.validators
+                        .iter()
+                        .all(|validator| validator.is_valid(schema, item))
+                })
+        } else {
+            true
+        }
+    }
+
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::Array(items) = instance {
+            let evaluated = evaluate_siblings(&self.siblings, schema, instance);
+            let errors: Vec<_> = items
+                .iter()
+                .enumerate()
+                .filter(|(idx, _)| !evaluated.items.contains(idx))
+                .flat_map(|(idx, item)| {
+                    let instance_path = instance_path.push(idx);
+                    self.validators
+                        .iter()
+                        .flat_map(move |validator| validator.validate(schema, item, &instance_path))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            Box::new(errors.into_iter())
+        } else {
+            no_error()
+        }
+    }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.items_from(instance, 0)
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        instance_path: &InstancePath,
+        annotations: &mut Annotations,
+    ) -> bool {
+        let mut valid = true;
+        if let Value::Array(items) = instance {
+            let evaluated = evaluate_siblings(&self.siblings, schema, instance);
+            for (idx, item) in items.iter().enumerate() {
+                if !evaluated.items.contains(&idx) {
+                    valid &= annotate_subschema(
+                        &self.validators,
+                        schema,
+                        item,
+                        &instance_path.push(idx),
+                        annotations,
+                    );
+                }
+            }
+        }
+        valid
+    }
+}
+impl ToString for UnevaluatedItemsValidator {
+    fn to_string(&self) -> String {
+        format!("unevaluatedItems: {}", format_validators(&self.validators))
+    }
+}
+
+pub(crate) struct UnevaluatedItemsFalseValidator {
+    siblings: Validators,
+    schema_path: JSONPointer,
+}
+impl UnevaluatedItemsFalseValidator {
+    #[inline]
+    pub(crate) fn compile(
+        parent: &Map<String, Value>,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        Ok(Box::new(UnevaluatedItemsFalseValidator {
+            siblings: compile_siblings(parent, context)?,
+            schema_path: context.as_pointer_with("unevaluatedItems"),
+        }))
+    }
+}
+impl Validate for UnevaluatedItemsFalseValidator {
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Array(items) = instance {
+            let evaluated = evaluate_siblings(&self.siblings, schema, instance);
+            (0..items.len()).all(|idx| evaluated.items.contains(&idx))
+        } else {
+            true
+        }
+    }
+
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
+        if let Value::Array(items) = instance {
+            let evaluated = evaluate_siblings(&self.siblings, schema, instance);
+            let unexpected: Vec<usize> = (0..items.len())
+                .filter(|idx| !evaluated.items.contains(idx))
+                .collect();
+            if !unexpected.is_empty() {
+                return error(ValidationError::unevaluated_items(
+                    self.schema_path.clone(),
+                    instance_path.into(),
+                    instance,
+                    unexpected,
+                ));
+            }
+        }
+        no_error()
+    }
+
+    fn evaluate<'a>(&self, _: &JSONSchema, instance: &'a Value, evaluated: &mut Evaluated<'a>) {
+        evaluated.items_from(instance, 0)
+    }
+}
+impl ToString for UnevaluatedItemsFalseValidator {
+    fn to_string(&self) -> String {
+        "unevaluatedItems: false".to_string()
+    }
+}
+
+#[inline]
+pub(crate) fn compile_properties(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::Bool(false) => Some(UnevaluatedPropertiesFalseValidator::compile(
+            parent, context,
+        )),
+        Value::Bool(true) | Value::Object(_) => Some(UnevaluatedPropertiesValidator::compile(
+            parent, schema, context,
+        )),
+        _ => Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("unevaluatedProperties"),
+            schema,
+        ))),
+    }
+}
+
+#[inline]
+pub(crate) fn compile_items(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::Bool(false) => Some(UnevaluatedItemsFalseValidator::compile(parent, context)),
+        Value::Bool(true) | Value::Object(_) => {
+            Some(UnevaluatedItemsValidator::compile(parent, schema, context))
+        }
+        _ => Some(Err(CompilationError::invalid_value(
+            context.as_pointer_with("unevaluatedItems"),
+            schema,
+        ))),
+    }
+}
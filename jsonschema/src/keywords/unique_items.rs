@@ -2,6 +2,7 @@ use crate::{
     compilation::{context::CompilationContext, JSONSchema},
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::CompilationResult,
+    paths::{InstancePath, JSONPointer},
     validator::Validate,
 };
 use ahash::{AHashSet, AHasher};
@@ -57,12 +58,14 @@ pub(crate) fn is_unique(items: &[Value]) -> bool {
     items.iter().map(HashedValue).all(move |x| seen.insert(x))
 }
 
-pub(crate) struct UniqueItemsValidator {}
+pub(crate) struct UniqueItemsValidator {
+    schema_path: JSONPointer,
+}
 
 impl UniqueItemsValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(UniqueItemsValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(UniqueItemsValidator { schema_path }))
     }
 }
 
@@ -76,11 +79,20 @@ impl Validate for UniqueItemsValidator {
         true
     }
 
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::unique_items(instance))
+            error(ValidationError::unique_items(
+                self.schema_path.clone(),
+                instance_path.into(),
+                instance,
+            ))
         }
     }
 }
@@ -94,11 +106,12 @@ impl ToString for UniqueItemsValidator {
 pub(crate) fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
     if let Value::Bool(value) = schema {
         if *value {
-            Some(UniqueItemsValidator::compile())
+            let schema_path = context.as_pointer_with("uniqueItems");
+            Some(UniqueItemsValidator::compile(schema_path))
         } else {
             None
         }
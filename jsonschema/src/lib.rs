@@ -46,7 +46,9 @@
 //!     let result = compiled.validate(&instance);
 //!     if let Err(errors) = result {
 //!         for error in errors {
-//!             println!("Validation error: {}", error)
+//!             println!("Validation error: {}", error);
+//!             println!("Instance path: {}", error.instance_path);
+//!             println!("Schema path: {}", error.schema_path);
 //!         }
 //!     }
 //!     Ok(())
@@ -73,19 +75,34 @@
     unreachable_pub,
     variant_size_differences
 )]
-#![allow(clippy::unnecessary_wraps, clippy::upper_case_acronyms)]
+#![allow(
+    clippy::unnecessary_wraps,
+    clippy::upper_case_acronyms,
+    clippy::result_large_err
+)]
 #![cfg_attr(not(test), allow(clippy::integer_arithmetic, clippy::unwrap_used))]
+#[cfg(any(feature = "reqwest", test))]
+mod cache;
 mod compilation;
 mod content_encoding;
 mod content_media_type;
 pub mod error;
 mod keywords;
+pub mod output;
+pub mod paths;
 pub mod primitive_type;
 mod resolver;
 mod schemas;
 mod validator;
-pub use compilation::{options::CompilationOptions, JSONSchema};
-pub use error::{CompilationError, ErrorIterator, ValidationError};
+#[cfg(any(feature = "reqwest", test))]
+pub use cache::DiskCache;
+pub use compilation::{options::CompilationOptions, JSONSchema, OwnedJSONSchema};
+pub use error::{
+    best_match, CompilationError, CompilationErrorKind, ErrorIterator, LoadError, SchemaViolation,
+    ValidationError,
+};
+pub use keywords::custom::{Keyword, KeywordFactory};
+pub use resolver::{SchemaResolver, SchemaResolverError};
 pub use schemas::Draft;
 use serde_json::Value;
 
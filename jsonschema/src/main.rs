@@ -48,9 +48,12 @@ pub fn main() -> BoxErrorResult<()> {
 fn validate_instances(instances: &[PathBuf], schema: PathBuf) -> BoxErrorResult<bool> {
     let mut success = true;
 
-    let schema_json = fs::read_to_string(schema)?;
+    let schema_json = fs::read_to_string(&schema)?;
     let schema_json = serde_json::from_str(&schema_json)?;
-    let schema = JSONSchema::compile(&schema_json)?;
+    // Relative references are resolved against the schema location
+    let schema = JSONSchema::options()
+        .with_base_path(&schema)
+        .compile(&schema_json)?;
 
     for instance in instances {
         let instance_path_name = instance.to_str().unwrap();
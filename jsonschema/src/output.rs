@@ -0,0 +1,950 @@
+//! Output formats defined by the JSON Schema specification.
+//!
+//! Validation results may be presented in four formats with an increasing level of detail:
+//!   - `Flag` - only a boolean result;
+//!   - `Basic` - a flat list of errors;
+//!   - `Detailed` - errors structured by the schema locations that produced them. Intermediate
+//!     nodes with only one child are collapsed into that child;
+//!   - `Verbose` - the same hierarchy as `Detailed` without collapsing.
+//!
+//! All of them are serializable with `serde` and follow the structure from the
+//! "Output Formatting" section of the specification.
+//!
+//! Only failed evaluations are reported, therefore `Verbose` contains all subschemas on the way to
+//! every error, but not the subschemas that passed validation. Errors of `anyOf` / `oneOf`
+//! subschemas are separate units, which are nested under the `anyOf` / `oneOf` unit in the
+//! hierarchical formats.
+//!
+//! Annotations, e.g. `title` or `default`, are available separately via `Output::annotations`.
+//!
+//! ```rust
+//! use jsonschema::JSONSchema;
+//! use serde_json::json;
+//!
+//! let schema = json!({"items": {"type": "string"}});
+//! let instance = json!(["a", 1]);
+//! let compiled = JSONSchema::compile(&schema).expect("A valid schema");
+//! let output = serde_json::to_value(compiled.apply(&instance).basic()).expect("Serializable");
+//! assert_eq!(
+//!     output,
+//!     json!({
+//!         "valid": false,
+//!         "errors": [
+//!             {
+//!                 "valid": false,
+//!                 "keywordLocation": "/items/type",
+//!                 "absoluteKeywordLocation": "json-schema:///#/items/type",
+//!                 "instanceLocation": "/1",
+//!                 "error": "'1' is not of type 'string'",
+//!                 "kind": {"Type": {"kind": {"Single": "string"}}}
+//!             }
+//!         ]
+//!     })
+//! );
+//! ```
+use crate::{
+    compilation::JSONSchema,
+    error::{ValidationError, ValidationErrorKind},
+    paths::{InstancePath, JSONPointer, PathChunk},
+    validator::{annotate_subschema, Annotations},
+};
+use serde::Serialize;
+use serde_json::Value;
+use url::Url;
+
+/// Validation result of a specific instance that can be presented in any of the output formats.
+#[derive(Debug)]
+pub struct Output<'a> {
+    schema: &'a JSONSchema<'a>,
+    instance: &'a Value,
+}
+
+impl<'a> Output<'a> {
+    pub(crate) const fn new(schema: &'a JSONSchema<'a>, instance: &'a Value) -> Output<'a> {
+        Output { schema, instance }
+    }
+
+    /// The `Flag` output format. It is the fastest one as no errors are collected.
+    #[must_use]
+    pub fn flag(&self) -> FlagOutput {
+        FlagOutput {
+            valid: self.schema.is_valid(self.instance),
+        }
+    }
+
+    /// The `Basic` output format - a flat list of all errors.
+    #[must_use]
+    pub fn basic(&self) -> BasicOutput {
+        let mut errors = Vec::new();
+        for error in self.errors() {
+            flatten(error, &mut errors)
+        }
+        BasicOutput {
+            valid: errors.is_empty(),
+            errors,
+        }
+    }
+
+    /// The `Detailed` output format - a condensed hierarchy of errors.
+    #[must_use]
+    pub fn detailed(&self) -> OutputUnit {
+        let mut root = self.hierarchy();
+        root.errors = root.errors.into_iter().map(OutputUnit::condense).collect();
+        root
+    }
+
+    /// The `Verbose` output format - a full hierarchy of errors.
+    #[must_use]
+    pub fn verbose(&self) -> OutputUnit {
+        self.hierarchy()
+    }
+
+    /// Annotations from all subschemas that apply to the instance and its parts.
+    ///
+    /// Subschemas of `anyOf`, `oneOf`, `if` and `contains` apply only if the instance is valid
+    /// against them, and `not` never applies. As the specification requires, annotations of
+    /// subschemas that fail validation are dropped, therefore invalid instances have none.
+    ///
+    /// Annotation keywords are compiled only if `CompilationOptions::should_collect_annotations`
+    /// is enabled, otherwise there are no annotations.
+    #[must_use]
+    pub fn annotations(&self) -> AnnotationsOutput {
+        let mut annotations = Annotations::default();
+        let valid = annotate_subschema(
+            &self.schema.validators,
+            self.schema,
+            self.instance,
+            &InstancePath::new(),
+            &mut annotations,
+        );
+        for annotation in &mut annotations.keywords {
+            // Annotations that didn't pass through any reference are located in the root schema
+            if annotation.absolute_keyword_location.is_none() {
+                annotation.absolute_keyword_location = Some(
+                    annotation
+                        .keyword_location
+                        .to_url(&self.schema.context.scope),
+                );
+            }
+        }
+        AnnotationsOutput {
+            valid,
+            annotations: annotations.keywords,
+        }
+    }
+
+    fn errors(&self) -> Box<dyn Iterator<Item = ValidationError<'a>> + 'a> {
+        match self.schema.validate(self.instance) {
+            Ok(()) => Box::new(std::iter::empty()),
+            Err(errors) => errors,
+        }
+    }
+
+    /// Build a tree where each error is nested under all subschemas that led to it.
+    fn hierarchy(&self) -> OutputUnit {
+        let mut root = OutputUnit::node(JSONPointer::default(), None, JSONPointer::default());
+        for error in self.errors() {
+            self.insert(&mut root, error)
+        }
+        root.valid = root.errors.is_empty();
+        root
+    }
+
+    /// Insert an error into the hierarchy under `parent` together with the errors of its
+    /// subschemas.
+    fn insert(&self, parent: &mut OutputUnit, error: ValidationError<'_>) {
+        let (mut leaf, context) = split(error);
+        for error in context.into_iter().flatten() {
+            self.insert(&mut leaf, error)
+        }
+        let keyword_chunks = leaf.keyword_location.chunks();
+        let instance_chunks = leaf.instance_location.chunks();
+        let depth = parent.keyword_location.chunks().len();
+        let mut current = parent;
+        for (keyword_length, instance_length) in ancestors(keyword_chunks, instance_chunks)
+            .into_iter()
+            // Subschemas up to `parent` are already in the hierarchy
+            .filter(|(keyword_length, _)| *keyword_length > depth)
+        {
+            let keyword_location = JSONPointer::from(keyword_chunks[..keyword_length].to_vec());
+            let instance_location = JSONPointer::from(instance_chunks[..instance_length].to_vec());
+            let position = current.errors.iter().position(|unit| {
+                unit.error.is_none()
+                    && unit.keyword_location == keyword_location
+                    && unit.instance_location == instance_location
+            });
+            current = if let Some(idx) = position {
+                &mut current.errors[idx]
+            } else {
+                let absolute_keyword_location = ancestor_location(
+                    &self.schema.context.scope,
+                    &leaf.absolute_keyword_location,
+                    keyword_chunks,
+                    keyword_length,
+                );
+                current.errors.push(OutputUnit::node(
+                    keyword_location,
+                    absolute_keyword_location,
+                    instance_location,
+                ));
+                current
+                    .errors
+                    .last_mut()
+                    .expect("The node is inserted above")
+            };
+        }
+        current.errors.push(leaf);
+    }
+}
+
+/// Add an error and the errors of its subschemas to a flat list.
+fn flatten(error: ValidationError<'_>, units: &mut Vec<OutputUnit>) {
+    let (unit, context) = split(error);
+    units.push(unit);
+    for error in context.into_iter().flatten() {
+        flatten(error, units)
+    }
+}
+
+/// Separate errors of subschemas from the error, so they can be reported as separate units.
+fn split(mut error: ValidationError<'_>) -> (OutputUnit, Vec<Vec<ValidationError<'static>>>) {
+    let context = match &mut error.kind {
+        ValidationErrorKind::AnyOf { context } | ValidationErrorKind::OneOfNotValid { context } => {
+            std::mem::take(context)
+        }
+        _ => Vec::new(),
+    };
+    (OutputUnit::from(error), context)
+}
+
+/// The `Flag` output format.
+#[derive(Debug, Serialize)]
+pub struct FlagOutput {
+    /// Whether the instance is valid.
+    pub valid: bool,
+}
+
+/// The `Basic` output format.
+#[derive(Debug, Serialize)]
+pub struct BasicOutput {
+    /// Whether the instance is valid.
+    pub valid: bool,
+    /// All errors that occurred during validation.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<OutputUnit>,
+}
+
+/// A single node of the output. It either describes an error or groups other nodes
+/// that were produced by the same subschema.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputUnit {
+    /// Whether the instance is valid against the subschema at `keyword_location`.
+    pub valid: bool,
+    /// Location of the keyword or subschema. `$ref` chunks are kept as they are.
+    pub keyword_location: JSONPointer,
+    /// Absolute location of the keyword or subschema after resolving all references.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute_keyword_location: Option<Url>,
+    /// Location of the value within the validated instance.
+    pub instance_location: JSONPointer,
+    /// Error message. Present only in nodes that describe a specific error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Type of the error. Present only in nodes that describe a specific error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ValidationErrorKind>,
+    /// Nested nodes.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<OutputUnit>,
+}
+
+impl OutputUnit {
+    const fn node(
+        keyword_location: JSONPointer,
+        absolute_keyword_location: Option<Url>,
+        instance_location: JSONPointer,
+    ) -> OutputUnit {
+        OutputUnit {
+            valid: false,
+            keyword_location,
+            absolute_keyword_location,
+            instance_location,
+            error: None,
+            kind: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Replace intermediate nodes that have only one child with this child.
+    fn condense(mut self) -> OutputUnit {
+        self.errors = self.errors.into_iter().map(OutputUnit::condense).collect();
+        if self.error.is_none() && self.errors.len() == 1 {
+            self.errors.pop().expect("The length is checked above")
+        } else {
+            self
+        }
+    }
+}
+
+impl From<ValidationError<'_>> for OutputUnit {
+    fn from(error: ValidationError<'_>) -> Self {
+        let message = error.to_string();
+        OutputUnit {
+            valid: false,
+            keyword_location: error.schema_path,
+            absolute_keyword_location: error.absolute_keyword_location,
+            instance_location: error.instance_path,
+            error: Some(message),
+            kind: Some(error.kind),
+            errors: Vec::new(),
+        }
+    }
+}
+
+/// Validation result together with annotations collected from the applied subschemas.
+#[derive(Debug, Serialize)]
+pub struct AnnotationsOutput {
+    /// Whether the instance is valid.
+    pub valid: bool,
+    /// Annotations in the order of evaluation.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+}
+
+impl AnnotationsOutput {
+    /// Annotations for the given location within the instance, e.g. `/items/0/name`.
+    pub fn at<'b>(&'b self, instance_location: &'b str) -> impl Iterator<Item = &'b Annotation> {
+        self.annotations
+            .iter()
+            .filter(move |annotation| annotation.instance_location.to_string() == instance_location)
+    }
+}
+
+/// A value of an annotation keyword that applies to a location within the instance.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotation {
+    /// Location of the keyword. `$ref` chunks are kept as they are.
+    pub keyword_location: JSONPointer,
+    /// Absolute location of the keyword after resolving all references.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute_keyword_location: Option<Url>,
+    /// Location of the value within the validated instance.
+    pub instance_location: JSONPointer,
+    /// The keyword value.
+    pub annotation: Value,
+}
+
+impl Annotation {
+    /// The name of the keyword, e.g. `title`.
+    #[must_use]
+    pub fn keyword(&self) -> &str {
+        match self.keyword_location.chunks().last() {
+            Some(PathChunk::Name(keyword)) => keyword,
+            _ => "",
+        }
+    }
+}
+
+/// Locations of all subschemas that were evaluated on the way to the failed keyword.
+/// Each location is represented as the lengths of the keyword & instance location prefixes.
+fn ancestors(
+    keyword_location: &[PathChunk],
+    instance_location: &[PathChunk],
+) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+    let mut idx = 0;
+    let mut depth = 0;
+    while let Some(PathChunk::Name(keyword)) = keyword_location.get(idx) {
+        let step = match keyword.as_str() {
+            "properties" | "patternProperties" => {
+                depth += 1;
+                2
+            }
+            "items" | "prefixItems" => {
+                depth += 1;
+                if let Some(PathChunk::Index(_)) = keyword_location.get(idx + 1) {
+                    2
+                } else {
+                    1
+                }
+            }
+            "additionalProperties"
+            | "additionalItems"
+            | "contains"
+            | "unevaluatedProperties"
+            | "unevaluatedItems" => {
+                depth += 1;
+                1
+            }
+            "allOf" | "anyOf" | "oneOf" | "dependencies" | "dependentSchemas" => 2,
+            "not" | "if" | "then" | "else" | "propertyNames" | "contentSchema" | "$ref"
+            | "$recursiveRef" | "$dynamicRef" => 1,
+            // Other keywords don't contain subschemas
+            _ => break,
+        };
+        idx += step;
+        if idx >= keyword_location.len() {
+            // The keyword itself is the failed one
+            break;
+        }
+        result.push((idx, depth.min(instance_location.len())));
+    }
+    result
+}
+
+/// Absolute location of a subschema that contains the failed keyword.
+///
+/// If there are no references between the subschema and the keyword, then the subschema is in the
+/// same document and its location is derived from the keyword location. Otherwise, it is known only
+/// if there are no references before the subschema, i.e. it is a part of the root schema.
+fn ancestor_location(
+    root: &Url,
+    leaf: &Option<Url>,
+    keyword_location: &[PathChunk],
+    length: usize,
+) -> Option<Url> {
+    let is_reference = |chunk: &PathChunk| matches!(chunk, PathChunk::Name(name) if matches!(name.as_str(), "$ref" | "$recursiveRef" | "$dynamicRef"));
+    if keyword_location[length..].iter().any(is_reference) {
+        if keyword_location[..length].iter().any(is_reference) {
+            None
+        } else {
+            Some(JSONPointer::from(keyword_location[..length].to_vec()).to_url(root))
+        }
+    } else {
+        leaf.as_ref().map(|leaf| {
+            let mut location = leaf.clone();
+            let fragment = leaf.fragment().unwrap_or("");
+            let components: Vec<_> = fragment.split('/').collect();
+            let retained = components
+                .len()
+                .saturating_sub(keyword_location.len() - length);
+            location.set_fragment(Some(&components[..retained].join("/")));
+            location
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compilation::JSONSchema, schemas::Draft};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn schema() -> Value {
+        // Adapted from the "Output Formatting" section of the specification
+        json!({
+            "$id": "https://example.com/polygon",
+            "definitions": {
+                "point": {
+                    "type": "object",
+                    "properties": {
+                        "x": {"type": "number"},
+                        "y": {"type": "number"}
+                    },
+                    "additionalProperties": false,
+                    "required": ["x", "y"]
+                }
+            },
+            "type": "array",
+            "items": {"$ref": "#/definitions/point"},
+            "minItems": 3
+        })
+    }
+
+    fn instance() -> Value {
+        json!([{"x": 2.5, "y": 1.3}, {"x": 1, "z": 6.7}])
+    }
+
+    #[test_case(&json!([{"x": 1, "y": 1}, {"x": 2, "y": 2}, {"x": 3, "y": 3}]), true)]
+    #[test_case(&json!([{"x": 1}]), false)]
+    fn flag(instance: &Value, expected: bool) {
+        let schema = schema();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let output = serde_json::to_value(compiled.apply(instance).flag()).unwrap();
+        assert_eq!(output, json!({ "valid": expected }));
+    }
+
+    #[test]
+    fn basic() {
+        let schema = schema();
+        let instance = instance();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let output = serde_json::to_value(compiled.apply(&instance).basic()).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "valid": false,
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/items/$ref/additionalProperties",
+                        "absoluteKeywordLocation": "https://example.com/polygon#/definitions/point/additionalProperties",
+                        "instanceLocation": "/1/z",
+                        "error": "False schema does not allow '\"z\"'",
+                        "kind": "FalseSchema"
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/items/$ref/required",
+                        "absoluteKeywordLocation": "https://example.com/polygon#/definitions/point/required",
+                        "instanceLocation": "/1",
+                        "error": "'y' is a required property",
+                        "kind": {"Required": {"property": "y"}}
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/minItems",
+                        "absoluteKeywordLocation": "https://example.com/polygon#/minItems",
+                        "instanceLocation": "",
+                        "error": "[{\"x\":2.5,\"y\":1.3},{\"x\":1,\"z\":6.7}] has less than 3 items",
+                        "kind": {"MinItems": {"limit": 3}}
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn basic_valid() {
+        let schema = json!({"minItems": 1});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let output = serde_json::to_value(compiled.apply(&json!([1])).basic()).unwrap();
+        assert_eq!(output, json!({"valid": true}));
+    }
+
+    #[test]
+    fn detailed() {
+        let schema = schema();
+        let instance = instance();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let output = serde_json::to_value(compiled.apply(&instance).detailed()).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "valid": false,
+                "keywordLocation": "",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/items/$ref",
+                        "absoluteKeywordLocation": "https://example.com/polygon#/definitions/point",
+                        "instanceLocation": "/1",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/items/$ref/additionalProperties",
+                                "absoluteKeywordLocation": "https://example.com/polygon#/definitions/point/additionalProperties",
+                                "instanceLocation": "/1/z",
+                                "error": "False schema does not allow '\"z\"'",
+                                "kind": "FalseSchema"
+                            },
+                            {
+                                "valid": false,
+                                "keywordLocation": "/items/$ref/required",
+                                "absoluteKeywordLocation": "https://example.com/polygon#/definitions/point/required",
+                                "instanceLocation": "/1",
+                                "error": "'y' is a required property",
+                                "kind": {"Required": {"property": "y"}}
+                            }
+                        ]
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/minItems",
+                        "absoluteKeywordLocation": "https://example.com/polygon#/minItems",
+                        "instanceLocation": "",
+                        "error": "[{\"x\":2.5,\"y\":1.3},{\"x\":1,\"z\":6.7}] has less than 3 items",
+                        "kind": {"MinItems": {"limit": 3}}
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn verbose() {
+        let schema = json!({"properties": {"foo": {"items": {"type": "string"}}}});
+        let instance = json!({"foo": [1]});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let output = serde_json::to_value(compiled.apply(&instance).verbose()).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "valid": false,
+                "keywordLocation": "",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/foo",
+                        "absoluteKeywordLocation": "json-schema:///#/properties/foo",
+                        "instanceLocation": "/foo",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/properties/foo/items",
+                                "absoluteKeywordLocation": "json-schema:///#/properties/foo/items",
+                                "instanceLocation": "/foo/0",
+                                "errors": [
+                                    {
+                                        "valid": false,
+                                        "keywordLocation": "/properties/foo/items/type",
+                                        "absoluteKeywordLocation": "json-schema:///#/properties/foo/items/type",
+                                        "instanceLocation": "/foo/0",
+                                        "error": "'1' is not of type 'string'",
+                                        "kind": {"Type": {"kind": {"Single": "string"}}}
+                                    }
+                                ]
+                            }
+                        ]
+                    }
+                ]
+            })
+        );
+        // The same hierarchy is condensed in the `Detailed` format
+        let output = serde_json::to_value(compiled.apply(&instance).detailed()).unwrap();
+        assert_eq!(
+            output["errors"][0]["keywordLocation"],
+            json!("/properties/foo/items/type")
+        );
+    }
+
+    fn one_of() -> (Value, Value) {
+        (
+            json!({"properties": {"a": {"oneOf": [{"minLength": 3}, {"type": "integer"}]}}}),
+            json!({"a": "x"}),
+        )
+    }
+
+    #[test]
+    fn basic_one_of() {
+        let (schema, instance) = one_of();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let output = serde_json::to_value(compiled.apply(&instance).basic()).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "valid": false,
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/a/oneOf",
+                        "absoluteKeywordLocation": "json-schema:///#/properties/a/oneOf",
+                        "instanceLocation": "/a",
+                        "error": "'\"x\"' is not valid under any of the given schemas",
+                        "kind": {"OneOfNotValid": {}}
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/a/oneOf/0/minLength",
+                        "absoluteKeywordLocation": "json-schema:///#/properties/a/oneOf/0/minLength",
+                        "instanceLocation": "/a",
+                        "error": "'\"x\"' is shorter than 3 characters",
+                        "kind": {"MinLength": {"limit": 3}}
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/a/oneOf/1/type",
+                        "absoluteKeywordLocation": "json-schema:///#/properties/a/oneOf/1/type",
+                        "instanceLocation": "/a",
+                        "error": "'\"x\"' is not of type 'integer'",
+                        "kind": {"Type": {"kind": {"Single": "integer"}}}
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn verbose_one_of() {
+        let (schema, instance) = one_of();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let output = serde_json::to_value(compiled.apply(&instance).verbose()).unwrap();
+        let one_of = &output["errors"][0]["errors"][0];
+        assert_eq!(one_of["keywordLocation"], json!("/properties/a/oneOf"));
+        assert_eq!(one_of["kind"], json!({"OneOfNotValid": {}}));
+        let branches: Vec<_> = one_of["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|branch| {
+                (
+                    branch["keywordLocation"].clone(),
+                    branch["errors"][0]["keywordLocation"].clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            branches,
+            vec![
+                (
+                    json!("/properties/a/oneOf/0"),
+                    json!("/properties/a/oneOf/0/minLength")
+                ),
+                (
+                    json!("/properties/a/oneOf/1"),
+                    json!("/properties/a/oneOf/1/type")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn detailed_one_of() {
+        let (schema, instance) = one_of();
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let output = serde_json::to_value(compiled.apply(&instance).detailed()).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "valid": false,
+                "keywordLocation": "",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/properties/a/oneOf",
+                        "absoluteKeywordLocation": "json-schema:///#/properties/a/oneOf",
+                        "instanceLocation": "/a",
+                        "error": "'\"x\"' is not valid under any of the given schemas",
+                        "kind": {"OneOfNotValid": {}},
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/properties/a/oneOf/0/minLength",
+                                "absoluteKeywordLocation": "json-schema:///#/properties/a/oneOf/0/minLength",
+                                "instanceLocation": "/a",
+                                "error": "'\"x\"' is shorter than 3 characters",
+                                "kind": {"MinLength": {"limit": 3}}
+                            },
+                            {
+                                "valid": false,
+                                "keywordLocation": "/properties/a/oneOf/1/type",
+                                "absoluteKeywordLocation": "json-schema:///#/properties/a/oneOf/1/type",
+                                "instanceLocation": "/a",
+                                "error": "'\"x\"' is not of type 'integer'",
+                                "kind": {"Type": {"kind": {"Single": "integer"}}}
+                            }
+                        ]
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn hierarchy_valid() {
+        let schema = json!({"minItems": 1});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let output = serde_json::to_value(compiled.apply(&json!([1])).verbose()).unwrap();
+        assert_eq!(
+            output,
+            json!({"valid": true, "keywordLocation": "", "instanceLocation": ""})
+        );
+    }
+
+    fn annotations(schema: &Value, instance: &Value) -> Vec<(String, String, Value)> {
+        let compiled = JSONSchema::options()
+            .should_collect_annotations(true)
+            .compile(schema)
+            .unwrap();
+        compiled
+            .apply(instance)
+            .annotations()
+            .annotations
+            .into_iter()
+            .map(|annotation| {
+                (
+                    annotation.instance_location.to_string(),
+                    annotation.keyword().to_string(),
+                    annotation.annotation,
+                )
+            })
+            .collect()
+    }
+
+    fn annotation(location: &str, keyword: &str, value: Value) -> (String, String, Value) {
+        (location.to_string(), keyword.to_string(), value)
+    }
+
+    #[test]
+    fn annotations_output() {
+        let schema = json!({
+            "$id": "https://example.com/person",
+            "title": "Person",
+            "properties": {
+                "name": {"$ref": "#/definitions/name"},
+                "tags": {"items": {"description": "Tag", "examples": ["admin"]}}
+            },
+            "definitions": {"name": {"type": "string", "default": "Anonymous"}}
+        });
+        let compiled = JSONSchema::options()
+            .should_collect_annotations(true)
+            .compile(&schema)
+            .unwrap();
+        let instance = json!({"name": "Alice", "tags": ["a"]});
+        let output = serde_json::to_value(compiled.apply(&instance).annotations()).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "valid": true,
+                "annotations": [
+                    {
+                        "keywordLocation": "/properties/name/$ref/default",
+                        "absoluteKeywordLocation": "https://example.com/person#/definitions/name/default",
+                        "instanceLocation": "/name",
+                        "annotation": "Anonymous"
+                    },
+                    {
+                        "keywordLocation": "/properties/tags/items/description",
+                        "absoluteKeywordLocation": "https://example.com/person#/properties/tags/items/description",
+                        "instanceLocation": "/tags/0",
+                        "annotation": "Tag"
+                    },
+                    {
+                        "keywordLocation": "/properties/tags/items/examples",
+                        "absoluteKeywordLocation": "https://example.com/person#/properties/tags/items/examples",
+                        "instanceLocation": "/tags/0",
+                        "annotation": ["admin"]
+                    },
+                    {
+                        "keywordLocation": "/title",
+                        "absoluteKeywordLocation": "https://example.com/person#/title",
+                        "instanceLocation": "",
+                        "annotation": "Person"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn annotations_disabled_by_default() {
+        let schema = json!({"title": "Person", "properties": {"name": {"title": "Name"}}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let output = compiled.apply(&json!({"name": "Alice"})).annotations();
+        assert!(output.valid);
+        assert!(output.annotations.is_empty());
+    }
+
+    // Validity is derived from subschemas while collecting annotations
+    #[test_case(&json!({"contains": {"minimum": 5}}), &json!([1, 6]), true)]
+    #[test_case(&json!({"contains": {"minimum": 5}}), &json!([1, 2]), false)]
+    #[test_case(&json!({"contains": {"minimum": 5}, "maxContains": 1}), &json!([6, 7]), false)]
+    #[test_case(&json!({"contains": {"minimum": 5}, "minContains": 0}), &json!([]), true)]
+    #[test_case(&json!({"oneOf": [{"type": "integer"}, {"minimum": 0}]}), &json!(1), false)]
+    #[test_case(&json!({"oneOf": [{"type": "integer"}, {"minimum": 0}]}), &json!(-1), true)]
+    #[test_case(&json!({"anyOf": [{"type": "integer"}, {"type": "null"}]}), &json!("1"), false)]
+    #[test_case(&json!({"if": {"type": "integer"}, "then": {"minimum": 0}}), &json!(-1), false)]
+    #[test_case(&json!({"if": {"type": "integer"}, "else": {"minLength": 2}}), &json!("a"), false)]
+    #[test_case(&json!({"if": {"type": "integer"}}), &json!("a"), true)]
+    #[test_case(&json!({"properties": {"a": {}}, "additionalProperties": false}), &json!({"b": 1}), false)]
+    #[test_case(&json!({"patternProperties": {"^a": {}}, "additionalProperties": false}), &json!({"a": 1}), true)]
+    #[test_case(&json!({"unevaluatedProperties": {"type": "integer"}}), &json!({"a": "1"}), false)]
+    #[test_case(&json!({"items": {"items": {"not": {"type": "null"}}}}), &json!([[1], [null]]), false)]
+    #[test_case(&json!({"$ref": "#/$defs/a", "$defs": {"a": {"minimum": 2}}}), &json!(1), false)]
+    fn annotations_validity(schema: &Value, instance: &Value, expected: bool) {
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft202012)
+            .should_collect_annotations(true)
+            .compile(schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.apply(instance).annotations().valid, expected);
+    }
+
+    #[test_case(
+        &json!({"anyOf": [{"type": "string", "title": "String"}, {"type": "integer", "title": "Integer"}]}),
+        &json!(1),
+        &[annotation("", "title", json!("Integer"))];
+        "only valid anyOf branches"
+    )]
+    #[test_case(
+        &json!({"oneOf": [{"type": "string", "title": "String"}, {"type": "integer", "title": "Integer"}]}),
+        &json!("a"),
+        &[annotation("", "title", json!("String"))];
+        "only valid oneOf branches"
+    )]
+    #[test_case(
+        &json!({"if": {"required": ["card"], "title": "Card"}, "then": {"description": "Paid by card"}, "else": {"description": "Paid in cash"}}),
+        &json!({"card": 1}),
+        &[annotation("", "title", json!("Card")), annotation("", "description", json!("Paid by card"))];
+        "if then"
+    )]
+    #[test_case(
+        &json!({"if": {"required": ["card"], "title": "Card"}, "then": {"description": "Paid by card"}, "else": {"description": "Paid in cash"}}),
+        &json!({}),
+        &[annotation("", "description", json!("Paid in cash"))];
+        "if else"
+    )]
+    #[test_case(
+        &json!({"not": {"type": "string", "title": "String"}}),
+        &json!(1),
+        &[];
+        "not"
+    )]
+    #[test_case(
+        &json!({"contains": {"type": "integer", "title": "Integer"}}),
+        &json!(["a", 1]),
+        &[annotation("/1", "title", json!("Integer"))];
+        "contains"
+    )]
+    #[test_case(
+        &json!({
+            "properties": {"a": {"title": "A"}},
+            "patternProperties": {"^x-": {"title": "Extension"}},
+            "additionalProperties": {"title": "Other"}
+        }),
+        &json!({"a": 1, "x-b": 2, "c": 3}),
+        &[annotation("/a", "title", json!("A")), annotation("/c", "title", json!("Other")), annotation("/x-b", "title", json!("Extension"))];
+        "additional properties"
+    )]
+    #[test_case(
+        &json!({"properties": {"name": {"type": "string", "title": "Name"}}, "required": ["email"]}),
+        &json!({"name": 1}),
+        &[];
+        "invalid instance"
+    )]
+    #[test_case(
+        &json!({"properties": {"name": {"type": "string", "title": "Name"}}}),
+        &json!({"name": 1}),
+        &[];
+        "invalid property"
+    )]
+    #[test_case(
+        &json!({"if": {"properties": {"card": {"type": "string", "title": "Card"}}, "title": "Checked"}, "else": {"title": "Cash"}}),
+        &json!({"card": 1}),
+        &[annotation("", "title", json!("Cash"))];
+        "invalid condition"
+    )]
+    fn annotations_apply(schema: &Value, instance: &Value, expected: &[(String, String, Value)]) {
+        assert_eq!(annotations(schema, instance), expected);
+    }
+
+    #[test]
+    fn annotations_at() {
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2019-09/schema",
+            "properties": {
+                "id": {"readOnly": true, "title": "ID"},
+                "password": {"writeOnly": true, "deprecated": true}
+            }
+        });
+        let compiled = JSONSchema::options()
+            .should_collect_annotations(true)
+            .compile(&schema)
+            .unwrap();
+        let instance = json!({"id": 1, "password": "secret"});
+        let output = compiled.apply(&instance).annotations();
+        let keywords: Vec<_> = output
+            .at("/password")
+            .map(|annotation| annotation.keyword())
+            .collect();
+        assert_eq!(keywords, vec!["deprecated", "writeOnly"]);
+        assert_eq!(output.at("/id").count(), 2);
+        assert_eq!(output.at("").count(), 0);
+    }
+}
@@ -0,0 +1,230 @@
+//! Facilities for working with paths within schemas or validated instances.
+use serde::{Serialize, Serializer};
+use std::fmt;
+use url::Url;
+
+/// JSON Pointer as a wrapper around individual path components.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JSONPointer(Vec<PathChunk>);
+
+impl JSONPointer {
+    /// JSON pointer as a vector of strings. Each component is casted to `String`.
+    /// Consumes `JSONPointer`.
+    #[must_use]
+    pub fn into_vec(self) -> Vec<String> {
+        self.0
+            .into_iter()
+            .map(|item| match item {
+                PathChunk::Name(value) => value,
+                PathChunk::Index(idx) => idx.to_string(),
+            })
+            .collect()
+    }
+
+    /// Individual path components.
+    #[must_use]
+    pub fn chunks(&self) -> &[PathChunk] {
+        &self.0
+    }
+
+    /// Whether the pointer refers to the whole document.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// A new pointer with `chunk` appended to the current one.
+    pub(crate) fn clone_with(&self, chunk: impl Into<PathChunk>) -> Self {
+        let mut new = self.clone();
+        new.0.push(chunk.into());
+        new
+    }
+
+    /// A new pointer with all chunks of `other` appended to the current one.
+    pub(crate) fn extend_with(&self, other: &[PathChunk]) -> Self {
+        let mut new = self.clone();
+        new.0.extend_from_slice(other);
+        new
+    }
+
+    /// Absolute location of this pointer within the document identified by `base`.
+    /// If `base` already contains a JSON Pointer fragment, this pointer is appended to it.
+    pub(crate) fn to_url(&self, base: &Url) -> Url {
+        let mut url = base.clone();
+        let fragment = format!("{}{}", base.fragment().unwrap_or(""), self);
+        url.set_fragment(Some(&fragment));
+        url
+    }
+}
+
+impl fmt::Display for JSONPointer {
+    /// Renders the path as RFC 6901 JSON Pointer, e.g. `/spec/containers/0/name`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in &self.0 {
+            f.write_str("/")?;
+            match chunk {
+                PathChunk::Name(value) => {
+                    for ch in value.chars() {
+                        match ch {
+                            '~' => f.write_str("~0")?,
+                            '/' => f.write_str("~1")?,
+                            _ => write!(f, "{}", ch)?,
+                        }
+                    }
+                }
+                PathChunk::Index(idx) => write!(f, "{}", idx)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serialized as a JSON Pointer string.
+impl Serialize for JSONPointer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// A key within a JSON object or an index within a JSON array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathChunk {
+    /// Property name within a JSON object.
+    Name(String),
+    /// Index within a JSON array.
+    Index(usize),
+}
+
+impl From<String> for PathChunk {
+    #[inline]
+    fn from(value: String) -> Self {
+        PathChunk::Name(value)
+    }
+}
+
+impl From<&str> for PathChunk {
+    #[inline]
+    fn from(value: &str) -> Self {
+        PathChunk::Name(value.to_string())
+    }
+}
+
+impl From<usize> for PathChunk {
+    #[inline]
+    fn from(value: usize) -> Self {
+        PathChunk::Index(value)
+    }
+}
+
+/// A node in a linked list representing the current location in the validated instance.
+/// Validators push new chunks onto it while descending and the list is converted to
+/// `JSONPointer` only when an error is actually created.
+#[derive(Debug)]
+pub(crate) struct InstancePath<'a> {
+    pub(crate) chunk: Option<PathChunk>,
+    pub(crate) parent: Option<&'a InstancePath<'a>>,
+}
+
+impl<'a> InstancePath<'a> {
+    pub(crate) const fn new() -> Self {
+        InstancePath {
+            chunk: None,
+            parent: None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn push(&'a self, chunk: impl Into<PathChunk>) -> Self {
+        InstancePath {
+            chunk: Some(chunk.into()),
+            parent: Some(self),
+        }
+    }
+
+    pub(crate) fn to_vec(&'a self) -> Vec<PathChunk> {
+        // The path capacity should be the average depth so we avoid extra allocations
+        let mut result = Vec::with_capacity(6);
+        let mut current = self;
+        if let Some(chunk) = &current.chunk {
+            result.push(chunk.clone())
+        }
+        while let Some(next) = current.parent {
+            current = next;
+            if let Some(chunk) = &current.chunk {
+                result.push(chunk.clone())
+            }
+        }
+        result.reverse();
+        result
+    }
+}
+
+impl From<&InstancePath<'_>> for JSONPointer {
+    #[inline]
+    fn from(path: &InstancePath<'_>) -> Self {
+        JSONPointer(path.to_vec())
+    }
+}
+
+impl From<Vec<PathChunk>> for JSONPointer {
+    #[inline]
+    fn from(chunks: Vec<PathChunk>) -> Self {
+        JSONPointer(chunks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InstancePath, JSONPointer, PathChunk};
+    use url::Url;
+
+    #[test]
+    fn instance_path_to_pointer() {
+        let root = InstancePath::new();
+        let spec = root.push("spec");
+        let containers = spec.push("containers");
+        let first = containers.push(0);
+        let pointer = JSONPointer::from(&first);
+        assert_eq!(
+            pointer.chunks(),
+            &[
+                PathChunk::Name("spec".to_string()),
+                PathChunk::Name("containers".to_string()),
+                PathChunk::Index(0)
+            ]
+        );
+        assert_eq!(pointer.to_string(), "/spec/containers/0");
+        assert_eq!(pointer.into_vec(), vec!["spec", "containers", "0"]);
+    }
+
+    #[test]
+    fn root_pointer() {
+        let pointer = JSONPointer::from(&InstancePath::new());
+        assert!(pointer.is_empty());
+        assert_eq!(pointer.to_string(), "");
+    }
+
+    #[test]
+    fn escaping() {
+        let root = InstancePath::new();
+        let path = root.push("a/b~c");
+        assert_eq!(JSONPointer::from(&path).to_string(), "/a~1b~0c");
+    }
+
+    #[test]
+    fn pointer_to_url() {
+        let pointer = JSONPointer::default()
+            .clone_with("properties")
+            .clone_with("foo");
+        let base = Url::parse("http://example.com/schema.json").unwrap();
+        assert_eq!(
+            pointer.to_url(&base).as_str(),
+            "http://example.com/schema.json#/properties/foo"
+        );
+        let base = Url::parse("http://example.com/schema.json#/definitions/a").unwrap();
+        assert_eq!(
+            pointer.to_url(&base).as_str(),
+            "http://example.com/schema.json#/definitions/a/properties/foo"
+        );
+    }
+}
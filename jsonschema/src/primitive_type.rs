@@ -1,11 +1,13 @@
 //! Primitive types for property type validators
 
-use serde_json::Value;
+use serde::{ser::SerializeSeq, Serialize, Serializer};
+use serde_json::{Number, Value};
 use std::{convert::TryFrom, fmt, ops::BitOrAssign};
 
 /// For faster error handling in "type" keyword validator we have this enum, to match
 /// with it instead of a string.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
 #[allow(missing_docs)]
 pub enum PrimitiveType {
     Array,
@@ -31,6 +33,51 @@ impl fmt::Display for PrimitiveType {
     }
 }
 
+impl PrimitiveType {
+    /// Convert a string to a value of this type, e.g. `"42"` or `"4.2e1"` to `42` for `integer`.
+    /// Only scalar types are supported and an empty string is converted to `null`.
+    pub(crate) fn coerce(self, value: &str) -> Option<Value> {
+        match self {
+            PrimitiveType::Boolean => match value {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            PrimitiveType::Integer => parse_integer(value),
+            PrimitiveType::Null if value.is_empty() => Some(Value::Null),
+            PrimitiveType::Number => parse_integer(value).or_else(|| {
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .map(Value::Number)
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn parse_integer(value: &str) -> Option<Value> {
+    if let Ok(integer) = value.parse::<u64>() {
+        Some(integer.into())
+    } else if let Ok(integer) = value.parse::<i64>() {
+        Some(integer.into())
+    } else {
+        // Integers in other notations, e.g. `1e2` or `100.0`
+        match value.parse::<f64>() {
+            #[allow(clippy::cast_possible_truncation)]
+            Ok(number)
+                if number.fract() == 0.0
+                    && number >= i64::MIN as f64
+                    && number < i64::MAX as f64 =>
+            {
+                Some((number as i64).into())
+            }
+            _ => None,
+        }
+    }
+}
+
 impl TryFrom<&str> for PrimitiveType {
     type Error = ();
 
@@ -126,6 +173,17 @@ impl IntoIterator for PrimitiveTypesBitMap {
         }
     }
 }
+/// Serialized as a list of type names, e.g. `["integer", "string"]`.
+impl Serialize for PrimitiveTypesBitMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        for primitive_type in *self {
+            seq.serialize_element(&primitive_type)?;
+        }
+        seq.end()
+    }
+}
+
 #[cfg(test)]
 impl From<Vec<PrimitiveType>> for PrimitiveTypesBitMap {
     fn from(value: Vec<PrimitiveType>) -> Self {
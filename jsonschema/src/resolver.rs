@@ -1,86 +1,279 @@
 //! Reference resolver. Implements logic, required by `$ref` keyword.
-//! Is able to load documents from remote locations via HTTP(S).
+//! Is able to load documents from remote locations via HTTP(S) or via a custom `SchemaResolver`.
+#[cfg(any(feature = "reqwest", test))]
+use crate::cache::{DiskCache, Lookup, Response};
+#[cfg(feature = "reqwest")]
+use crate::schemas::Subschemas;
 use crate::{
-    compilation::{DEFAULT_ROOT_URL, DEFAULT_SCOPE},
+    compilation::{context::resource_of, DEFAULT_ROOT_URL},
     error::{CompilationError, ValidationError},
-    schemas::{id_of, Draft},
+    schemas::{anchor_of, dynamic_anchor_of, id_of, Draft},
 };
 use ahash::AHashMap;
 use parking_lot::RwLock;
 use serde_json::Value;
-use std::borrow::Cow;
+use std::{borrow::Cow, error, fmt, fs::File, io::BufReader, ops::Deref, sync::Arc};
 use url::Url;
 
-#[derive(Debug)]
+/// An error returned by `SchemaResolver`.
+pub type SchemaResolverError = Box<dyn error::Error + Send + Sync>;
+
+/// Loads documents referenced via `$ref` that are not a part of the schema being compiled and
+/// are not added via `CompilationOptions::with_document`.
+///
+/// It replaces the built-in loading over HTTP(S), so documents may come from any source and
+/// use any URL scheme. Loaded documents are cached for the lifetime of the compiled schema.
+pub trait SchemaResolver: Send + Sync {
+    /// Load the document located at `url`. The URL doesn't contain a fragment.
+    fn resolve(&self, url: &Url) -> Result<Value, SchemaResolverError>;
+}
+
+/// A schema found by the resolver.
+///
+/// Documents that are not borrowed from the caller are shared via `Arc`, therefore resolving
+/// references to them doesn't copy anything.
+#[derive(Debug, Clone)]
+pub(crate) enum Resolved<'a> {
+    /// A subschema of the borrowed root schema.
+    Borrowed(&'a Value),
+    /// A subschema of an owned root schema, a stored or a loaded document, located by
+    /// a JSON pointer.
+    Shared(Arc<Value>, String),
+}
+
+impl Deref for Resolved<'_> {
+    type Target = Value;
+
+    fn deref(&self) -> &Value {
+        match self {
+            Resolved::Borrowed(schema) => schema,
+            Resolved::Shared(document, pointer) => document
+                .pointer(pointer)
+                .expect("The pointer is checked during resolving"),
+        }
+    }
+}
+
+/// Named sub-schemas of stored documents together with the base URLs that enclose them.
+/// Sub-schemas are located by JSON pointers within their documents.
+type StoredSchemas = AHashMap<String, (Url, Arc<Value>, String)>;
+
 pub(crate) struct Resolver<'a> {
     // canonical_id: sub-schema mapping to resolve documents by their ID
     // canonical_id is composed with the root document id
     // (if not specified, then `DEFAULT_ROOT_URL` is used for this purpose)
-    schemas: AHashMap<String, &'a Value>,
-    store: RwLock<AHashMap<String, Value>>,
+    // Each sub-schema is stored together with the base URL that encloses it
+    schemas: AHashMap<String, (Url, Resolved<'a>)>,
+    store: RwLock<AHashMap<String, Arc<Value>>>,
+    // The same mapping for sub-schemas of stored and loaded documents
+    store_schemas: RwLock<StoredSchemas>,
+    resolver: Option<Arc<dyn SchemaResolver>>,
+    // Persists documents loaded via HTTP(S)
+    #[cfg(any(feature = "reqwest", test))]
+    cache: Option<DiskCache>,
+    // Whether `file://` references may be loaded from the file system
+    allow_files: bool,
+    // The root document
+    root: Resolved<'a>,
+    // The base URL of the root document
+    scope: Url,
+    draft: Draft,
+}
+
+impl fmt::Debug for Resolver<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Resolver");
+        debug
+            .field("schemas", &self.schemas)
+            .field("root", &self.root)
+            .field("store", &self.store)
+            .field("store_schemas", &self.store_schemas)
+            .field("custom_resolver", &self.resolver.is_some())
+            .field("allow_files", &self.allow_files)
+            .field("scope", &self.scope)
+            .field("draft", &self.draft);
+        #[cfg(any(feature = "reqwest", test))]
+        debug.field("cache", &self.cache);
+        debug.finish()
+    }
 }
 
 impl<'a> Resolver<'a> {
     pub(crate) fn new(
         draft: Draft,
         scope: &Url,
-        schema: &'a Value,
-        store: AHashMap<String, Value>,
+        root: Resolved<'a>,
+        store: AHashMap<String, Arc<Value>>,
+        resolver: Option<Arc<dyn SchemaResolver>>,
+        allow_files: bool,
     ) -> Result<Resolver<'a>, CompilationError> {
+        let scope = resource_of(scope);
         let mut schemas = AHashMap::new();
         // traverse the schema and store all named ones under their canonical ids
-        find_schemas(draft, schema, scope, &mut |id, schema| {
-            schemas.insert(id, schema);
-            None
-        })?;
-        Ok(Resolver {
+        // The first schema with the given id wins
+        match &root {
+            Resolved::Borrowed(schema) => find_schemas(
+                draft,
+                schema,
+                &scope,
+                &mut vec![],
+                &mut |id, base_url, schema, _| {
+                    schemas
+                        .entry(id)
+                        .or_insert_with(|| (base_url.clone(), Resolved::Borrowed(schema)));
+                },
+            )?,
+            Resolved::Shared(document, _) => find_schemas(
+                draft,
+                document,
+                &scope,
+                &mut vec![],
+                &mut |id, base_url, _, path| {
+                    schemas.entry(id).or_insert_with(|| {
+                        (
+                            base_url.clone(),
+                            Resolved::Shared(Arc::clone(document), to_pointer(path)),
+                        )
+                    });
+                },
+            )?,
+        }
+        let resolver = Resolver {
             schemas,
-            store: RwLock::new(store),
-        })
+            root,
+            store: RwLock::new(AHashMap::new()),
+            store_schemas: RwLock::new(AHashMap::new()),
+            resolver,
+            #[cfg(any(feature = "reqwest", test))]
+            cache: None,
+            allow_files,
+            scope,
+            draft,
+        };
+        for (id, document) in store {
+            resolver.add_document(id, document);
+        }
+        Ok(resolver)
+    }
+
+    /// Persist documents loaded via HTTP(S) in `cache`.
+    #[cfg(any(feature = "reqwest", test))]
+    pub(crate) fn with_cache(mut self, cache: Option<DiskCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Add a document to the store and index its named sub-schemas.
+    fn add_document(&self, id: String, document: Arc<Value>) {
+        if let Ok(base_url) = Url::parse(&id) {
+            let mut store_schemas = self.store_schemas.write();
+            // Documents with invalid ids are still available by their location
+            let _ = find_schemas(
+                self.draft,
+                &document,
+                &base_url,
+                &mut vec![],
+                &mut |id, base_url, _, path| {
+                    store_schemas.entry(id).or_insert_with(|| {
+                        (base_url.clone(), Arc::clone(&document), to_pointer(path))
+                    });
+                },
+            );
+        }
+        self.store.write().insert(id, document);
     }
 
     /// Load a document for the given `url`.
     /// It may be:
-    ///   - the root document (`DEFAULT_ROOT_URL`) case;
+    ///   - the root document (`DEFAULT_ROOT_URL` or the base URL of the root schema);
     ///   - named subschema that is stored in `self.schemas`;
+    ///   - document loaded by a custom `SchemaResolver`;
     ///   - document from a remote location;
-    fn resolve_url(&self, url: &Url, schema: &'a Value) -> Result<Cow<'a, Value>, ValidationError> {
+    fn resolve_url(&self, url: &Url) -> Result<Resolved<'a>, ValidationError> {
         match url.as_str() {
-            DEFAULT_ROOT_URL => Ok(Cow::Borrowed(schema)),
+            DEFAULT_ROOT_URL => Ok(self.root.clone()),
+            _ if url == &self.scope => Ok(self.root.clone()),
             url_str => {
                 if let Some(cached) = self.store.read().get(url_str) {
-                    return Ok(Cow::Owned(cached.clone()));
+                    return Ok(Resolved::Shared(Arc::clone(cached), String::new()));
+                }
+                if let Some((_, value)) = self.schemas.get(url_str) {
+                    return Ok(value.clone());
+                }
+                if let Some((_, document, pointer)) = self.store_schemas.read().get(url_str) {
+                    return Ok(Resolved::Shared(Arc::clone(document), pointer.clone()));
                 }
-                match self.schemas.get(url_str) {
-                    Some(value) => Ok(Cow::Borrowed(value)),
+                let document = match &self.resolver {
+                    Some(resolver) => resolver
+                        .resolve(url)
+                        .map_err(|error| ValidationError::resolver(url.clone(), error))?,
                     None => match url.scheme() {
                         "http" | "https" => {
                             #[cfg(any(feature = "reqwest", test))]
                             {
-                                let response = reqwest::blocking::get(url.as_str())?;
-                                let document: Value = response.json()?;
-                                self.store
-                                    .write()
-                                    .insert(url_str.to_string(), document.clone());
-                                Ok(Cow::Owned(document))
+                                self.fetch(url)?
                             }
                             #[cfg(not(any(feature = "reqwest", test)))]
                             panic!("trying to resolve an http(s), but reqwest support has not been included");
                         }
-                        http_scheme => Err(ValidationError::unknown_reference_scheme(
-                            http_scheme.to_owned(),
-                        )),
+                        "file" if self.allow_files => {
+                            let path = url.to_file_path().map_err(|_| {
+                                ValidationError::invalid_reference(url_str.to_string())
+                            })?;
+                            let file = File::open(path)?;
+                            serde_json::from_reader(BufReader::new(file))?
+                        }
+                        // Including `file` if reading files is not allowed
+                        scheme => {
+                            return Err(ValidationError::unknown_reference_scheme(
+                                scheme.to_owned(),
+                            ))
+                        }
                     },
-                }
+                };
+                let document = Arc::new(document);
+                self.add_document(url_str.to_string(), Arc::clone(&document));
+                Ok(Resolved::Shared(document, String::new()))
             }
         }
     }
+    /// Load a document via HTTP(S), using the cache if it is set.
+    #[cfg(any(feature = "reqwest", test))]
+    fn fetch(&self, url: &Url) -> Result<Value, ValidationError<'static>> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return Ok(reqwest::blocking::get(url.as_str())?.json()?),
+        };
+        let load = || {
+            let revalidation = match cache.lookup(url)? {
+                Lookup::Fresh(document) => return Ok(document),
+                Lookup::Request(revalidation) => revalidation,
+            };
+            let mut request = reqwest::blocking::Client::new().get(url.as_str());
+            for (name, value) in revalidation.headers() {
+                request = request.header(name, value);
+            }
+            let response = request
+                .send()
+                .and_then(reqwest::blocking::Response::error_for_status)
+                .and_then(|response| {
+                    if Response::is_modified(response.status()) {
+                        let headers = response.headers().clone();
+                        Ok(Response::modified(response.json()?, &headers))
+                    } else {
+                        Ok(Response::NotModified)
+                    }
+                });
+            revalidation.finish(response.map_err(Into::into))
+        };
+        load().map_err(|error| ValidationError::resolver(url.clone(), error))
+    }
+
     pub(crate) fn resolve_fragment(
         &self,
         draft: Draft,
         url: &Url,
-        schema: &'a Value,
-    ) -> Result<(Url, Cow<'a, Value>), ValidationError> {
+    ) -> Result<(Url, Resolved<'a>), ValidationError> {
         let mut resource = url.clone();
         resource.set_fragment(None);
         let fragment =
@@ -88,32 +281,65 @@ impl<'a> Resolver<'a> {
 
         // Location-independent identifiers are searched before trying to resolve by
         // fragment-less url
-        if let Some(x) = find_schemas(draft, schema, &DEFAULT_SCOPE, &mut |id, x| {
-            if id == url.as_str() {
-                Some(x)
-            } else {
-                None
-            }
-        })? {
-            return Ok((resource, Cow::Borrowed(x)));
+        if let Some((scope, x)) = self.schemas.get(url.as_str()) {
+            return Ok((scope.clone(), x.clone()));
         }
 
         // Each resolved document may be in a changed subfolder
         // They are tracked when JSON pointer is resolved and added to the resource
-        match self.resolve_url(&resource, schema)? {
-            Cow::Borrowed(document) => match pointer(draft, document, fragment.as_ref()) {
-                Some((folders, resolved)) => {
-                    Ok((join_folders(resource, &folders)?, Cow::Borrowed(resolved)))
-                }
+        match self.resolve_url(&resource)? {
+            Resolved::Borrowed(document) => match pointer(draft, document, fragment.as_ref()) {
+                Some((folders, resolved)) => Ok((
+                    join_folders(resource, &folders)?,
+                    Resolved::Borrowed(resolved),
+                )),
                 None => Err(ValidationError::invalid_reference(url.as_str().to_string())),
             },
-            Cow::Owned(document) => match pointer(draft, &document, fragment.as_ref()) {
-                Some((folders, x)) => {
-                    Ok((join_folders(resource, &folders)?, Cow::Owned(x.clone())))
+            Resolved::Shared(document, base_pointer) => {
+                // The base pointer is not empty if `resource` is a named sub-schema
+                let scope = document
+                    .pointer(&base_pointer)
+                    .and_then(|base| pointer(draft, base, fragment.as_ref()))
+                    .map(|(folders, _)| join_folders(resource, &folders));
+                match scope {
+                    Some(scope) => Ok((
+                        scope?,
+                        Resolved::Shared(
+                            Arc::clone(&document),
+                            format!("{}{}", base_pointer, fragment),
+                        ),
+                    )),
+                    // Plain name fragments in other documents
+                    None => match self.store_schemas.read().get(url.as_str()) {
+                        Some((scope, document, pointer)) => Ok((
+                            scope.clone(),
+                            Resolved::Shared(Arc::clone(document), pointer.clone()),
+                        )),
+                        None => Err(ValidationError::invalid_reference(url.as_str().to_string())),
+                    },
                 }
-                None => Err(ValidationError::invalid_reference(url.as_str().to_string())),
-            },
+            }
+        }
+    }
+
+    /// Find the outermost schema resource in `dynamic_scope` that contains a schema with
+    /// `"$dynamicAnchor": <name>`. It is the target of `$dynamicRef` in Draft 2020-12.
+    pub(crate) fn resolve_dynamic_anchor(
+        &self,
+        draft: Draft,
+        name: &str,
+        dynamic_scope: &[Url],
+    ) -> Option<(Url, Url, Resolved<'a>)> {
+        for resource in dynamic_scope {
+            let mut url = resource.clone();
+            url.set_fragment(Some(name));
+            if let Ok((scope, resolved)) = self.resolve_fragment(draft, &url) {
+                if dynamic_anchor_of(draft, &resolved) == Some(name) {
+                    return Some((url, scope, resolved));
+                }
+            }
         }
+        None
     }
 }
 
@@ -126,16 +352,29 @@ fn join_folders(mut resource: Url, folders: &[&str]) -> Result<Url, url::ParseEr
     Ok(resource)
 }
 
-/// Find all sub-schemas in the document and execute callback on each of them.
+/// Convert a path within a document to a JSON pointer.
+fn to_pointer(path: &[Cow<'_, str>]) -> String {
+    let mut pointer = String::new();
+    for chunk in path {
+        pointer.push('/');
+        pointer.push_str(&chunk.replace('~', "~0").replace('/', "~1"));
+    }
+    pointer
+}
+
+/// Find all identified sub-schemas in the document and execute callback on each of them.
+/// The callback receives the schema identifier, the base URL that encloses the schema,
+/// the schema itself and its location within the document.
 #[inline]
 pub(crate) fn find_schemas<'a, F>(
     draft: Draft,
     schema: &'a Value,
     base_url: &Url,
+    path: &mut Vec<Cow<'a, str>>,
     callback: &mut F,
-) -> Result<Option<&'a Value>, url::ParseError>
+) -> Result<(), url::ParseError>
 where
-    F: FnMut(String, &'a Value) -> Option<&'a Value>,
+    F: FnMut(String, &Url, &'a Value, &[Cow<'a, str>]),
 {
     match schema {
         Value::Object(item) => {
@@ -145,35 +384,107 @@ where
                 if let Some("") = new_url.fragment() {
                     new_url.set_fragment(None);
                 }
-                if let Some(x) = callback(new_url.to_string(), schema) {
-                    return Ok(Some(x));
-                }
-                for (_, subschema) in item {
-                    let result = find_schemas(draft, subschema, &new_url, callback)?;
-                    if result.is_some() {
-                        return Ok(result);
-                    }
+                callback(new_url.to_string(), base_url, schema, path);
+                find_anchor(draft, schema, &new_url, base_url, path, callback);
+                for (key, subschema) in item {
+                    path.push(Cow::Borrowed(key));
+                    find_schemas(draft, subschema, &new_url, path, callback)?;
+                    path.pop();
                 }
             } else {
-                for (_, subschema) in item {
-                    let result = find_schemas(draft, subschema, base_url, callback)?;
-                    if result.is_some() {
-                        return Ok(result);
-                    }
+                find_anchor(draft, schema, base_url, base_url, path, callback);
+                for (key, subschema) in item {
+                    path.push(Cow::Borrowed(key));
+                    find_schemas(draft, subschema, base_url, path, callback)?;
+                    path.pop();
                 }
             }
         }
         Value::Array(items) => {
-            for item in items {
-                let result = find_schemas(draft, item, base_url, callback)?;
-                if result.is_some() {
-                    return Ok(result);
-                }
+            for (idx, item) in items.iter().enumerate() {
+                path.push(Cow::Owned(idx.to_string()));
+                find_schemas(draft, item, base_url, path, callback)?;
+                path.pop();
             }
         }
         _ => {}
     }
-    Ok(None)
+    Ok(())
+}
+
+/// Find all references in the document and execute callback on each of them.
+/// The callback receives the reference resolved against the base URL that encloses it and
+/// its location within the document.
+/// Only keywords that contain subschemas in `draft` are traversed, therefore `$ref` inside
+/// e.g. `const` or `default` is not a reference.
+#[cfg(feature = "reqwest")]
+pub(crate) fn find_references<'a, F>(
+    draft: Draft,
+    schema: &'a Value,
+    base_url: &Url,
+    path: &mut Vec<Cow<'a, str>>,
+    callback: &mut F,
+) -> Result<(), url::ParseError>
+where
+    F: FnMut(Url, &[Cow<'a, str>]),
+{
+    if let Value::Object(item) = schema {
+        let base_url = match id_of(draft, schema) {
+            Some(id) => Cow::Owned(base_url.join(id)?),
+            None => Cow::Borrowed(base_url),
+        };
+        for (key, value) in item {
+            path.push(Cow::Borrowed(key));
+            match (draft.subschemas(key), value) {
+                (_, Value::String(reference)) if draft.is_reference(key) => {
+                    callback(base_url.join(reference)?, path)
+                }
+                (Some(Subschemas::Single), _) | (Some(Subschemas::SingleOrArray), _)
+                    if !value.is_array() =>
+                {
+                    find_references(draft, value, &base_url, path, callback)?
+                }
+                (Some(Subschemas::Array), Value::Array(items))
+                | (Some(Subschemas::SingleOrArray), Value::Array(items)) => {
+                    for (idx, item) in items.iter().enumerate() {
+                        path.push(Cow::Owned(idx.to_string()));
+                        find_references(draft, item, &base_url, path, callback)?;
+                        path.pop();
+                    }
+                }
+                (Some(Subschemas::Map), Value::Object(subschemas)) => {
+                    for (name, subschema) in subschemas {
+                        path.push(Cow::Borrowed(name));
+                        find_references(draft, subschema, &base_url, path, callback)?;
+                        path.pop();
+                    }
+                }
+                _ => {}
+            }
+            path.pop();
+        }
+    }
+    Ok(())
+}
+
+/// Execute callback on a schema with `$anchor`. It is identified by a plain name fragment
+/// within the closest base URL.
+#[inline]
+fn find_anchor<'a, F>(
+    draft: Draft,
+    schema: &'a Value,
+    resource_url: &Url,
+    base_url: &Url,
+    path: &[Cow<'a, str>],
+    callback: &mut F,
+) where
+    F: FnMut(String, &Url, &'a Value, &[Cow<'a, str>]),
+{
+    if let Some(anchor) = anchor_of(draft, schema) {
+        let mut url = resource_url.clone();
+        url.set_fragment(Some(anchor));
+        callback(url.to_string(), base_url, schema, path)
+    }
 }
 
 /// Based on `serde_json`, but tracks folders in the traversed documents.
@@ -228,15 +539,17 @@ mod tests {
     use super::*;
     use crate::JSONSchema;
     use serde_json::*;
-    use std::borrow::Cow;
+    use test_case::test_case;
     use url::Url;
 
     fn make_resolver(schema: &Value) -> Resolver {
         Resolver::new(
             Draft::Draft7,
             &Url::parse("json-schema:///").unwrap(),
-            schema,
+            Resolved::Borrowed(schema),
             AHashMap::new(),
+            None,
+            false,
         )
         .unwrap()
     }
@@ -263,8 +576,11 @@ mod tests {
         // Then in the resolver schema there should be only this schema
         assert_eq!(resolver.schemas.len(), 1);
         assert_eq!(
-            resolver.schemas.get("json-schema:///#foo"),
-            schema.pointer("/definitions/A").as_ref()
+            resolver
+                .schemas
+                .get("json-schema:///#foo")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("/definitions/A")
         );
     }
 
@@ -283,12 +599,18 @@ mod tests {
         // Then in the resolver schema there should be only these schemas
         assert_eq!(resolver.schemas.len(), 2);
         assert_eq!(
-            resolver.schemas.get("json-schema:///#foo"),
-            schema.pointer("/definitions/A/0").as_ref()
+            resolver
+                .schemas
+                .get("json-schema:///#foo")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("/definitions/A/0")
         );
         assert_eq!(
-            resolver.schemas.get("json-schema:///#bar"),
-            schema.pointer("/definitions/A/1").as_ref()
+            resolver
+                .schemas
+                .get("json-schema:///#bar")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("/definitions/A/1")
         );
     }
 
@@ -324,12 +646,18 @@ mod tests {
         // Then in the resolver schema there should be root & sub-schema
         assert_eq!(resolver.schemas.len(), 2);
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/tree"),
-            schema.pointer("").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/tree")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("")
         );
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/node"),
-            schema.pointer("/definitions/node").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/node")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("/definitions/node")
         );
     }
 
@@ -344,8 +672,11 @@ mod tests {
         let resolver = make_resolver(&schema);
         assert_eq!(resolver.schemas.len(), 1);
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/bar#foo"),
-            schema.pointer("/definitions/A").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/bar#foo")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("/definitions/A")
         );
     }
 
@@ -369,18 +700,25 @@ mod tests {
         let resolver = make_resolver(&schema);
         assert_eq!(resolver.schemas.len(), 3);
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/root"),
-            schema.pointer("").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/root")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("")
         );
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/nested.json"),
-            schema.pointer("/definitions/A").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/nested.json")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("/definitions/A")
         );
         assert_eq!(
             resolver
                 .schemas
-                .get("http://localhost:1234/nested.json#foo"),
-            schema.pointer("/definitions/A/definitions/B").as_ref()
+                .get("http://localhost:1234/nested.json#foo")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("/definitions/A/definitions/B")
         );
     }
 
@@ -396,12 +734,18 @@ mod tests {
         let resolver = make_resolver(&schema);
         assert_eq!(resolver.schemas.len(), 2);
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/"),
-            schema.pointer("").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("")
         );
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/folder/"),
-            schema.pointer("/items").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/folder/")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("/items")
         );
     }
 
@@ -426,12 +770,16 @@ mod tests {
         assert_eq!(
             resolver
                 .schemas
-                .get("http://localhost:1234/scope_change_defs1.json"),
-            schema.pointer("").as_ref()
+                .get("http://localhost:1234/scope_change_defs1.json")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("")
         );
         assert_eq!(
-            resolver.schemas.get("http://localhost:1234/folder/"),
-            schema.pointer("/definitions/baz").as_ref()
+            resolver
+                .schemas
+                .get("http://localhost:1234/folder/")
+                .map(|(_, schema)| &**schema),
+            schema.pointer("/definitions/baz")
         );
     }
 
@@ -447,9 +795,8 @@ mod tests {
         });
         let resolver = make_resolver(&schema);
         let url = Url::parse("json-schema:///#/definitions/a").unwrap();
-        if let (resource, Cow::Borrowed(resolved)) = resolver
-            .resolve_fragment(Draft::Draft7, &url, &schema)
-            .unwrap()
+        if let (resource, Resolved::Borrowed(resolved)) =
+            resolver.resolve_fragment(Draft::Draft7, &url).unwrap()
         {
             assert_eq!(resource, Url::parse("json-schema:///").unwrap());
             assert_eq!(resolved, schema.pointer("/definitions/a").unwrap());
@@ -478,4 +825,254 @@ mod tests {
             .schemas
             .contains_key("http://foo.com/schema.json"));
     }
+
+    struct InMemoryResolver {
+        documents: AHashMap<String, Value>,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl InMemoryResolver {
+        fn new() -> InMemoryResolver {
+            let mut documents = AHashMap::new();
+            documents.insert(
+                "db://schemas/person".to_string(),
+                json!({
+                    "properties": {"name": {"$ref": "db://schemas/name"}},
+                    "definitions": {"age": {"minimum": 0}}
+                }),
+            );
+            documents.insert("db://schemas/name".to_string(), json!({"type": "string"}));
+            InMemoryResolver {
+                documents,
+                calls: std::sync::atomic::AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl SchemaResolver for InMemoryResolver {
+        fn resolve(&self, url: &Url) -> std::result::Result<Value, SchemaResolverError> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.documents
+                .get(url.as_str())
+                .cloned()
+                .ok_or_else(|| format!("Document not found: {}", url).into())
+        }
+    }
+
+    impl SchemaResolver for Arc<InMemoryResolver> {
+        fn resolve(&self, url: &Url) -> std::result::Result<Value, SchemaResolverError> {
+            self.as_ref().resolve(url)
+        }
+    }
+
+    #[test_case(&json!({"name": "Alice"}), true)]
+    #[test_case(&json!({"name": 42}), false)]
+    #[test_case(&json!({"age": -1}), false)]
+    #[test_case(&json!({"age": 1}), true)]
+    fn custom_resolver(instance: &Value, expected: bool) {
+        let schema = json!({
+            "allOf": [{"$ref": "db://schemas/person"}],
+            "properties": {"age": {"$ref": "db://schemas/person#/definitions/age"}}
+        });
+        let compiled = JSONSchema::options()
+            .with_resolver(InMemoryResolver::new())
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn custom_resolver_caches_documents() {
+        let schema = json!({
+            "properties": {
+                "foo": {"$ref": "db://schemas/name"},
+                "bar": {"$ref": "db://schemas/name"}
+            }
+        });
+        let resolver = Arc::new(InMemoryResolver::new());
+        let compiled = JSONSchema::options()
+            .with_resolver(Arc::clone(&resolver))
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!({"foo": "a", "bar": "b"})));
+        assert!(!compiled.is_valid(&json!({"foo": 1, "bar": 2})));
+        assert_eq!(resolver.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn custom_resolver_error() {
+        let schema = json!({"$ref": "db://schemas/unknown"});
+        let compiled = JSONSchema::options()
+            .with_resolver(InMemoryResolver::new())
+            .compile(&schema)
+            .unwrap();
+        let instance = json!(42);
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "Failed to resolve db://schemas/unknown: Document not found: db://schemas/unknown"
+        );
+    }
+
+    fn schemas_dir() -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/schemas")
+    }
+
+    #[test_case(&json!({"name": "Alice", "address": {"city": "Berlin", "country": "DE"}}), true)]
+    #[test_case(&json!({"name": "Alice", "address": {"city": 42}}), false)]
+    #[test_case(&json!({"name": "Alice", "address": {"country": "FR"}}), false)]
+    #[test_case(&json!({"address": {}}), false)]
+    fn file_references(instance: &Value, expected: bool) {
+        let path = schemas_dir().join("person.json");
+        let schema: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let compiled = JSONSchema::options()
+            .with_base_path(&path)
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn absolute_file_reference() {
+        let url = Url::from_file_path(schemas_dir().join("common/country.json")).unwrap();
+        let schema = json!({"items": {"$ref": url.as_str()}});
+        let compiled = JSONSchema::options()
+            .should_allow_file_references(true)
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!(["NL"])));
+        assert!(!compiled.is_valid(&json!(["FR"])));
+    }
+
+    #[test]
+    fn file_references_are_disabled_by_default() {
+        let url = Url::from_file_path(schemas_dir().join("common/country.json")).unwrap();
+        let schema = json!({"items": {"$ref": url.as_str()}});
+        let compiled = JSONSchema::compile(&schema).unwrap();
+        let instance = json!(["NL"]);
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0].kind,
+            crate::error::ValidationErrorKind::UnknownReferenceScheme { scheme } if scheme == "file"
+        ));
+    }
+
+    #[test]
+    fn missing_file_reference() {
+        let schema = json!({"$ref": "unknown.json"});
+        let compiled = JSONSchema::options()
+            .with_base_path(schemas_dir().join("person.json"))
+            .compile(&schema)
+            .unwrap();
+        let instance = json!(42);
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0].kind,
+            crate::error::ValidationErrorKind::FileNotFound { .. }
+        ));
+    }
+
+    fn stored_document() -> Value {
+        json!({
+            "$id": "http://localhost:1234/tree.json",
+            "definitions": {
+                "node": {
+                    "$id": "node.json",
+                    "definitions": {"value": {"type": "integer"}}
+                },
+                "leaf": {"$id": "#leaf", "type": "null"}
+            }
+        })
+    }
+
+    #[test]
+    fn stored_document_index() {
+        let schema = json!({});
+        let mut store = AHashMap::new();
+        store.insert(
+            "http://localhost:1234/tree.json".to_string(),
+            Arc::new(stored_document()),
+        );
+        let resolver = Resolver::new(
+            Draft::Draft7,
+            &Url::parse("json-schema:///").unwrap(),
+            Resolved::Borrowed(&schema),
+            store,
+            None,
+            false,
+        )
+        .unwrap();
+        let index = resolver.store_schemas.read();
+        assert_eq!(index.len(), 3);
+        assert_eq!(
+            index
+                .get("http://localhost:1234/node.json")
+                .map(|(_, _, pointer)| pointer.as_str()),
+            Some("/definitions/node")
+        );
+        assert_eq!(
+            index
+                .get("http://localhost:1234/tree.json#leaf")
+                .map(|(_, _, pointer)| pointer.as_str()),
+            Some("/definitions/leaf")
+        );
+    }
+
+    #[test]
+    fn stored_document_is_shared() {
+        let schema = json!({});
+        let document = Arc::new(stored_document());
+        let mut store = AHashMap::new();
+        store.insert(
+            "http://localhost:1234/tree.json".to_string(),
+            Arc::clone(&document),
+        );
+        let resolver = Resolver::new(
+            Draft::Draft7,
+            &Url::parse("json-schema:///").unwrap(),
+            Resolved::Borrowed(&schema),
+            store,
+            None,
+            false,
+        )
+        .unwrap();
+        let url = Url::parse("http://localhost:1234/node.json#/definitions/value").unwrap();
+        let (scope, resolved) = resolver.resolve_fragment(Draft::Draft7, &url).unwrap();
+        assert_eq!(
+            scope,
+            Url::parse("http://localhost:1234/node.json").unwrap()
+        );
+        assert_eq!(*resolved, json!({"type": "integer"}));
+        // The resolved schema points into the stored document instead of copying it
+        match resolved {
+            Resolved::Shared(shared, _) => assert!(Arc::ptr_eq(&shared, &document)),
+            Resolved::Borrowed(_) => panic!("Stored documents are shared"),
+        }
+    }
+
+    #[test_case(&json!({"value": 1, "leaf": null}), true)]
+    #[test_case(&json!({"value": "1"}), false)]
+    #[test_case(&json!({"leaf": 1}), false)]
+    fn stored_document_references(instance: &Value, expected: bool) {
+        let schema = json!({
+            "properties": {
+                "value": {"$ref": "http://localhost:1234/node.json#/definitions/value"},
+                "leaf": {"$ref": "http://localhost:1234/tree.json#leaf"}
+            }
+        });
+        let compiled = JSONSchema::options()
+            .with_document(
+                "http://localhost:1234/tree.json".to_string(),
+                stored_document(),
+            )
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(instance), expected);
+        assert_eq!(compiled.validate(instance).is_ok(), expected);
+    }
 }
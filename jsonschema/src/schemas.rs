@@ -10,6 +10,10 @@ pub enum Draft {
     Draft6,
     /// JSON Schema Draft 7
     Draft7,
+    /// JSON Schema Draft 2019-09
+    Draft201909,
+    /// JSON Schema Draft 2020-12
+    Draft202012,
 }
 
 impl Default for Draft {
@@ -18,58 +22,137 @@ impl Default for Draft {
     }
 }
 
+/// The way a keyword contains subschemas.
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Subschemas {
+    /// A single subschema, e.g. `not`.
+    Single,
+    /// An array of subschemas, e.g. `allOf`.
+    Array,
+    /// A single subschema or an array of them, i.e. `items` before Draft 2020-12.
+    SingleOrArray,
+    /// An object with subschemas as values, e.g. `properties`.
+    /// Values of `dependencies` may be arrays of property names as well.
+    Map,
+}
+
 type CompileFunc =
     fn(&Map<String, Value>, &Value, &CompilationContext) -> Option<keywords::CompilationResult>;
 
 impl Draft {
     pub(crate) fn get_validator(self, keyword: &str) -> Option<CompileFunc> {
         match keyword {
-            "additionalItems" => Some(keywords::additional_items::compile),
+            "$dynamicRef" => match self {
+                Draft::Draft202012 => Some(keywords::ref_::compile_dynamic),
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => None,
+            },
+            "$recursiveRef" => match self {
+                Draft::Draft201909 => Some(keywords::ref_::compile_recursive),
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft202012 => None,
+            },
+            // In older drafts `$ref` overrides all other keywords and is handled separately
+            "$ref" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => Some(keywords::ref_::compile),
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
+            "additionalItems" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => {
+                    Some(keywords::additional_items::compile)
+                }
+                Draft::Draft202012 => None,
+            },
             "additionalProperties" => Some(keywords::additional_properties::compile),
             "allOf" => Some(keywords::all_of::compile),
             "anyOf" => Some(keywords::any_of::compile),
             "const" => match self {
                 Draft::Draft4 => None,
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::const_::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::const_::compile)
+                }
             },
             "contains" => match self {
                 Draft::Draft4 => None,
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::contains::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::contains::compile)
+                }
             },
             "contentMediaType" => match self {
-                Draft::Draft7 | Draft::Draft6 => Some(keywords::content::compile_media_type),
+                Draft::Draft7 | Draft::Draft6 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::content::compile_media_type)
+                }
                 Draft::Draft4 => None,
             },
             "contentEncoding" => match self {
-                Draft::Draft7 | Draft::Draft6 => Some(keywords::content::compile_content_encoding),
+                Draft::Draft7 | Draft::Draft6 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::content::compile_content_encoding)
+                }
                 Draft::Draft4 => None,
             },
-            "dependencies" => Some(keywords::dependencies::compile),
+            "contentSchema" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::content::compile_content_schema)
+                }
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
+            "dependencies" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => {
+                    Some(keywords::dependencies::compile)
+                }
+                Draft::Draft201909 | Draft::Draft202012 => None,
+            },
+            "dependentRequired" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::dependencies::compile_dependent_required)
+                }
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
+            "dependentSchemas" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::dependencies::compile_dependent_schemas)
+                }
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
             "enum" => Some(keywords::enum_::compile),
             "exclusiveMaximum" => match self {
-                Draft::Draft7 | Draft::Draft6 => Some(keywords::exclusive_maximum::compile),
+                Draft::Draft7 | Draft::Draft6 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::exclusive_maximum::compile)
+                }
                 Draft::Draft4 => None,
             },
             "exclusiveMinimum" => match self {
-                Draft::Draft7 | Draft::Draft6 => Some(keywords::exclusive_minimum::compile),
+                Draft::Draft7 | Draft::Draft6 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::exclusive_minimum::compile)
+                }
                 Draft::Draft4 => None,
             },
             "format" => Some(keywords::format::compile),
             "if" => match self {
-                Draft::Draft7 => Some(keywords::if_::compile),
+                Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::if_::compile)
+                }
                 Draft::Draft6 | Draft::Draft4 => None,
             },
-            "items" => Some(keywords::items::compile),
+            "items" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => {
+                    Some(keywords::items::compile)
+                }
+                Draft::Draft202012 => Some(keywords::items::compile_with_prefix),
+            },
             "maximum" => match self {
                 Draft::Draft4 => Some(keywords::legacy::maximum_draft_4::compile),
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::maximum::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::maximum::compile)
+                }
             },
             "maxItems" => Some(keywords::max_items::compile),
             "maxLength" => Some(keywords::max_length::compile),
             "maxProperties" => Some(keywords::max_properties::compile),
             "minimum" => match self {
                 Draft::Draft4 => Some(keywords::legacy::minimum_draft_4::compile),
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::minimum::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::minimum::compile)
+                }
             },
             "minItems" => Some(keywords::min_items::compile),
             "minLength" => Some(keywords::min_length::compile),
@@ -79,26 +162,142 @@ impl Draft {
             "oneOf" => Some(keywords::one_of::compile),
             "pattern" => Some(keywords::pattern::compile),
             "patternProperties" => Some(keywords::pattern_properties::compile),
+            "prefixItems" => match self {
+                Draft::Draft202012 => Some(keywords::prefix_items::compile),
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => None,
+            },
             "properties" => Some(keywords::properties::compile),
             "propertyNames" => match self {
                 Draft::Draft4 => None,
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::property_names::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::property_names::compile)
+                }
             },
             "required" => Some(keywords::required::compile),
             "type" => match self {
                 Draft::Draft4 => Some(keywords::legacy::type_draft_4::compile),
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::type_::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::type_::compile)
+                }
+            },
+            "unevaluatedItems" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::unevaluated::compile_items)
+                }
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
+            "unevaluatedProperties" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::unevaluated::compile_properties)
+                }
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
             },
             "uniqueItems" => Some(keywords::unique_items::compile),
             _ => None,
         }
     }
+
+    /// URL of the meta-schema, as it is stored in the embedded meta-schemas.
+    #[inline]
+    pub(crate) const fn meta_schema_url(self) -> &'static str {
+        match self {
+            Draft::Draft4 => "http://json-schema.org/draft-04/schema",
+            Draft::Draft6 => "http://json-schema.org/draft-06/schema",
+            Draft::Draft7 => "http://json-schema.org/draft-07/schema",
+            Draft::Draft201909 => "https://json-schema.org/draft/2019-09/schema",
+            Draft::Draft202012 => "https://json-schema.org/draft/2020-12/schema",
+        }
+    }
+
+    /// Whether applicators mark evaluated properties and items for `unevaluatedProperties`
+    /// and `unevaluatedItems`. Therefore, no-op subschemas like `true` are not skipped.
+    #[inline]
+    pub(crate) const fn collects_annotations(self) -> bool {
+        match self {
+            Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => false,
+            Draft::Draft201909 | Draft::Draft202012 => true,
+        }
+    }
+
+    /// The way `keyword` contains subschemas in this draft, if it does.
+    #[cfg(feature = "reqwest")]
+    pub(crate) fn subschemas(self, keyword: &str) -> Option<Subschemas> {
+        match keyword {
+            "additionalProperties" | "not" => Some(Subschemas::Single),
+            "additionalItems" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => {
+                    Some(Subschemas::Single)
+                }
+                Draft::Draft202012 => None,
+            },
+            "contains" | "propertyNames" => match self {
+                Draft::Draft4 => None,
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(Subschemas::Single)
+                }
+            },
+            "if" | "then" | "else" => match self {
+                Draft::Draft4 | Draft::Draft6 => None,
+                Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => Some(Subschemas::Single),
+            },
+            "contentSchema" | "unevaluatedItems" | "unevaluatedProperties" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+                Draft::Draft201909 | Draft::Draft202012 => Some(Subschemas::Single),
+            },
+            "items" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => {
+                    Some(Subschemas::SingleOrArray)
+                }
+                Draft::Draft202012 => Some(Subschemas::Single),
+            },
+            "allOf" | "anyOf" | "oneOf" => Some(Subschemas::Array),
+            "prefixItems" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => None,
+                Draft::Draft202012 => Some(Subschemas::Array),
+            },
+            "definitions" | "patternProperties" | "properties" => Some(Subschemas::Map),
+            "dependencies" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => Some(Subschemas::Map),
+                Draft::Draft201909 | Draft::Draft202012 => None,
+            },
+            "$defs" | "dependentSchemas" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+                Draft::Draft201909 | Draft::Draft202012 => Some(Subschemas::Map),
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether `keyword` is a reference to another schema in this draft.
+    #[cfg(feature = "reqwest")]
+    pub(crate) fn is_reference(self, keyword: &str) -> bool {
+        match keyword {
+            "$ref" => true,
+            "$recursiveRef" => self == Draft::Draft201909,
+            "$dynamicRef" => self == Draft::Draft202012,
+            _ => false,
+        }
+    }
+
+    /// Whether `$ref` overrides all other keywords in the same schema object.
+    /// Since Draft 2019-09 `$ref` is a regular keyword that is applied alongside its siblings.
+    #[inline]
+    pub(crate) const fn ref_overrides_siblings(self) -> bool {
+        match self {
+            Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => true,
+            Draft::Draft201909 | Draft::Draft202012 => false,
+        }
+    }
 }
 
 /// Get the `Draft` from a JSON Schema URL.
 #[inline]
 pub(crate) fn draft_from_url(url: &str) -> Option<Draft> {
     match url {
+        "https://json-schema.org/draft/2020-12/schema#"
+        | "https://json-schema.org/draft/2020-12/schema" => Some(Draft::Draft202012),
+        "https://json-schema.org/draft/2019-09/schema#"
+        | "https://json-schema.org/draft/2019-09/schema" => Some(Draft::Draft201909),
         "http://json-schema.org/draft-07/schema#" => Some(Draft::Draft7),
         "http://json-schema.org/draft-06/schema#" => Some(Draft::Draft6),
         "http://json-schema.org/draft-04/schema#" => Some(Draft::Draft4),
@@ -129,12 +328,52 @@ pub(crate) fn id_of(draft: Draft, schema: &Value) -> Option<&str> {
     }
 }
 
+#[inline]
+pub(crate) fn anchor_of(draft: Draft, schema: &Value) -> Option<&str> {
+    match draft {
+        Draft::Draft201909 => schema.get("$anchor").and_then(Value::as_str),
+        // Dynamic anchors are also regular plain name fragments
+        Draft::Draft202012 => schema
+            .get("$anchor")
+            .or_else(|| schema.get("$dynamicAnchor"))
+            .and_then(Value::as_str),
+        Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+    }
+}
+
+#[inline]
+pub(crate) fn dynamic_anchor_of(draft: Draft, schema: &Value) -> Option<&str> {
+    match draft {
+        Draft::Draft202012 => schema.get("$dynamicAnchor").and_then(Value::as_str),
+        Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => None,
+    }
+}
+
+/// Whether `schema` or any of its subschemas may be a target of `$dynamicRef` in Draft 2020-12.
+/// Nested resources are included, so the result may be a false positive, but not a false negative.
+pub(crate) fn has_dynamic_anchors(draft: Draft, schema: &Value) -> bool {
+    fn contains_anchor(schema: &Value) -> bool {
+        match schema {
+            Value::Object(object) => {
+                object.contains_key("$dynamicAnchor") || object.values().any(contains_anchor)
+            }
+            Value::Array(items) => items.iter().any(contains_anchor),
+            _ => false,
+        }
+    }
+    draft == Draft::Draft202012 && contains_anchor(schema)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::{json, Value};
     use test_case::test_case;
 
+    #[test_case(&json!({"$schema": "https://json-schema.org/draft/2020-12/schema#"}), Some(Draft::Draft202012))]
+    #[test_case(&json!({"$schema": "https://json-schema.org/draft/2020-12/schema"}), Some(Draft::Draft202012); "draft 2020-12 without fragment")]
+    #[test_case(&json!({"$schema": "https://json-schema.org/draft/2019-09/schema#"}), Some(Draft::Draft201909))]
+    #[test_case(&json!({"$schema": "https://json-schema.org/draft/2019-09/schema"}), Some(Draft::Draft201909); "draft 2019-09 without fragment")]
     #[test_case(&json!({"$schema": "http://json-schema.org/draft-07/schema#"}), Some(Draft::Draft7))]
     #[test_case(&json!({"$schema": "http://json-schema.org/draft-06/schema#"}), Some(Draft::Draft6))]
     #[test_case(&json!({"$schema": "http://json-schema.org/draft-04/schema#"}), Some(Draft::Draft4))]
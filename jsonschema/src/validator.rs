@@ -1,13 +1,43 @@
-use crate::{compilation::JSONSchema, error::ErrorIterator};
+use crate::{
+    compilation::JSONSchema,
+    error::ErrorIterator,
+    keywords::{BoxedValidator, Validators},
+    output::Annotation,
+    paths::{InstancePath, JSONPointer},
+    primitive_type::{PrimitiveType, PrimitiveTypesBitMap},
+};
+use ahash::AHashSet;
 use serde_json::Value;
 use std::fmt;
 
 pub(crate) trait Validate: Send + Sync + ToString {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a>;
+    fn validate<'a>(
+        &self,
+        schema: &'a JSONSchema,
+        instance: &'a Value,
+        instance_path: &InstancePath,
+    ) -> ErrorIterator<'a>;
     // The same as above, but does not construct ErrorIterator.
     // It is faster for cases when the result is not needed (like anyOf), since errors are
     // not constructed
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool;
+    // Mark properties and items of `instance` that are evaluated by this validator.
+    // Only valid subschemas produce annotations, hence applicators check the validity of their
+    // subschemas first. It is used by `unevaluatedProperties` and `unevaluatedItems`.
+    fn evaluate<'a>(&self, _: &JSONSchema, _: &'a Value, _: &mut Evaluated<'a>) {}
+    // Collect annotations that apply to `instance` and its children and return whether
+    // `instance` is valid. Applicators pass it down to subschemas that apply to the instance,
+    // conditional ones only if they are valid, and derive their own validity from the results,
+    // so every subschema is checked once.
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        _: &InstancePath,
+        _: &mut Annotations,
+    ) -> bool {
+        self.is_valid(schema, instance)
+    }
 }
 
 impl fmt::Debug for dyn Validate + Send + Sync {
@@ -15,3 +45,262 @@ impl fmt::Debug for dyn Validate + Send + Sync {
         f.write_str(&self.to_string())
     }
 }
+
+/// Properties and items of an instance that were evaluated by applicator keywords,
+/// for example `properties` or `items`.
+#[derive(Debug, Default)]
+pub(crate) struct Evaluated<'a> {
+    pub(crate) properties: AHashSet<&'a str>,
+    pub(crate) items: AHashSet<usize>,
+}
+
+impl<'a> Evaluated<'a> {
+    /// Mark all properties of an object as evaluated.
+    #[inline]
+    pub(crate) fn all_properties(&mut self, instance: &'a Value) {
+        if let Value::Object(item) = instance {
+            self.properties.extend(item.keys().map(String::as_str))
+        }
+    }
+
+    /// Mark all items of an array starting from the given index as evaluated.
+    #[inline]
+    pub(crate) fn items_from(&mut self, instance: &'a Value, start: usize) {
+        if let Value::Array(items) = instance {
+            self.items.extend(start..items.len())
+        }
+    }
+
+    /// Mark the first `count` items of an array as evaluated.
+    #[inline]
+    pub(crate) fn items_until(&mut self, instance: &'a Value, count: usize) {
+        if let Value::Array(items) = instance {
+            self.items.extend(0..count.min(items.len()))
+        }
+    }
+}
+
+/// Collect annotations from a subschema if `instance` is valid against it.
+/// Returns whether the instance is valid.
+#[inline]
+pub(crate) fn evaluate_subschema<'a>(
+    validators: &[BoxedValidator],
+    schema: &JSONSchema,
+    instance: &'a Value,
+    evaluated: &mut Evaluated<'a>,
+) -> bool {
+    if validators
+        .iter()
+        .all(|validator| validator.is_valid(schema, instance))
+    {
+        for validator in validators {
+            validator.evaluate(schema, instance, evaluated)
+        }
+        true
+    } else {
+        false
+    }
+}
+
+/// Annotations collected from subschemas that apply to an instance.
+#[derive(Debug, Default)]
+pub(crate) struct Annotations {
+    /// Values of annotation keywords, e.g. `title`.
+    pub(crate) keywords: Vec<Annotation>,
+    /// `default` values of properties that are missing in the instance.
+    pub(crate) defaults: Vec<MissingProperty>,
+    /// Strings that don't match the `type` keyword and may be converted to one of its types.
+    pub(crate) coercions: Vec<Coercion>,
+}
+
+/// A property that is missing in an object and has a `default` value in its subschema.
+#[derive(Debug)]
+pub(crate) struct MissingProperty {
+    /// Location of the object within the instance.
+    pub(crate) instance_path: JSONPointer,
+    pub(crate) name: String,
+    pub(crate) default: Value,
+}
+
+/// A string value that should be converted to one of `types`.
+#[derive(Debug)]
+pub(crate) struct Coercion {
+    pub(crate) instance_path: JSONPointer,
+    pub(crate) types: PrimitiveTypesBitMap,
+}
+
+/// Numbers of collected annotations of each kind.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AnnotationsLength {
+    keywords: usize,
+    defaults: usize,
+    coercions: usize,
+}
+
+impl Annotations {
+    #[inline]
+    pub(crate) fn len(&self) -> AnnotationsLength {
+        AnnotationsLength {
+            keywords: self.keywords.len(),
+            defaults: self.defaults.len(),
+            coercions: self.coercions.len(),
+        }
+    }
+
+    /// Drop annotations collected after `length` was taken.
+    #[inline]
+    pub(crate) fn truncate(&mut self, length: AnnotationsLength) {
+        self.keywords.truncate(length.keywords);
+        self.defaults.truncate(length.defaults);
+        self.coercions.truncate(length.coercions);
+    }
+
+    /// Record `default` values of properties that are missing in `instance`.
+    #[inline]
+    pub(crate) fn missing_properties(
+        &mut self,
+        defaults: &[(String, Value)],
+        instance: &Value,
+        instance_path: &InstancePath,
+    ) {
+        if let Value::Object(item) = instance {
+            for (name, default) in defaults {
+                if !item.contains_key(name) {
+                    self.defaults.push(MissingProperty {
+                        instance_path: instance_path.into(),
+                        name: name.clone(),
+                        default: default.clone(),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Record `instance` if it is a string, but `types` don't allow strings.
+    #[inline]
+    pub(crate) fn coercion(
+        &mut self,
+        types: PrimitiveTypesBitMap,
+        instance: &Value,
+        instance_path: &InstancePath,
+    ) {
+        if instance.is_string() && !types.contains_type(PrimitiveType::String) {
+            self.coercions.push(Coercion {
+                instance_path: instance_path.into(),
+                types,
+            })
+        }
+    }
+}
+
+/// Collect annotations from a subschema that always applies to `instance`.
+/// Annotation keywords are dropped if `instance` is not valid against the subschema, while
+/// defaults and coercions are kept, because they are used to fix invalid instances.
+/// Returns whether the instance is valid.
+#[inline]
+pub(crate) fn annotate_subschema(
+    validators: &[BoxedValidator],
+    schema: &JSONSchema,
+    instance: &Value,
+    instance_path: &InstancePath,
+    annotations: &mut Annotations,
+) -> bool {
+    let start = annotations.keywords.len();
+    let mut valid = true;
+    for validator in validators {
+        // Every validator is annotated, even after the instance is known to be invalid
+        valid &= validator.annotate(schema, instance, instance_path, annotations);
+    }
+    if !valid {
+        annotations.keywords.truncate(start)
+    }
+    valid
+}
+
+/// Collect annotations from all `subschemas` that `instance` is valid against, or, if there is
+/// no such subschema, coercions from the first one it is valid against after them.
+/// It is used by `anyOf` and `oneOf`, so their branches can be chosen by coerced values.
+/// Returns the number of subschemas the instance is valid against.
+pub(crate) fn annotate_branches(
+    subschemas: &[Validators],
+    schema: &JSONSchema,
+    instance: &Value,
+    instance_path: &InstancePath,
+    annotations: &mut Annotations,
+) -> usize {
+    let mut valid = 0;
+    let mut invalid = Vec::new();
+    for validators in subschemas {
+        let start = annotations.len();
+        if annotate_subschema(validators, schema, instance, instance_path, annotations) {
+            valid += 1;
+        } else {
+            let coercions = annotations.coercions.split_off(start.coercions);
+            annotations.truncate(start);
+            invalid.push((validators, coercions));
+        }
+    }
+    if valid > 0 {
+        return valid;
+    }
+    let depth = JSONPointer::from(instance_path).chunks().len();
+    for (validators, coercions) in invalid {
+        if coercions.is_empty() {
+            continue;
+        }
+        // Coercions are relative to the root instance, while only `instance` is coerced here
+        let relative = coercions
+            .iter()
+            .map(|coercion| Coercion {
+                instance_path: coercion.instance_path.chunks()[depth..].to_vec().into(),
+                types: coercion.types,
+            })
+            .collect::<Vec<_>>();
+        let mut coerced = instance.clone();
+        coerce(&mut coerced, relative.iter());
+        if validators
+            .iter()
+            .all(|validator| validator.is_valid(schema, &coerced))
+        {
+            annotations.coercions.extend(coercions);
+            break;
+        }
+    }
+    valid
+}
+
+/// Convert strings in `instance` to the first type of their coercions that they can be
+/// converted to.
+pub(crate) fn coerce<'a>(instance: &mut Value, coercions: impl Iterator<Item = &'a Coercion>) {
+    for coercion in coercions {
+        if let Some(value) = instance.pointer_mut(&coercion.instance_path.to_string()) {
+            if let Value::String(string) = value {
+                if let Some(coerced) = coercion
+                    .types
+                    .into_iter()
+                    .find_map(|primitive_type| primitive_type.coerce(string))
+                {
+                    *value = coerced;
+                }
+            }
+        }
+    }
+}
+
+/// Collect annotations from a subschema if `instance` is valid against it.
+/// Returns whether the instance is valid.
+#[inline]
+pub(crate) fn annotate_if_valid(
+    validators: &[BoxedValidator],
+    schema: &JSONSchema,
+    instance: &Value,
+    instance_path: &InstancePath,
+    annotations: &mut Annotations,
+) -> bool {
+    let start = annotations.len();
+    let valid = annotate_subschema(validators, schema, instance, instance_path, annotations);
+    if !valid {
+        annotations.truncate(start)
+    }
+    valid
+}